@@ -1,375 +1,413 @@
-use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
-use quote::{ToTokens, quote, quote_spanned};
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, format_ident, quote, quote_spanned};
 use syn::{
-  DeriveInput, GenericParam, Ident, Lifetime, Token, Type,
-  parse::{Parse, ParseStream, Parser},
-  parse_macro_input, parse_quote,
-  punctuated::Punctuated,
+  Attribute, DeriveInput, Field, Fields, Ident, Index, Meta, Path, Token, WherePredicate,
+  parenthesized, parse::Parse, parse_macro_input, parse_quote, parse_quote_spanned,
   spanned::Spanned,
-  visit::Visit,
 };
 
-type MetaPunct = Punctuated<Metadata, Token![,]>;
-fn find_meta_kv(meta: &MetaPunct, key: &str) -> syn::Result<Option<(Span, TokenTree)>> {
-  meta
-    .iter()
-    .find_map(|attr| {
-      if attr.key() == key {
-        return if let Metadata::KeyValue { span, value, .. } = attr {
-          Some(Ok((span.clone(), value.clone())))
-        } else {
-          Some(Err(syn::Error::new(
-            attr.key().span(),
-            "expected key value pair",
-          )))
-        };
-      }
-      None
-    })
-    .transpose()
-}
-fn find_meta_list(
-  meta: &MetaPunct,
-  key: &str,
-) -> syn::Result<Option<(Span, Punctuated<TokenTree, Token![,]>)>> {
-  meta
-    .iter()
-    .find_map(|attr| {
-      if attr.key() == key {
-        return if let Metadata::List { span, values, .. } = attr {
-          Some(Ok((span.clone(), values.clone())))
-        } else {
-          Some(Err(syn::Error::new(attr.key().span(), "expected list")))
-        };
-      }
-      None
-    })
-    .transpose()
-}
-
-fn find_crate_name(meta: &MetaPunct) -> syn::Result<TokenStream> {
-  Ok(
-    find_meta_kv(meta, "crate")?
-      .map(|(_, value)| value.into_token_stream())
-      .unwrap_or_else(|| match proc_macro_crate::crate_name("aubystd").unwrap() {
-        proc_macro_crate::FoundCrate::Itself => quote!(crate),
-        proc_macro_crate::FoundCrate::Name(name) => {
-          let name = Ident::new(&name, Span::call_site());
-          quote!(::#name)
-        }
-      }),
-  )
-}
-
-enum Metadata {
-  KeyValue {
-    span: Span,
-    key: Ident,
-    value: TokenTree,
-  },
-  List {
-    span: Span,
-    key: Ident,
-    values: Punctuated<TokenTree, Token![,]>,
-  },
-}
-impl Metadata {
-  pub fn key(&self) -> &Ident {
-    match self {
-      Metadata::KeyValue { key, .. } => key,
-      Metadata::List { key, .. } => key,
+fn default_crate_name() -> TokenStream {
+  match proc_macro_crate::crate_name("aubystd").unwrap() {
+    proc_macro_crate::FoundCrate::Itself => quote!(crate),
+    proc_macro_crate::FoundCrate::Name(name) => {
+      let name = Ident::new(&name, Span::call_site());
+      quote!(::#name)
     }
   }
 }
-impl Parse for Metadata {
-  fn parse(input: ParseStream) -> syn::Result<Self> {
-    let key: Ident = input.parse()?;
-    if input.peek(Token![=]) {
-      let _: Token![=] = input.parse()?;
-      let value: TokenTree = input.parse()?;
-      let span = Span::join(&key.span(), value.span()).unwrap();
-      Ok(Metadata::KeyValue { span, key, value })
-    } else if let Ok(list) = input.step(|c| {
-      let x = c
-        .group(Delimiter::Parenthesis)
-        .ok_or(syn::Error::new(Span::call_site(), "parens"))?;
-      Ok((x.0.token_stream(), x.2))
-    }) {
-      let values = Punctuated::parse_terminated.parse2(list)?;
-      let span = Span::join(&key.span(), values.span()).unwrap();
-      Ok(Metadata::List { span, key, values })
-    } else {
-      Err(syn::Error::new(
-        input.span(),
-        format!("invalid attribute argument \"{key}\""),
-      ))
-    }
+
+/// Derives `SliceDst` for a `repr(C)` or `repr(transparent)` struct, whose last field is a slice
+/// DST.
+///
+/// The header is generated as `<Name>Header`, which has every other field followed by the header of
+/// the last field. It's configured with `#[slice_dst(...)]`:
+/// - `header = Name` renames the header
+/// - `derive(...)` derives traits for the header
+/// - `attr(...)` adds attributes to the header
+/// - `crate = path` sets the path to aubystd
+///
+/// The offsets of the fields are checked against the header at compile time, whenever the struct's
+/// `SliceDst` implementation is used.
+#[proc_macro_derive(SliceDst, attributes(slice_dst))]
+pub fn slice_dst(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  struct Options {
+    header: Option<Ident>,
+    derives: Vec<Path>,
+    attrs: Vec<Meta>,
+    crate_name: Option<Path>,
   }
-}
 
-#[proc_macro_attribute]
-pub fn slice_dst(
-  attr: proc_macro::TokenStream,
-  item: proc_macro::TokenStream,
-) -> proc_macro::TokenStream {
-  fn slice_dst_derive(
-    attr: TokenStream,
-    item: TokenStream,
-    DeriveInput {
-      attrs,
-      vis,
-      ident,
-      mut generics,
-      data,
-    }: DeriveInput,
-  ) -> syn::Result<TokenStream> {
-    let meta: Punctuated<Metadata, Token![,]> = if attr.is_empty() {
-      Punctuated::new()
-    } else {
-      Punctuated::parse_separated_nonempty.parse2(attr)?
+  fn parse_options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options {
+      header: None,
+      derives: vec![],
+      attrs: vec![],
+      crate_name: None,
     };
-    let _: () = meta
+
+    for attr in attrs
       .iter()
-      .filter_map(|x| {
-        ["header", "derive", "crate"]
-          .iter()
-          .all(|key| *x.key() != *key)
-          .then(|| syn::Error::new(x.key().span(), format!("invalid argument: {}", x.key())))
-      })
-      .reduce(|mut left, right| {
-        left.combine(right);
-        left
-      })
-      .map(|err| Err(err))
-      .transpose()?
-      .unwrap_or_default();
-
-    let crate_name = find_crate_name(&meta)?;
-
-    let attrs: Vec<_> = attrs
-      .into_iter()
-      .filter(|attr| !attr.path().is_ident("derive"))
-      .collect();
-    let derive = find_meta_list(&meta, "derive")?.unwrap_or((Span::call_site(), parse_quote!()));
+      .filter(|attr| attr.path().is_ident("slice_dst"))
+    {
+      attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("header") {
+          options.header = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("derive") {
+          meta.parse_nested_meta(|derive| {
+            options.derives.push(derive.path);
+            Ok(())
+          })?;
+        } else if meta.path.is_ident("attr") {
+          let content;
+          parenthesized!(content in meta.input);
+          options
+            .attrs
+            .extend(content.parse_terminated(Meta::parse, Token![,])?);
+        } else if meta.path.is_ident("crate") {
+          options.crate_name = Some(meta.value()?.parse()?);
+        } else {
+          return Err(meta.error(
+            "unsupported slice_dst argument, expected `header`, `derive`, `attr` or `crate`",
+          ));
+        }
+        Ok(())
+      })?;
+    }
 
-    let syn::Data::Struct(data_struct) = data else {
-      return Err(syn::Error::new(ident.span(), "only structs are supported"));
-    };
+    Ok(options)
+  }
 
-    let reprs = attrs
+  /// Checks that the struct is `repr(C)` or `repr(transparent)`, and returns its repr attributes.
+  fn check_repr(attrs: &[Attribute], ident: &Ident) -> syn::Result<Vec<Attribute>> {
+    let reprs: Vec<_> = attrs
       .iter()
-      .filter_map(|attr| {
-        if !attr.path().is_ident("repr") {
-          return None;
+      .filter(|attr| attr.path().is_ident("repr"))
+      .cloned()
+      .collect();
+
+    let mut stable = false;
+    for repr in &reprs {
+      repr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("C") || meta.path.is_ident("transparent") {
+          stable = true;
+          Ok(())
+        } else if meta.path.is_ident("packed") || meta.path.is_ident("align") {
+          Err(meta.error(
+            "slice DSTs can't be packed or aligned, since the header is laid out without it",
+          ))
+        } else {
+          Err(meta.error("unsupported repr, slice DSTs must be `repr(C)` or `repr(transparent)`"))
         }
+      })?;
+    }
 
-        let mut repr = None;
-        let result = attr
-          .parse_nested_meta(|meta| {
-            if ["C", "transparent", "align", "packed"]
-              .iter()
-              .any(|ident| meta.path.is_ident(ident))
-            {
-              repr = Some(meta.path);
-              Ok(())
-            } else {
-              Err(syn::Error::new(meta.path.span(), "unsupported repr"))
-            }
-          })
-          .map(|_| repr.unwrap());
-        Some(result)
-      })
-      .collect::<syn::Result<Vec<_>>>()?;
-
-    if reprs.len() == 0
-      || reprs
-        .iter()
-        .all(|path| !path.is_ident("C") && !path.is_ident("transparent"))
-    {
+    if !stable {
       return Err(syn::Error::new(
         ident.span(),
-        "struct must be either repr(C) or repr(transparent)",
+        "slice DSTs must be `repr(C)` or `repr(transparent)`",
       ));
-    };
-
-    enum FieldsKind {
-      Named,
-      Unnamed,
     }
 
-    let (last, last_index, fields, fields_kind) = match data_struct.fields {
-      syn::Fields::Named(mut fields_named) => (
-        fields_named.named.pop().map(|last| last.into_value()),
-        fields_named.named.len(),
-        fields_named.named.into_iter(),
-        FieldsKind::Named,
-      ),
-      syn::Fields::Unnamed(mut fields_unnamed) => (
-        fields_unnamed.unnamed.pop().map(|last| last.into_value()),
-        fields_unnamed.unnamed.len(),
-        fields_unnamed.unnamed.into_iter(),
-        FieldsKind::Unnamed,
-      ),
-      syn::Fields::Unit => {
+    Ok(reprs)
+  }
+
+  fn slice_dst_derive(
+    DeriveInput {
+      attrs,
+      vis,
+      ident,
+      generics,
+      data,
+    }: DeriveInput,
+  ) -> syn::Result<TokenStream> {
+    let data_struct = match data {
+      syn::Data::Struct(data_struct) => data_struct,
+      syn::Data::Enum(data_enum) => {
         return Err(syn::Error::new(
-          ident.span(),
-          "unit structs cannot be slice DSTs",
+          data_enum.enum_token.span(),
+          "enums can't be slice DSTs, only structs can have an unsized field",
+        ));
+      }
+      syn::Data::Union(data_union) => {
+        return Err(syn::Error::new(
+          data_union.union_token.span(),
+          "unions can't be slice DSTs, only structs can have an unsized field",
         ));
       }
     };
-    // let fields = fields.map(|mut field| {
-    //   field.vis = Visibility::Public(Pub(Span::call_site()));
-    //   field
-    // });
 
-    let Some(last) = last else {
+    let options = parse_options(&attrs)?;
+    let reprs = check_repr(&attrs, &ident)?;
+    let crate_name = options
+      .crate_name
+      .map(ToTokens::into_token_stream)
+      .unwrap_or_else(default_crate_name);
+    let slice_dst = quote!(#crate_name::alloc::SliceDst);
+
+    let fields = data_struct.fields;
+    let Some(tail) = fields.iter().next_back() else {
+      let span = match &fields {
+        Fields::Unit => ident.span(),
+        _ => fields.span(),
+      };
       return Err(syn::Error::new(
-        ident.span(),
-        "struct must contain at least one unsized field",
+        span,
+        "slice DSTs must have a slice DST as their last field",
       ));
     };
+    let tail_index = fields.len() - 1;
+    let header_fields: Vec<_> = fields.iter().take(tail_index).collect();
 
-    let last_ty = last.ty;
+    let member = |index: usize, field: &Field| match &field.ident {
+      Some(ident) => ident.to_token_stream(),
+      None => Index::from(index).to_token_stream(),
+    };
+    let header_members: Vec<_> = header_fields
+      .iter()
+      .enumerate()
+      .map(|(index, field)| member(index, field))
+      .collect();
+    let tail_member = member(tail_index, tail);
+    let tail_header_member = match &tail.ident {
+      Some(tail_ident) => format_ident!("{tail_ident}_header").into_token_stream(),
+      None => tail_member.clone(),
+    };
 
-    let header_name = match fields.len() {
-      1.. => {
-        // Ident::new(&format!("{ident}Header"), Span::call_site()).to_token_stream()
+    let tail_ty = &tail.ty;
+    let tail_vis = &tail.vis;
+    // the bounds are higher-ranked so that a last field that isn't a slice DST doesn't fail them
+    // wherever they're declared, only in the check on the last field
+    let tail_bound: WherePredicate =
+      parse_quote_spanned!(tail_ty.span()=> for<'__slice_dst> #tail_ty: #slice_dst);
+    let tail_header_ty = quote!(<#tail_ty as #slice_dst>::Header);
 
-        let (_, header) = find_meta_kv(&meta, "header")?.ok_or(syn::Error::new(
-          Span::call_site(),
-          "must have a header name",
-        ))?;
-        header.to_token_stream()
-      }
-      // 1 => fields.clone().next().unwrap().ty.to_token_stream(),
-      0 => quote!(()),
+    let mut header_generics = generics.clone();
+    header_generics
+      .make_where_clause()
+      .predicates
+      .push(tail_bound.clone());
+
+    let header_name = options
+      .header
+      .unwrap_or_else(|| format_ident!("{ident}Header"));
+    let (_, header_ty_generics, header_where) = header_generics.split_for_impl();
+    let header_ty = quote!(#header_name #header_ty_generics);
+
+    let derives = &options.derives;
+    let header_attrs = &options.attrs;
+    let header_struct = match &fields {
+      Fields::Named(_) => quote! {
+        #[doc(hidden)]
+        #[derive(#(#derives),*)]
+        #(#[#header_attrs])*
+        #(#reprs)*
+        #vis struct #header_name #header_generics #header_where {
+          #(#header_fields,)*
+          #tail_vis #tail_header_member: #tail_header_ty,
+        }
+      },
+      _ => quote! {
+        #[doc(hidden)]
+        #[derive(#(#derives),*)]
+        #(#[#header_attrs])*
+        #(#reprs)*
+        #vis struct #header_name #header_generics (
+          #(#header_fields,)*
+          #tail_vis #tail_header_ty,
+        ) #header_where;
+      },
     };
 
-    let header_assoc_generics = (generics.params.len() > 0).then_some({
-      let params = generics.params.iter().map(|param| match param {
-        syn::GenericParam::Lifetime(lifetime_param) => lifetime_param.lifetime.to_token_stream(),
-        syn::GenericParam::Type(type_param) => type_param.ident.to_token_stream(),
-        syn::GenericParam::Const(const_param) => const_param.ident.to_token_stream(),
-      });
-      quote!(<#(#params),*>)
-    });
+    let (_, struct_ty_generics, _) = generics.split_for_impl();
+    let metadata_bound: WherePredicate = parse_quote!(
+      for<'__slice_dst> #ident #struct_ty_generics: #crate_name::internal::SliceDstMetadata
+    );
+
+    let mut impl_generics = generics.clone();
+    let predicates = &mut impl_generics.make_where_clause().predicates;
+    predicates.push(tail_bound);
+    predicates.push(metadata_bound);
+    let (impl_generics, ty_generics, where_clause) = impl_generics.split_for_impl();
+
+    let layout_assertions = quote! {
+      let probe = #crate_name::internal::SliceDstProbe::<Self>::new();
+      let ptr = probe.as_ptr();
+      #(
+        ::core::assert!(
+          ::core::mem::offset_of!(Self, #header_members)
+            == ::core::mem::offset_of!(#header_ty, #header_members),
+          ::core::concat!(
+            "`", ::core::stringify!(#header_members), "` isn't at the same offset in the header",
+          ),
+        );
+      )*
 
-    let last_ident = match fields_kind {
-      FieldsKind::Named => last.ident.to_token_stream(),
-      FieldsKind::Unnamed => last_index.to_token_stream(),
+      // Safety: the probe has room for a zero length Self
+      let tail = unsafe { probe.offset_of(&raw const (*ptr).#tail_member) };
+      ::core::assert!(
+        ::core::mem::size_of::<#tail_header_ty>() == 0
+          || tail == ::core::mem::offset_of!(#header_ty, #tail_header_member),
+        "the header of the last field isn't at the same offset in the header",
+      );
+      ::core::assert!(
+        tail + #crate_name::internal::SliceDstProbe::<#tail_ty>::ELEMENTS_OFFSET
+          == #crate_name::internal::SliceDstProbe::<Self>::ELEMENTS_OFFSET,
+        "the header is padded past the start of the elements, reorder the fields to avoid padding",
+      );
     };
 
-    let addr_of_slice = if fields.len() < 1 {
+    // structs without generics are checked even when unused, starting with whether the last field
+    // is a slice DST, which is the only error when it isn't
+    let eager_assertion = generics.params.is_empty().then(|| {
+      let tail_offset = quote_spanned!(tail_ty.span()=> <#tail_ty as LastField>::TAIL_OFFSET);
       quote! {
-        unsafe { &raw mut (*ptr).#last_ident }
-      }
-    } else {
-      quote! {
-        <#last_ty as #crate_name::alloc::SliceDst>::addr_of_slice(unsafe { &raw mut (*ptr).#last_ident })
-      }
-    };
+        const _: usize = {
+          #[diagnostic::on_unimplemented(
+            message = "the last field of a slice DST must be a slice DST",
+            label = "`{Self}` isn't a slice DST",
+          )]
+          trait LastField {
+            const TAIL_OFFSET: usize;
+          }
+
+          #[diagnostic::do_not_recommend]
+          impl LastField for #tail_ty #where_clause {
+            const TAIL_OFFSET: usize = #ident::__AUBYSTD_SLICE_DST_TAIL_OFFSET;
+          }
 
-    let header = if fields.len() < 1 {
-      quote!()
-    } else {
-      struct UnusedGenerics {
-        used_params: Vec<Ident>,
-        used_lifetimes: Vec<Lifetime>,
+          #tail_offset
+        };
       }
+    });
 
-      impl<'ast> Visit<'ast> for UnusedGenerics {
-        fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
-          self.used_lifetimes.push(lifetime.clone());
-        }
-        fn visit_ident(&mut self, i: &'ast proc_macro2::Ident) {
-          self.used_params.push(i.clone());
+    Ok(quote! {
+      #header_struct
+
+      unsafe impl #impl_generics #slice_dst for #ident #ty_generics #where_clause {
+        type Header = #header_ty;
+        type Element = <#tail_ty as #slice_dst>::Element;
+
+        fn addr_of_slice(ptr: *mut Self) -> *mut [Self::Element] {
+          <#tail_ty as #slice_dst>::addr_of_slice(#crate_name::internal::slice_dst_field(
+            ptr,
+            Self::__AUBYSTD_SLICE_DST_TAIL_OFFSET,
+          ))
         }
       }
 
-      let fields_unused = fields.clone();
-      let mut unused_generics = UnusedGenerics {
-        used_lifetimes: vec![],
-        used_params: vec![],
-      };
-      // return Err(syn::Error::new(Span::call_site(), format!("fuck you {last_ty:?}")));
-      unused_generics.visit_type(&last_ty);
-      if !matches!(last_ty, Type::Slice(_)) && unused_generics.used_params.len() == 1 {
-        for param_name in &unused_generics.used_params {
-          if let Some(param) = generics
-            .type_params_mut()
-            .find(|param| param.ident == *param_name)
-          {
-            param
-              .bounds
-              .push(parse_quote!(#crate_name::alloc::SliceDst));
-          }
-        }
-      };
-      for ele in fields_unused {
-        unused_generics.visit_field(&ele);
+      impl #impl_generics #ident #ty_generics #where_clause {
+        /// The offset of the last field, once the layout has been checked.
+        #[doc(hidden)]
+        const __AUBYSTD_SLICE_DST_TAIL_OFFSET: usize = {
+          #layout_assertions
+          tail
+        };
       }
 
-      generics.params = generics
-        .params
-        .into_iter()
-        .filter(|param| match param {
-          GenericParam::Lifetime(lifetime) => {
-            unused_generics.used_lifetimes.contains(&lifetime.lifetime)
-          }
-          GenericParam::Const(const_param) => {
-            unused_generics.used_params.contains(&const_param.ident)
-          }
-          GenericParam::Type(type_param) => unused_generics.used_params.contains(&type_param.ident),
-        })
-        .collect();
-
-      let (derive_span, derives) = derive;
-      let derives = derives.iter();
-      let derive = quote_spanned! {derive_span=>#[derive(#(#derives),*)]};
-      match fields_kind {
-        FieldsKind::Named => {
-          let last_ident_header = Ident::new(&format!("{last_ident}_header"), Span::call_site());
-          quote! {
-            #[doc(hidden)]
-            #derive
-            #(#attrs)*
-            #vis struct #header_name #generics {
-              #(#fields,)*
-              pub #last_ident_header: <#last_ty as #crate_name::alloc::SliceDst>::Header
-            }
-          }
-        }
-        FieldsKind::Unnamed => {
-          quote! {
-            #[doc(hidden)]
-            #derive
-            #(#attrs)*
-            #vis struct #header_name #generics (#(#fields,)* <#last_ty as #crate_name::alloc::SliceDst>::Header);
-          }
+      #eager_assertion
+    })
+  }
+
+  let input = parse_macro_input!(item as DeriveInput);
+  match slice_dst_derive(input) {
+    Ok(output) => output.into(),
+    Err(error) => error.into_compile_error().into(),
+  }
+}
+
+/// Derives `TryCloneIn` by cloning every field in the same allocator.
+#[proc_macro_derive(TryCloneIn)]
+pub fn try_clone_in(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  fn clone_fields(path: TokenStream, fields: &Fields, trait_path: &TokenStream) -> TokenStream {
+    // the bindings are hygienic, so a field named like the allocator parameter can't shadow it
+    let bindings: Vec<_> = (0..fields.len())
+      .map(|index| Ident::new(&format!("__aubystd_field_{index}"), Span::mixed_site()))
+      .collect();
+    let clones = fields.iter().zip(&bindings).map(|(field, binding)| {
+      let ty = &field.ty;
+      quote!(<#ty as #trait_path>::try_clone_in(#binding, allocator).await?)
+    });
+
+    match fields {
+      Fields::Named(_) => {
+        let names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+        quote! {
+          #path { #(#names: #bindings),* } => #path { #(#names: #clones),* }
         }
       }
-    };
+      Fields::Unnamed(_) => quote! {
+        #path ( #(#bindings),* ) => #path ( #(#clones),* )
+      },
+      Fields::Unit => quote!(#path => #path),
+    }
+  }
+
+  fn try_clone_in_derive(
+    DeriveInput {
+      ident,
+      generics,
+      data,
+      ..
+    }: DeriveInput,
+  ) -> syn::Result<TokenStream> {
+    let crate_name = default_crate_name();
+    let trait_path = quote!(#crate_name::alloc::TryCloneIn<'__aubystd_a, __AubystdA>);
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (arms, fields): (Vec<_>, Vec<_>) = match &data {
+      syn::Data::Struct(data_struct) => (
+        vec![clone_fields(quote!(Self), &data_struct.fields, &trait_path)],
+        data_struct.fields.iter().collect(),
+      ),
+      syn::Data::Enum(data_enum) => (
+        data_enum
+          .variants
+          .iter()
+          .map(|variant| {
+            let variant_ident = &variant.ident;
+            clone_fields(quote!(Self::#variant_ident), &variant.fields, &trait_path)
+          })
+          .collect(),
+        data_enum
+          .variants
+          .iter()
+          .flat_map(|variant| &variant.fields)
+          .collect(),
+      ),
+      syn::Data::Union(_) => {
+        return Err(syn::Error::new(ident.span(), "unions are not supported"));
+      }
+    };
 
-    Ok(quote! {
-      #item
-      #header
+    let mut impl_generics = generics.clone();
+    impl_generics.params.insert(0, parse_quote!('__aubystd_a));
+    impl_generics.params.push(parse_quote!(
+      __AubystdA: #crate_name::alloc::LayoutAllocator + ?Sized + '__aubystd_a
+    ));
+    let where_clause = impl_generics.make_where_clause();
+    for field in fields {
+      let ty = &field.ty;
+      where_clause.predicates.push(parse_quote!(#ty: #trait_path));
+    }
 
-      unsafe impl #impl_generics #crate_name::alloc::SliceDst for #ident #ty_generics #where_clause {
-        type Header = #header_name #header_assoc_generics;
-        type Element = <#last_ty as #crate_name::alloc::SliceDst>::Element;
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
 
-        fn addr_of_slice(ptr: *mut Self) -> *mut [Self::Element] {
-          #addr_of_slice
+    Ok(quote! {
+      impl #impl_generics #trait_path for #ident #ty_generics #where_clause {
+        async fn try_clone_in(
+          &self,
+          allocator: &'__aubystd_a __AubystdA,
+        ) -> ::core::result::Result<Self, <__AubystdA as #crate_name::alloc::LayoutAllocator>::Error> {
+          ::core::result::Result::Ok(match self {
+            #(#arms,)*
+          })
         }
       }
     })
   }
 
-  let input_item = item.clone();
-  let input = parse_macro_input!(input_item as DeriveInput);
-  match slice_dst_derive(attr.into(), item.into(), input) {
+  let input = parse_macro_input!(item as DeriveInput);
+  match try_clone_in_derive(input) {
     Ok(output) => output.into(),
     Err(error) => error.into_compile_error().into(),
   }
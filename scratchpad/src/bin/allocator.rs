@@ -4,7 +4,6 @@ use aubystd::{
   alloc::{
     Allocator, SliceAllocator, SliceDst, UnsizedMaybeUninit,
     allocator::{ArenaAllocator, ForeignAllocator, Malloc},
-    slice_dst,
     strategy::{Rc, RcStrategy, Unique, UniqueStrategy},
   },
   prelude::UninitStrategyHandleExt,
@@ -20,8 +19,8 @@ use core::{
   task::{Context, Poll},
 };
 
-#[slice_dst(header = AHeader, derive(FromZeros))]
-#[derive(FromZeros)]
+#[derive(SliceDst, FromZeros)]
+#[slice_dst(derive(FromZeros), attr(zerocopy(crate = "aubystd::zerocopy")))]
 #[repr(C)]
 #[zerocopy(crate = "aubystd::zerocopy")]
 struct A<T: Debug + ?Sized> {
@@ -36,7 +35,7 @@ impl<T: Debug + ?Sized> Display for A<T> {
   }
 }
 
-#[slice_dst(header = BHeader)]
+#[derive(SliceDst)]
 #[repr(C)]
 struct B<A: Debug> {
   #[allow(unused)]
@@ -46,7 +45,7 @@ struct B<A: Debug> {
   last: A,
 }
 
-#[slice_dst(header = CHeader)]
+#[derive(SliceDst)]
 #[repr(C)]
 struct C<A: Debug> {
   #[allow(unused)]
@@ -58,7 +57,7 @@ struct C<A: Debug> {
   last: [u32],
 }
 
-#[slice_dst(header = DHeader)]
+#[derive(SliceDst)]
 #[repr(C)]
 struct D {
   #[allow(unused)]
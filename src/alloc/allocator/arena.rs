@@ -7,8 +7,8 @@ use core::{
 };
 
 use crate::alloc::{
-  FreeVtable, LayoutAllocator, OutOfMemory, SliceAllocator, SliceDst, UnsizedMaybeUninit,
-  strategy::Strategy,
+  FreeVtable, OutOfMemory, SliceDst, SyncAllocator, SyncLayoutAllocator, SyncSliceAllocator,
+  UnsizedMaybeUninit, strategy::Strategy,
 };
 
 use super::calculate_layout_for_dst;
@@ -67,10 +67,10 @@ impl<A: UnsafeCellBuffer> ArenaAllocator<A> {
   }
 }
 
-impl<'s, T: 's, A: UnsafeCellBuffer> Allocator<'s, T> for ArenaAllocator<A> {
+impl<'s, T: 's, A: UnsafeCellBuffer> SyncAllocator<'s, T> for ArenaAllocator<A> {
   type Error = OutOfMemory;
 
-  async fn reserve_item<S: Strategy>(
+  fn reserve_item_sync<S: Strategy>(
     &'s self,
   ) -> Result<S::UninitHandle<'s, MaybeUninit<T>>, OutOfMemory>
   where
@@ -86,11 +86,11 @@ impl<'s, T: 's, A: UnsafeCellBuffer> Allocator<'s, T> for ArenaAllocator<A> {
   }
 }
 
-impl<'s, T: SliceDst + ?Sized + 's, A: UnsafeCellBuffer> SliceAllocator<'s, T>
+impl<'s, T: SliceDst + ?Sized + 's, A: UnsafeCellBuffer> SyncSliceAllocator<'s, T>
   for ArenaAllocator<A>
 {
   type Error = OutOfMemory;
-  async fn reserve_slice<S: Strategy>(
+  fn reserve_slice_sync<S: Strategy>(
     &'s self,
     length: usize,
   ) -> Result<S::UninitHandle<'s, UnsizedMaybeUninit<T>>, OutOfMemory>
@@ -110,10 +110,10 @@ impl<'s, T: SliceDst + ?Sized + 's, A: UnsafeCellBuffer> SliceAllocator<'s, T>
   }
 }
 
-impl<A: UnsafeCellBuffer> LayoutAllocator for ArenaAllocator<A> {
+impl<A: UnsafeCellBuffer> SyncLayoutAllocator for ArenaAllocator<A> {
   type Error = OutOfMemory;
 
-  async fn reserve_layout<'s, S: Strategy>(
+  fn reserve_layout_sync<'s, S: Strategy>(
     &'s self,
     layout: core::alloc::Layout,
   ) -> Result<S::Handle<'s, [MaybeUninit<u8>]>, Self::Error>
@@ -1,7 +1,8 @@
 use core::{alloc::Layout, mem::MaybeUninit, ptr};
 
 use crate::alloc::{
-  FreeVtable, LayoutAllocator, SliceAllocator, UnsizedMaybeUninit, strategy::Strategy,
+  FreeVtable, SyncAllocator, SyncLayoutAllocator, SyncSliceAllocator, UnsizedMaybeUninit,
+  strategy::Strategy,
 };
 
 use super::{OutOfMemory, calculate_layout_for_dst};
@@ -68,10 +69,10 @@ impl<C: CStyleAllocator> ForeignAllocator<C> {
   }
 }
 
-impl<'s, T: 's, C: CStyleAllocator> Allocator<'s, T> for ForeignAllocator<C> {
+impl<'s, T: 's, C: CStyleAllocator> SyncAllocator<'s, T> for ForeignAllocator<C> {
   type Error = OutOfMemory;
 
-  async fn reserve_item<S: Strategy>(
+  fn reserve_item_sync<S: Strategy>(
     &'s self,
   ) -> Result<S::UninitHandle<'s, MaybeUninit<T>>, OutOfMemory>
   where
@@ -89,12 +90,12 @@ impl<'s, T: 's, C: CStyleAllocator> Allocator<'s, T> for ForeignAllocator<C> {
   }
 }
 
-impl<'s, T: SliceDst + ?Sized + 's, C: CStyleAllocator> SliceAllocator<'s, T>
+impl<'s, T: SliceDst + ?Sized + 's, C: CStyleAllocator> SyncSliceAllocator<'s, T>
   for ForeignAllocator<C>
 {
   type Error = OutOfMemory;
 
-  async fn reserve_slice<S: Strategy>(
+  fn reserve_slice_sync<S: Strategy>(
     &'s self,
     length: usize,
   ) -> Result<S::UninitHandle<'s, UnsizedMaybeUninit<T>>, OutOfMemory>
@@ -115,10 +116,10 @@ impl<'s, T: SliceDst + ?Sized + 's, C: CStyleAllocator> SliceAllocator<'s, T>
   }
 }
 
-impl<C: CStyleAllocator> LayoutAllocator for ForeignAllocator<C> {
+impl<C: CStyleAllocator> SyncLayoutAllocator for ForeignAllocator<C> {
   type Error = OutOfMemory;
 
-  async fn reserve_layout<'s, S: Strategy>(
+  fn reserve_layout_sync<'s, S: Strategy>(
     &'s self,
     layout: Layout,
   ) -> Result<S::Handle<'s, [MaybeUninit<u8>]>, Self::Error>
@@ -148,8 +149,11 @@ impl<C: CStyleAllocator> LayoutAllocator for ForeignAllocator<C> {
 #[cfg(feature = "libc")]
 pub mod tests {
   use crate::alloc::{
+    FromBytesError, GrowthStrategy, SliceAllocator, SyncAllocator,
     allocator::{ForeignAllocator, StdAlloc},
     strategy::UniqueStrategy,
+    string::String,
+    vec::Vec,
   };
 
   #[pollster::test]
@@ -163,4 +167,49 @@ pub mod tests {
     let arena = ForeignAllocator::new(StdAlloc);
     let _handle = arena.take::<UniqueStrategy>(5u32).await.unwrap();
   }
+
+  #[test]
+  fn allocate_sync() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let value = allocator.take_sync::<UniqueStrategy>(5u32).unwrap();
+    assert_eq!(*value, 5);
+
+    let mut values: Vec<u32, UniqueStrategy, _> =
+      Vec::new_sync(&allocator, GrowthStrategy::Exponential).unwrap();
+    values.extend_sync(0..10).unwrap();
+    values.push_resize_sync(10).unwrap();
+    assert!(values.iter().copied().eq(0..11));
+
+    let mut text: String<UniqueStrategy, _> =
+      String::with_capacity_sync(&allocator, GrowthStrategy::Exponential, 2).unwrap();
+    text.push_str_sync("hello").unwrap();
+    text.push_sync('!').unwrap();
+    assert_eq!(text.as_str(), "hello!");
+  }
+
+  #[pollster::test]
+  async fn from_bytes() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut values: Vec<u16, UniqueStrategy, _> = Vec::new(&allocator, GrowthStrategy::Exponential)
+      .await
+      .unwrap();
+    values.extend([1, 2, 0x0300]).await.unwrap();
+
+    let copy = SliceAllocator::<[u16]>::from_bytes::<UniqueStrategy>(&allocator, values.as_bytes())
+      .await
+      .unwrap();
+    assert_eq!(&*copy, &[1, 2, 0x0300]);
+
+    let odd = SliceAllocator::<[u16]>::from_bytes::<UniqueStrategy>(&allocator, &[0; 3]).await;
+    assert!(matches!(odd, Err(FromBytesError::Size)));
+
+    let text = SliceAllocator::<str>::try_from_bytes::<UniqueStrategy>(&allocator, b"hello")
+      .await
+      .unwrap();
+    assert_eq!(&*text, "hello");
+
+    let invalid =
+      SliceAllocator::<str>::try_from_bytes::<UniqueStrategy>(&allocator, &[0xff, 0xfe]).await;
+    assert!(matches!(invalid, Err(FromBytesError::Validity)));
+  }
 }
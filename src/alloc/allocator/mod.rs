@@ -15,7 +15,7 @@ pub use arena::*;
 pub use foreign::*;
 
 use thiserror::Error;
-use zerocopy::FromZeros;
+use zerocopy::{ConvertError, FromBytes, FromZeros, Immutable, KnownLayout, TryFromBytes};
 
 use crate::alloc::{UnsizedMaybeUninit, strategy::Strategy};
 
@@ -76,16 +76,73 @@ pub trait SliceAllocator<'s, T: SliceDst + ?Sized + 's> {
     S::Data<'s, UnsizedMaybeUninit<T>>: SliceDst,
   {
     let slice = self.reserve_slice::<S>(length).await?;
-    unsafe {
-      let ptr = S::UninitHandle::as_value_ptr(&slice);
-      ptr.cast::<T::Header>().write_bytes(0, 1);
-      let (ptr, _) = ptr.to_raw_parts();
-      T::addr_of_slice(ptr::from_raw_parts_mut(ptr, length))
-        .cast::<T::Element>()
-        .write_bytes(0, length);
-    };
+    // Safety: the header and elements are zeroed, which is valid for both
+    unsafe { write_zeros::<T, S>(&slice, length) };
     Ok(unsafe { S::UninitHandle::assume_init(slice) })
   }
+
+  /// Copies `bytes` into a new allocation, whose length is inferred from the size of `bytes`.
+  ///
+  /// Fails with [FromBytesError::Size] if no length has exactly that size.
+  // like from_zeros, this takes the allocator rather than converting from it
+  #[allow(clippy::wrong_self_convention)]
+  async fn from_bytes<S: Strategy>(
+    &'s self,
+    bytes: &[u8],
+  ) -> Result<S::Handle<'s, T>, FromBytesError<Self::Error>>
+  where
+    T: FromBytes + KnownLayout + Immutable,
+    S::Data<'s, UnsizedMaybeUninit<T>>: SliceDst,
+  {
+    self.try_from_bytes::<S>(bytes).await
+  }
+
+  /// Copies `bytes` into a new allocation, and checks that they're a valid `T` before returning it.
+  ///
+  /// Fails with [FromBytesError::Size] if no length has exactly the size of `bytes`, and with
+  /// [FromBytesError::Validity] if the bytes aren't a valid `T`.
+  async fn try_from_bytes<S: Strategy>(
+    &'s self,
+    bytes: &[u8],
+  ) -> Result<S::Handle<'s, T>, FromBytesError<Self::Error>>
+  where
+    T: TryFromBytes + KnownLayout + Immutable,
+    S::Data<'s, UnsizedMaybeUninit<T>>: SliceDst,
+  {
+    let length = length_for_size::<T>(bytes.len()).ok_or(FromBytesError::Size)?;
+    let slice = self
+      .reserve_slice::<S>(length)
+      .await
+      .map_err(FromBytesError::Allocator)?;
+
+    unsafe {
+      let ptr = S::UninitHandle::as_value_ptr(&slice).cast::<u8>();
+      // Safety: the allocation is exactly `bytes.len()` bytes long, since it holds `length` elements
+      ptr.copy_from_nonoverlapping(bytes.as_ptr(), bytes.len());
+
+      // Safety: every byte was initialized above, and the allocation is aligned for T
+      let copied = core::slice::from_raw_parts(ptr.cast_const(), bytes.len());
+      match T::try_ref_from_bytes(copied) {
+        Ok(value) if ptr::metadata(value) == length => {}
+        Ok(_) => return Err(FromBytesError::Size),
+        Err(ConvertError::Validity(_)) => return Err(FromBytesError::Validity),
+        Err(_) => return Err(FromBytesError::Size),
+      }
+
+      // Safety: the copied bytes are a valid T
+      Ok(S::UninitHandle::assume_init(slice))
+    }
+  }
+}
+
+#[derive(Debug, Error)]
+pub enum FromBytesError<E: Error> {
+  #[error("{0}")]
+  Allocator(E),
+  #[error("no length has exactly the size of the bytes")]
+  Size,
+  #[error("the bytes aren't a valid value")]
+  Validity,
 }
 
 pub trait LayoutAllocator {
@@ -100,6 +157,160 @@ pub trait LayoutAllocator {
   // S::Data<'s, [MaybeUninit<u8>]>: Pointee<Metadata = usize>;
 }
 
+/// A synchronous [Allocator], for allocators that never have to wait for memory.
+///
+/// Every [SyncAllocator] is also an [Allocator], so it can be used by async code as well.
+pub trait SyncAllocator<'s, T: 's> {
+  type Error: Error;
+
+  /// Allocates an uninitialized handle
+  fn reserve_item_sync<S: Strategy>(
+    &'s self,
+  ) -> Result<S::UninitHandle<'s, MaybeUninit<T>>, Self::Error>
+  where
+    S::Data<'s, MaybeUninit<T>>: Sized;
+
+  fn take_sync<S: Strategy>(&'s self, value: T) -> Result<S::Handle<'s, T>, Self::Error>
+  where
+    S::Data<'s, MaybeUninit<T>>: Sized,
+  {
+    let item = self.reserve_item_sync::<S>()?;
+    unsafe {
+      S::UninitHandle::as_value_ptr(&item)
+        .cast::<T>()
+        .write(value)
+    };
+    // Safety: item was initialized above
+    Ok(unsafe { S::UninitHandle::assume_init(item) })
+  }
+}
+
+impl<'s, T: 's, A: SyncAllocator<'s, T>> Allocator<'s, T> for A {
+  type Error = A::Error;
+
+  async fn reserve_item<S: Strategy>(
+    &'s self,
+  ) -> Result<S::UninitHandle<'s, MaybeUninit<T>>, Self::Error>
+  where
+    S::Data<'s, MaybeUninit<T>>: Sized,
+  {
+    self.reserve_item_sync::<S>()
+  }
+}
+
+/// A synchronous [SliceAllocator], for allocators that never have to wait for memory.
+///
+/// Every [SyncSliceAllocator] is also a [SliceAllocator].
+pub trait SyncSliceAllocator<'s, T: SliceDst + ?Sized + 's> {
+  type Error: Error;
+
+  fn reserve_slice_sync<S: Strategy>(
+    &'s self,
+    length: usize,
+  ) -> Result<S::UninitHandle<'s, UnsizedMaybeUninit<T>>, Self::Error>
+  where
+    S::Data<'s, UnsizedMaybeUninit<T>>: SliceDst;
+
+  // like from_zeros, this takes the allocator rather than converting from it
+  #[allow(clippy::wrong_self_convention)]
+  fn from_zeros_sync<S: Strategy>(&'s self, length: usize) -> Result<S::Handle<'s, T>, Self::Error>
+  where
+    T::Header: FromZeros,
+    T::Element: FromZeros,
+    S::Data<'s, UnsizedMaybeUninit<T>>: SliceDst,
+  {
+    let slice = self.reserve_slice_sync::<S>(length)?;
+    // Safety: the header and elements are zeroed, which is valid for both
+    unsafe { write_zeros::<T, S>(&slice, length) };
+    Ok(unsafe { S::UninitHandle::assume_init(slice) })
+  }
+}
+
+impl<'s, T: SliceDst + ?Sized + 's, A: SyncSliceAllocator<'s, T>> SliceAllocator<'s, T> for A {
+  type Error = A::Error;
+
+  async fn reserve_slice<S: Strategy>(
+    &'s self,
+    length: usize,
+  ) -> Result<S::UninitHandle<'s, UnsizedMaybeUninit<T>>, Self::Error>
+  where
+    S::Data<'s, UnsizedMaybeUninit<T>>: SliceDst,
+  {
+    self.reserve_slice_sync::<S>(length)
+  }
+}
+
+/// A synchronous [LayoutAllocator], for allocators that never have to wait for memory.
+///
+/// Every [SyncLayoutAllocator] is also a [LayoutAllocator].
+pub trait SyncLayoutAllocator {
+  type Error: Error;
+
+  fn reserve_layout_sync<'s, S: Strategy>(
+    &'s self,
+    layout: Layout,
+  ) -> Result<S::Handle<'s, [MaybeUninit<u8>]>, Self::Error>
+  where
+    S::Data<'s, ()>: Sized;
+}
+
+impl<A: SyncLayoutAllocator> LayoutAllocator for A {
+  type Error = A::Error;
+
+  async fn reserve_layout<'s, S: Strategy>(
+    &'s self,
+    layout: Layout,
+  ) -> Result<S::Handle<'s, [MaybeUninit<u8>]>, Self::Error>
+  where
+    S::Data<'s, ()>: Sized,
+  {
+    self.reserve_layout_sync::<S>(layout)
+  }
+}
+
+/// Finds the length of `T` whose layout is exactly `size` bytes, if there is one.
+fn length_for_size<T: SliceDst + ?Sized>(size: usize) -> Option<usize> {
+  // Safety: lengths are at most `size` elements, so the layout can't overflow isize
+  let layout_size = |length| {
+    unsafe { Layout::for_value_raw(ptr::from_raw_parts::<T>(ptr::null::<()>(), length)) }.size()
+  };
+
+  let element = size_of::<T::Element>();
+  if element == 0 {
+    return (layout_size(0) == size).then_some(0);
+  }
+
+  // the largest length that fits, like zerocopy infers it
+  let (mut low, mut high) = (0, size / element);
+  while low < high {
+    let middle = low + (high - low).div_ceil(2);
+    if layout_size(middle) <= size {
+      low = middle;
+    } else {
+      high = middle - 1;
+    }
+  }
+
+  (layout_size(low) == size).then_some(low)
+}
+
+/// Zeroes the header and the first `length` elements of `slice`.
+///
+/// Safety: `slice` must have been reserved for `length` elements
+unsafe fn write_zeros<'s, T: SliceDst + ?Sized + 's, S: Strategy>(
+  slice: &S::UninitHandle<'s, UnsizedMaybeUninit<T>>,
+  length: usize,
+) {
+  unsafe {
+    let ptr = S::UninitHandle::as_value_ptr(slice);
+    ptr.cast::<T::Header>().write_bytes(0, 1);
+    let (ptr, _) = ptr.to_raw_parts();
+    T::addr_of_slice(ptr::from_raw_parts_mut(ptr, length))
+      .cast::<T::Element>()
+      .write_bytes(0, length);
+  };
+}
+
 pub fn calculate_layout_for_dst<T: SliceDst + ?Sized>(
   element_count: usize,
 ) -> Result<Layout, LayoutError> {
@@ -0,0 +1,163 @@
+use core::{clone::CloneToUninit, ptr};
+
+pub use aubystd_macros::TryCloneIn;
+
+use crate::{
+  alloc::{
+    Allocator, LayoutAllocator, SliceAllocator, UnsizedMaybeUninit,
+    strategy::{Strategy, Unique, UniqueStrategy},
+  },
+  types::vec::{BaseVecHeader, SliceVec},
+};
+
+/// Like [Clone], for values that have to allocate from `A` to be cloned, which can fail.
+///
+/// Every [Clone] type implements this without allocating. It can be derived for structs and enums
+/// whose fields all implement it.
+pub trait TryCloneIn<'a, A: LayoutAllocator + ?Sized + 'a>: Sized {
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, A::Error>;
+}
+
+impl<'a, T: Clone, A: LayoutAllocator + ?Sized + 'a> TryCloneIn<'a, A> for T {
+  async fn try_clone_in(&self, _allocator: &'a A) -> Result<Self, A::Error> {
+    Ok(self.clone())
+  }
+}
+
+/// Clones `value` into a new allocation from `allocator`.
+pub(crate) async fn clone_slice_dst_in<
+  'a,
+  T: SliceDst + CloneToUninit + ?Sized + 'a,
+  S: Strategy,
+  A: SliceAllocator<'a, T>,
+>(
+  value: &T,
+  allocator: &'a A,
+) -> Result<S::Handle<'a, T>, A::Error>
+where
+  S::Data<'a, UnsizedMaybeUninit<T>>: SliceDst,
+{
+  let length = ptr::metadata(value);
+  let handle = allocator.reserve_slice::<S>(length).await?;
+
+  unsafe {
+    let (ptr, _) = S::UninitHandle::as_value_ptr(&handle).to_raw_parts();
+    // Safety: the allocation was reserved for the same metadata as value
+    value.clone_to_uninit(ptr.cast());
+    // Safety: initialized by cloning from value
+    Ok(S::UninitHandle::assume_init(handle))
+  }
+}
+
+impl<'a, T: TryCloneIn<'a, A> + 'a, A: 'a> TryCloneIn<'a, A> for Unique<'a, T>
+where
+  A: LayoutAllocator + Allocator<'a, T, Error = <A as LayoutAllocator>::Error>,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let value = (**self).try_clone_in(allocator).await?;
+    allocator.take::<UniqueStrategy>(value).await
+  }
+}
+
+impl<'a, T: Clone + 'a, A: 'a> TryCloneIn<'a, A> for Unique<'a, [T]>
+where
+  A: LayoutAllocator + SliceAllocator<'a, [T], Error = <A as LayoutAllocator>::Error>,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    clone_slice_dst_in::<_, UniqueStrategy, _>(&**self, allocator).await
+  }
+}
+
+impl<'a, A: 'a> TryCloneIn<'a, A> for Unique<'a, str>
+where
+  A: LayoutAllocator + SliceAllocator<'a, str, Error = <A as LayoutAllocator>::Error>,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    clone_slice_dst_in::<_, UniqueStrategy, _>(&**self, allocator).await
+  }
+}
+
+impl<'a, T: TryCloneIn<'a, A> + 'a, A: 'a> TryCloneIn<'a, A> for Unique<'a, SliceVec<T>>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let mut handle = allocator
+      .reserve_slice::<UniqueStrategy>(self.capacity())
+      .await?;
+    handle.header.write(BaseVecHeader::new());
+    // Safety: the header was initialized, and the values are uninitialized while length is 0
+    let mut vec: Self = unsafe { Unique::assume_init(handle) };
+
+    for value in self.iter() {
+      let Ok(_) = vec.push(value.try_clone_in(allocator).await?) else {
+        unreachable!("not enough space for value");
+      };
+    }
+
+    Ok(vec)
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::alloc::{
+    ForeignAllocator, GrowthStrategy, StdAlloc, TryCloneIn, strategy::UniqueStrategy, vec::Vec,
+  };
+
+  #[derive(TryCloneIn)]
+  struct Document<'a, A: crate::alloc::SliceAllocator<'a, crate::types::vec::SliceVec<u32>>> {
+    id: u32,
+    values: Vec<'a, u32, UniqueStrategy, A>,
+  }
+
+  /// Has a field named like the allocator parameter of `try_clone_in`.
+  #[derive(TryCloneIn)]
+  struct Pool<'a, A: crate::alloc::SliceAllocator<'a, crate::types::vec::SliceVec<u32>>> {
+    allocator: u32,
+    values: Vec<'a, u32, UniqueStrategy, A>,
+  }
+
+  #[derive(TryCloneIn, Debug, PartialEq)]
+  enum Shape {
+    Circle(u32),
+    Rectangle { width: u32, height: u32 },
+    Empty,
+  }
+
+  #[pollster::test]
+  async fn derive() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut values = Vec::new(&allocator, GrowthStrategy::Exponential)
+      .await
+      .unwrap();
+    values.extend([1, 2, 3]).await.unwrap();
+    let document = Document { id: 7, values };
+
+    let mut clone = document.try_clone_in(&allocator).await.unwrap();
+    clone.values[0] = 4;
+    assert_eq!(clone.id, 7);
+    assert_eq!(&**document.values, &[1, 2, 3]);
+    assert_eq!(&**clone.values, &[4, 2, 3]);
+
+    let pool = Pool {
+      allocator: 5,
+      values: document.values,
+    };
+    let clone = pool.try_clone_in(&allocator).await.unwrap();
+    assert_eq!(clone.allocator, 5);
+    assert_eq!(&**clone.values, &[1, 2, 3]);
+
+    for shape in [
+      Shape::Circle(1),
+      Shape::Rectangle {
+        width: 2,
+        height: 3,
+      },
+      Shape::Empty,
+    ] {
+      assert_eq!(shape.try_clone_in(&allocator).await.unwrap(), shape);
+    }
+  }
+}
@@ -0,0 +1,239 @@
+use core::ptr;
+
+use crate::{
+  alloc::{
+    GrowthStrategy, LayoutAllocator, SliceAllocator, UnsizedMaybeUninit,
+    strategy::{Rc, RcStrategy, Strategy, Unique, UniqueStrategy},
+    vec::Vec,
+  },
+  types::vec::SliceVec,
+};
+
+/// Like [FromIterator], for containers that have to allocate from `A`, which can fail.
+pub trait FromIteratorIn<'a, T, A: LayoutAllocator + ?Sized + 'a>: Sized {
+  async fn from_iter_in<I: IntoIterator<Item = T>>(
+    iter: I,
+    allocator: &'a A,
+  ) -> Result<Self, A::Error>;
+}
+
+/// Collects iterators into containers that allocate from `A`.
+///
+/// The container is sized by the iterator's [size_hint](Iterator::size_hint), so iterators with an
+/// exact hint, like those implementing [TrustedLen](core::iter::TrustedLen), allocate only once.
+pub trait CollectIn<'a, A: LayoutAllocator + ?Sized + 'a>: Iterator + Sized {
+  async fn collect_in<C: FromIteratorIn<'a, Self::Item, A>>(
+    self,
+    allocator: &'a A,
+  ) -> Result<C, A::Error> {
+    C::from_iter_in(self, allocator).await
+  }
+}
+
+impl<'a, A: LayoutAllocator + ?Sized + 'a, I: Iterator> CollectIn<'a, A> for I {}
+
+/// Moves the values of `iter` into a slice allocated from `allocator`.
+///
+/// The slice is allocated directly when the iterator's size hint is exact, and otherwise the values
+/// are collected into a [Vec] first. The hint can't be trusted, so when the iterator returns a
+/// different amount of values, the ones already read are moved into a [Vec] with the rest.
+async fn collect_slice_in<'a, T: 'a, S: Strategy, A, I: Iterator<Item = T>>(
+  iter: I,
+  allocator: &'a A,
+) -> Result<S::Handle<'a, [T]>, <A as LayoutAllocator>::Error>
+where
+  A: LayoutAllocator
+    + SliceAllocator<'a, [T], Error = <A as LayoutAllocator>::Error>
+    + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+  S::Data<'a, UnsizedMaybeUninit<[T]>>: SliceDst,
+{
+  // the fallback keeps going after a None when the hint was wrong, which only fused iterators allow
+  let mut iter = iter.fuse();
+  let (lower_bound, upper_bound) = iter.size_hint();
+  let mut vec: Vec<'a, T, UniqueStrategy, A> = if upper_bound == Some(lower_bound) {
+    let handle = allocator.reserve_slice::<S>(lower_bound).await?;
+    let values = S::UninitHandle::as_value_ptr(&handle).cast::<T>();
+
+    let mut length = 0;
+    while length < lower_bound
+      && let Some(value) = iter.next()
+    {
+      // Safety: length < lower_bound, which the slice was reserved for
+      unsafe { values.add(length).write(value) };
+      length += 1;
+    }
+
+    let extra = match length == lower_bound {
+      true => iter.next(),
+      false => None,
+    };
+    if length == lower_bound && extra.is_none() {
+      // Safety: every value was initialized above
+      return Ok(unsafe { S::UninitHandle::assume_init(handle) });
+    }
+
+    let vec = Vec::with_capacity(
+      allocator,
+      GrowthStrategy::Exponential,
+      length + usize::from(extra.is_some()),
+    )
+    .await;
+    let mut vec: Vec<'a, T, UniqueStrategy, A> = match vec {
+      Ok(vec) => vec,
+      Err(error) => {
+        for index in 0..length {
+          // Safety: the values before length were initialized
+          unsafe { values.add(index).drop_in_place() };
+        }
+        return Err(error);
+      }
+    };
+
+    unsafe {
+      // Safety: vec has room for every value in the slice, which are moved out of it
+      ptr::copy_nonoverlapping(
+        values,
+        vec.spare_capacity_mut().as_mut_ptr().cast::<T>(),
+        length,
+      );
+      // Safety: initialized by copying from the slice
+      vec.set_len(length);
+    }
+    if let Some(extra) = extra {
+      // vec has room for the extra value
+      let _ = vec.push(extra);
+    }
+
+    vec
+  } else {
+    Vec::with_capacity(allocator, GrowthStrategy::Exponential, lower_bound).await?
+  };
+  vec.extend(iter).await?;
+
+  let length = vec.len();
+  let handle = allocator.reserve_slice::<S>(length).await?;
+  unsafe {
+    // Safety: the slice was reserved for every value in vec, which are moved out of it
+    ptr::copy_nonoverlapping(
+      vec.as_ptr(),
+      S::UninitHandle::as_value_ptr(&handle).cast::<T>(),
+      length,
+    );
+    vec.set_len(0);
+    // Safety: initialized by copying from vec
+    Ok(S::UninitHandle::assume_init(handle))
+  }
+}
+
+impl<'a, T: 'a, A: 'a> FromIteratorIn<'a, T, A> for Unique<'a, [T]>
+where
+  A: LayoutAllocator
+    + SliceAllocator<'a, [T], Error = <A as LayoutAllocator>::Error>
+    + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+{
+  async fn from_iter_in<I: IntoIterator<Item = T>>(
+    iter: I,
+    allocator: &'a A,
+  ) -> Result<Self, <A as LayoutAllocator>::Error> {
+    collect_slice_in::<T, UniqueStrategy, A, I::IntoIter>(iter.into_iter(), allocator).await
+  }
+}
+
+impl<'a, T: 'a, A: 'a> FromIteratorIn<'a, T, A> for Rc<'a, [T]>
+where
+  A: LayoutAllocator
+    + SliceAllocator<'a, [T], Error = <A as LayoutAllocator>::Error>
+    + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+{
+  async fn from_iter_in<I: IntoIterator<Item = T>>(
+    iter: I,
+    allocator: &'a A,
+  ) -> Result<Self, <A as LayoutAllocator>::Error> {
+    collect_slice_in::<T, RcStrategy, A, I::IntoIter>(iter.into_iter(), allocator).await
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::alloc::{
+    CollectIn, ForeignAllocator, StdAlloc,
+    strategy::{Rc, Unique, UniqueStrategy},
+    string::String,
+    vec::Vec,
+  };
+
+  #[pollster::test]
+  async fn collect() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+
+    let vec = (0..10)
+      .map(|value| value * 2)
+      .collect_in::<Vec<u32, UniqueStrategy, _>>(&allocator)
+      .await
+      .unwrap();
+    assert_eq!(vec.capacity(), 10);
+    assert!(vec.iter().copied().eq((0..20).step_by(2)));
+
+    let odd = (0..10)
+      .filter(|value| value % 2 == 1)
+      .collect_in::<Unique<[u32]>>(&allocator)
+      .await
+      .unwrap();
+    assert_eq!(&*odd, &[1, 3, 5, 7, 9]);
+
+    let shared = [1u32, 2, 3]
+      .into_iter()
+      .collect_in::<Rc<[u32]>>(&allocator)
+      .await
+      .unwrap();
+    assert_eq!(&*shared, &[1, 2, 3]);
+
+    let text = "hello"
+      .chars()
+      .rev()
+      .collect_in::<String<UniqueStrategy, _>>(&allocator)
+      .await
+      .unwrap();
+    assert_eq!(text.as_str(), "olleh");
+  }
+
+  /// Returns `0..length`, while claiming to return exactly `hint` values. It isn't fused, and
+  /// returns `length + 1` after the first `None`.
+  struct WrongHint {
+    next: u32,
+    length: u32,
+    hint: usize,
+  }
+
+  impl Iterator for WrongHint {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+      let value = self.next;
+      self.next += 1;
+      (value < self.length || value == self.length + 1).then_some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      (self.hint, Some(self.hint))
+    }
+  }
+
+  #[pollster::test]
+  async fn wrong_size_hint() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+
+    for (length, hint) in [(3, 5), (5, 3), (5, 4), (0, 2), (2, 0)] {
+      let values = WrongHint {
+        next: 0,
+        length,
+        hint,
+      }
+      .collect_in::<Unique<[u32]>>(&allocator)
+      .await
+      .unwrap();
+      assert!(values.iter().copied().eq(0..length));
+    }
+  }
+}
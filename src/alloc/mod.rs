@@ -1,4 +1,6 @@
 pub mod allocator;
+pub mod clone;
+pub mod collect;
 mod free;
 mod slice_dst;
 pub mod strategy;
@@ -6,6 +8,8 @@ pub mod types;
 mod uninit;
 
 pub use allocator::*;
+pub use clone::*;
+pub use collect::*;
 pub use free::*;
 pub use slice_dst::*;
 pub use types::*;
@@ -1,6 +1,6 @@
 use core::{cell::UnsafeCell, ptr::Pointee};
 
-pub use aubystd_macros::slice_dst;
+pub use aubystd_macros::SliceDst;
 
 // todo: describe unsafe contract (repr assertion, used for allocations so must match type)
 pub unsafe trait SliceDst: Pointee<Metadata = usize> {
@@ -36,3 +36,73 @@ unsafe impl<T: SliceDst + ?Sized> SliceDst for UnsafeCell<T> {
     T::addr_of_slice(UnsafeCell::raw_get(ptr as *const _))
   }
 }
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use core::{cell::Cell, marker::PhantomData};
+
+  use zerocopy::FromZeros;
+
+  use crate::alloc::{
+    ForeignAllocator, SliceAllocator, SliceDst, StdAlloc,
+    strategy::{Unique, UniqueStrategy},
+  };
+
+  #[derive(SliceDst)]
+  #[slice_dst(derive(FromZeros))]
+  #[repr(C)]
+  struct Packet {
+    kind: u32,
+    length: u32,
+    payload: [u8],
+  }
+
+  #[derive(SliceDst)]
+  #[slice_dst(header = TaggedPrefix)]
+  #[repr(C)]
+  struct Tagged<'a, T: ?Sized, U> {
+    tag: Cell<usize>,
+    phantom: PhantomData<&'a U>,
+    value: T,
+  }
+
+  #[derive(SliceDst)]
+  #[repr(transparent)]
+  struct Wrapper(Packet);
+
+  #[pollster::test]
+  async fn derive() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+
+    let mut packet: Unique<Packet> = allocator.from_zeros::<UniqueStrategy>(3).await.unwrap();
+    packet.payload.copy_from_slice(b"abc");
+    assert_eq!(
+      (packet.kind, packet.length, &packet.payload),
+      (0, 0, &b"abc"[..])
+    );
+
+    let mut tagged =
+      SliceAllocator::<Tagged<Wrapper, u8>>::reserve_slice::<UniqueStrategy>(&allocator, 2)
+        .await
+        .unwrap();
+    tagged.header.write(TaggedPrefix {
+      tag: Cell::new(7),
+      phantom: PhantomData,
+      value_header: WrapperHeader(PacketHeader {
+        kind: 1,
+        length: 2,
+        payload_header: (),
+      }),
+    });
+    for element in &mut tagged.slice {
+      element.write(b'x');
+    }
+    // Safety: the header and elements were initialized above
+    let tagged = unsafe { Unique::assume_init(tagged) };
+
+    let Wrapper(packet) = &tagged.value;
+    assert_eq!(tagged.tag.get(), 7);
+    assert_eq!((packet.kind, packet.length, &packet.payload), (1, 2, &b"xx"[..]));
+  }
+}
@@ -8,8 +8,6 @@ use core::{
   sync::atomic::{AtomicUsize, Ordering},
 };
 
-use aubystd_macros::slice_dst;
-
 use crate::alloc::strategy::{StrategyVariance, UninitType, assert_alignment};
 
 use super::{FreeVtable, Strategy, StrategyHandle, UninitStrategyHandleExt};
@@ -17,7 +15,7 @@ use super::{FreeVtable, Strategy, StrategyHandle, UninitStrategyHandleExt};
 #[derive(Default)]
 pub struct ArcStrategy;
 
-#[slice_dst(header = ArcDataHeader)]
+#[derive(SliceDst)]
 #[doc(hidden)]
 #[repr(C)]
 pub struct ArcData<'a, T: ?Sized> {
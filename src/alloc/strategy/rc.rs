@@ -8,8 +8,6 @@ use core::{
   ptr::{self, Pointee},
 };
 
-use aubystd_macros::slice_dst;
-
 use crate::alloc::strategy::{StrategyVariance, UninitType, assert_alignment};
 
 use super::{FreeVtable, Strategy, StrategyHandle, UninitStrategyHandleExt};
@@ -17,7 +15,7 @@ use super::{FreeVtable, Strategy, StrategyHandle, UninitStrategyHandleExt};
 #[derive(Default)]
 pub struct RcStrategy;
 
-#[slice_dst(header = RcDataHeader)]
+#[derive(SliceDst)]
 #[doc(hidden)]
 #[repr(C)]
 pub struct RcData<'a, T: ?Sized> {
@@ -9,8 +9,6 @@ use core::{
   ptr::{self, Pointee},
 };
 
-use aubystd_macros::slice_dst;
-
 use crate::alloc::strategy::{PinStrategyHandle, StrategyVariance, UninitType, assert_alignment};
 
 use super::{FreeVtable, Strategy, StrategyHandle, UninitStrategyHandleExt};
@@ -18,7 +16,7 @@ use super::{FreeVtable, Strategy, StrategyHandle, UninitStrategyHandleExt};
 #[derive(Default)]
 pub struct UniqueStrategy;
 
-#[slice_dst(header = UniqueDataHeader)]
+#[derive(SliceDst)]
 #[doc(hidden)]
 #[repr(C)]
 pub struct UniqueData<'a, T: ?Sized> {
@@ -0,0 +1,321 @@
+use core::{
+  fmt::{self, Debug},
+  ops::{BitAndAssign, BitOrAssign, BitXorAssign, Deref, DerefMut},
+};
+
+use crate::{
+  alloc::{
+    GrowthStrategy, LayoutAllocator, SliceAllocator, TryCloneIn, UnsizedMaybeUninit,
+    strategy::Strategy, vec::Vec,
+  },
+  types::{
+    bitset::{self, Ones, WORD_BITS, words_for},
+    vec::SliceVec,
+  },
+};
+
+/// A growable set of bits packed into a [Vec] of words.
+pub struct BitVec<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<usize>>>
+where
+  S::Handle<'a, SliceVec<usize>>: Deref<Target = SliceVec<usize>>,
+{
+  words: Vec<'a, usize, S, A>,
+  length: usize,
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<usize>>> BitVec<'a, S, A>
+where
+  S::Handle<'a, SliceVec<usize>>: Deref<Target = SliceVec<usize>>,
+{
+  pub async fn new(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<usize>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<usize>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<usize>>>: SliceDst,
+  {
+    Self::with_capacity(allocator, strategy, 0).await
+  }
+
+  /// Creates an empty vec with room for at least `capacity` bits.
+  pub async fn with_capacity(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<usize>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<usize>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<usize>>>: SliceDst,
+  {
+    Ok(Self {
+      words: Vec::with_capacity(allocator, strategy, words_for(capacity)).await?,
+      length: 0,
+    })
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<usize>>> BitVec<'a, S, A>
+where
+  S::Handle<'a, SliceVec<usize>>: DerefMut<Target = SliceVec<usize>>,
+{
+  pub fn len(&self) -> usize {
+    self.length
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.length == 0
+  }
+
+  /// The number of bits that fit before the vec has to grow.
+  pub fn capacity(&self) -> usize {
+    self.words.capacity() * WORD_BITS
+  }
+
+  fn check(&self, index: usize) {
+    let length = self.length;
+    assert!(
+      index < length,
+      "bit index {index} out of bounds for length {length}"
+    );
+  }
+
+  /// Whether bit `index` is set.
+  ///
+  /// Panics if `index >= len`.
+  pub fn test(&self, index: usize) -> bool {
+    self.check(index);
+    bitset::test(&self.words, index)
+  }
+
+  /// Panics if `index >= len`.
+  pub fn set(&mut self, index: usize) {
+    self.check(index);
+    bitset::set(&mut self.words, index);
+  }
+
+  /// Panics if `index >= len`.
+  pub fn clear(&mut self, index: usize) {
+    self.check(index);
+    bitset::clear(&mut self.words, index);
+  }
+
+  /// Panics if `index >= len`.
+  pub fn toggle(&mut self, index: usize) {
+    self.check(index);
+    bitset::toggle(&mut self.words, index);
+  }
+
+  /// Clears every bit without changing the length.
+  pub fn reset(&mut self) {
+    self.words.fill(0);
+  }
+
+  /// Flips every bit.
+  pub fn negate(&mut self) {
+    for word in self.words.iter_mut() {
+      *word = !*word;
+    }
+    bitset::mask_tail(&mut self.words, self.length);
+  }
+
+  pub fn count_ones(&self) -> usize {
+    bitset::count_ones(&self.words)
+  }
+
+  /// Counts the set bits before `index`.
+  ///
+  /// Panics if `index > len`.
+  pub fn rank(&self, index: usize) -> usize {
+    let length = self.length;
+    assert!(
+      index <= length,
+      "bit index {index} out of bounds for length {length}"
+    );
+    bitset::rank(&self.words, index)
+  }
+
+  /// Finds the index of the `n`th set bit, counting from zero.
+  pub fn select(&self, n: usize) -> Option<usize> {
+    bitset::select(&self.words, n)
+  }
+
+  pub fn iter_ones(&self) -> Ones<'_> {
+    Ones::new(&self.words)
+  }
+
+  pub fn pop(&mut self) -> Option<bool> {
+    let index = self.length.checked_sub(1)?;
+    let value = bitset::test(&self.words, index);
+    self.truncate(index);
+
+    Some(value)
+  }
+
+  /// Drops every bit after the first `length` bits.
+  pub fn truncate(&mut self, length: usize) {
+    if length >= self.length {
+      return;
+    }
+
+    self.words.truncate(words_for(length));
+    bitset::mask_tail(&mut self.words, length);
+    self.length = length;
+  }
+
+  pub fn as_words(&self) -> &[usize] {
+    &self.words
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<usize>>> BitVec<'a, S, A>
+where
+  S::Handle<'a, SliceVec<usize>>: DerefMut<Target = SliceVec<usize>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<usize>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<usize>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<usize>>>: SliceDst,
+{
+  /// Appends a bit, growing the vec if it's full.
+  pub async fn push(&mut self, value: bool) -> Result<(), A::Error> {
+    if self.length.is_multiple_of(WORD_BITS) {
+      self.words.push_resize(0).await?;
+    }
+
+    self.length += 1;
+    if value {
+      bitset::set(&mut self.words, self.length - 1);
+    }
+
+    Ok(())
+  }
+
+  /// Changes the length to `length`, filling any new bits with `value`.
+  pub async fn resize(&mut self, length: usize, value: bool) -> Result<(), A::Error> {
+    if length <= self.length {
+      self.truncate(length);
+      return Ok(());
+    }
+
+    let fill = if value { usize::MAX } else { 0 };
+    if value && !self.length.is_multiple_of(WORD_BITS) {
+      let last = self.words.len() - 1;
+      self.words[last] |= usize::MAX << (self.length % WORD_BITS);
+    }
+
+    let words = words_for(length);
+    if self.words.capacity() < words {
+      self.words.grow(words - self.words.len()).await?;
+    }
+    while self.words.len() < words {
+      let Ok(_) = self.words.push(fill) else {
+        unreachable!("not enough space for value");
+      };
+    }
+
+    self.length = length;
+    bitset::mask_tail(&mut self.words, length);
+
+    Ok(())
+  }
+
+  pub async fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) -> Result<(), A::Error> {
+    for value in iter {
+      self.push(value).await?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, S: Strategy, A> TryCloneIn<'a, A> for BitVec<'a, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<usize>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<usize>>: DerefMut<Target = SliceVec<usize>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<usize>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<usize>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<usize>>>: SliceDst,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    Ok(Self {
+      words: self.words.try_clone_in(allocator).await?,
+      length: self.length,
+    })
+  }
+}
+
+macro_rules! bitwise_op {
+  ($assign_trait:ident, $assign_method:ident, $op:tt) => {
+    /// Panics if the vecs have different lengths.
+    impl<'a, 'b, S: Strategy, A: SliceAllocator<'a, SliceVec<usize>>, S2: Strategy, A2: SliceAllocator<'b, SliceVec<usize>>>
+      $assign_trait<&BitVec<'b, S2, A2>> for BitVec<'a, S, A>
+    where
+      S::Handle<'a, SliceVec<usize>>: DerefMut<Target = SliceVec<usize>>,
+      S2::Handle<'b, SliceVec<usize>>: DerefMut<Target = SliceVec<usize>>,
+    {
+      fn $assign_method(&mut self, rhs: &BitVec<'b, S2, A2>) {
+        assert_eq!(self.length, rhs.length, "bit vecs have different lengths");
+        for (word, rhs) in self.words.iter_mut().zip(rhs.as_words()) {
+          *word = *word $op rhs;
+        }
+      }
+    }
+  };
+}
+
+bitwise_op!(BitAndAssign, bitand_assign, &);
+bitwise_op!(BitOrAssign, bitor_assign, |);
+bitwise_op!(BitXorAssign, bitxor_assign, ^);
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<usize>>> Debug for BitVec<'a, S, A>
+where
+  S::Handle<'a, SliceVec<usize>>: DerefMut<Target = SliceVec<usize>>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.iter_ones()).finish()
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::alloc::{
+    ForeignAllocator, GrowthStrategy, StdAlloc, bit_vec::BitVec, strategy::UniqueStrategy,
+  };
+
+  #[pollster::test]
+  async fn grow_and_combine() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut bits = BitVec::<UniqueStrategy, _>::new(&allocator, GrowthStrategy::Exponential)
+      .await
+      .unwrap();
+    bits
+      .extend((0..100).map(|index| index % 3 == 0))
+      .await
+      .unwrap();
+    bits.resize(150, true).await.unwrap();
+
+    assert_eq!(bits.len(), 150);
+    assert_eq!(bits.count_ones(), 34 + 50);
+    assert_eq!(bits.rank(99), 33);
+    assert_eq!(bits.select(34), Some(100));
+    assert_eq!(bits.pop(), Some(true));
+
+    let mut other = BitVec::<UniqueStrategy, _>::new(&allocator, GrowthStrategy::Exact)
+      .await
+      .unwrap();
+    other.resize(149, false).await.unwrap();
+    other.set(3);
+    other.set(4);
+    other.negate();
+
+    bits &= &other;
+    assert_eq!(bits.count_ones(), 33 + 49);
+    assert!(!bits.test(3) && !bits.test(4) && bits.test(6));
+
+    bits.truncate(64);
+    assert!(
+      bits
+        .iter_ones()
+        .eq((0..64).step_by(3).filter(|&index| index != 3))
+    );
+  }
+}
@@ -0,0 +1,1483 @@
+use core::{
+  borrow::Borrow,
+  cmp::Ordering,
+  fmt::{self, Debug},
+  iter::FusedIterator,
+  marker::PhantomData,
+  mem,
+  ops::{Bound, Index, RangeBounds},
+  ptr::NonNull,
+};
+
+use crate::{
+  alloc::{
+    Allocator,
+    strategy::{StrategyHandle, Unique, UniqueStrategy},
+  },
+  types::vec::FixedVec,
+};
+
+const B: usize = 6;
+const CAPACITY: usize = 2 * B - 1;
+const MIN_LENGTH: usize = B - 1;
+
+/// A single node of a [BTreeMap]. Leaves are nodes without any edges.
+#[doc(hidden)]
+pub struct BTreeNode<'a, K, V> {
+  parent: Option<NonNull<BTreeNode<'a, K, V>>>,
+  parent_index: usize,
+  keys: FixedVec<K, CAPACITY>,
+  values: FixedVec<V, CAPACITY>,
+  edges: FixedVec<Unique<'a, BTreeNode<'a, K, V>>, { CAPACITY + 1 }>,
+}
+
+type NodePtr<'a, K, V> = NonNull<BTreeNode<'a, K, V>>;
+
+impl<'a, K, V> BTreeNode<'a, K, V> {
+  fn new() -> Self {
+    Self {
+      parent: None,
+      parent_index: 0,
+      keys: FixedVec::new(),
+      values: FixedVec::new(),
+      edges: FixedVec::new(),
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.keys.len()
+  }
+
+  fn is_leaf(&self) -> bool {
+    self.edges.is_empty()
+  }
+
+  fn edge(&self, index: usize) -> NodePtr<'a, K, V> {
+    // Safety: value pointers of live handles are never null
+    unsafe { NonNull::new_unchecked(Unique::as_value_ptr(&self.edges[index])) }
+  }
+
+  /// Searches this node for `key`, returning the index of the matching key or the index of the
+  /// edge the key would be found under.
+  fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Result<usize, usize>
+  where
+    K: Borrow<Q>,
+  {
+    for (index, node_key) in self.keys.iter().enumerate() {
+      match key.cmp(node_key.borrow()) {
+        Ordering::Less => return Err(index),
+        Ordering::Equal => return Ok(index),
+        Ordering::Greater => {}
+      }
+    }
+
+    Err(self.len())
+  }
+
+  /// Points the parent links of every edge from `start` onwards back at this node.
+  fn correct_parent_links(&mut self, start: usize) {
+    let this = NonNull::from(&mut *self);
+    for (index, edge) in self.edges.iter_mut().enumerate().skip(start) {
+      edge.parent = Some(this);
+      edge.parent_index = index;
+    }
+  }
+
+  fn push(&mut self, key: K, value: V, edge: Option<Unique<'a, Self>>) {
+    let (Ok(()), Ok(())) = (self.keys.push(key), self.values.push(value)) else {
+      unreachable!("node is full");
+    };
+    if let Some(edge) = edge {
+      let Ok(()) = self.edges.push(edge) else {
+        unreachable!("node is full");
+      };
+      self.correct_parent_links(self.edges.len() - 1);
+    }
+  }
+
+  fn push_front(&mut self, key: K, value: V, edge: Option<Unique<'a, Self>>) {
+    let (Ok(()), Ok(())) = (self.keys.insert(0, key), self.values.insert(0, value)) else {
+      unreachable!("node is full");
+    };
+    if let Some(edge) = edge {
+      let Ok(()) = self.edges.insert(0, edge) else {
+        unreachable!("node is full");
+      };
+      self.correct_parent_links(0);
+    }
+  }
+
+  fn pop(&mut self) -> (K, V, Option<Unique<'a, Self>>) {
+    let key = self.keys.pop().expect("node is empty");
+    let value = self.values.pop().expect("node is empty");
+    (key, value, self.edges.pop())
+  }
+
+  fn pop_front(&mut self) -> (K, V, Option<Unique<'a, Self>>) {
+    let key = self.keys.remove(0);
+    let value = self.values.remove(0);
+    let edge = (!self.is_leaf()).then(|| self.edges.remove(0));
+    self.correct_parent_links(0);
+    (key, value, edge)
+  }
+
+  /// Splits the full child at `index` in half, moving its upper half into `sibling` and its median
+  /// into this node.
+  fn split_edge(&mut self, index: usize, mut sibling: Unique<'a, Self>) {
+    // Safety: edges are uniquely owned by this node
+    let child = unsafe { self.edge(index).as_mut() };
+    debug_assert_eq!(child.len(), CAPACITY);
+
+    while child.len() > B {
+      let key = child.keys.remove(B);
+      let value = child.values.remove(B);
+      let edge = (!child.is_leaf()).then(|| child.edges.remove(B + 1));
+      sibling.push(key, value, edge);
+    }
+    if !child.is_leaf() {
+      let edge = child.edges.remove(B);
+      sibling.push_edge_front(edge);
+    }
+    let key = child.keys.pop().unwrap();
+    let value = child.values.pop().unwrap();
+
+    let (Ok(()), Ok(()), Ok(())) = (
+      self.keys.insert(index, key),
+      self.values.insert(index, value),
+      self.edges.insert(index + 1, sibling),
+    ) else {
+      unreachable!("node is full");
+    };
+    self.correct_parent_links(index + 1);
+  }
+
+  fn push_edge_front(&mut self, edge: Unique<'a, Self>) {
+    let Ok(()) = self.edges.insert(0, edge) else {
+      unreachable!("node is full");
+    };
+    self.correct_parent_links(0);
+  }
+
+  /// Moves the last key of the child at `index` up into this node, and the key it replaces down
+  /// into the front of the child at `index + 1`.
+  fn rotate_right(&mut self, index: usize) {
+    // Safety: both edges are distinct and uniquely owned by this node
+    let (left, right) = unsafe { (self.edge(index).as_mut(), self.edge(index + 1).as_mut()) };
+
+    let (key, value, edge) = left.pop();
+    let key = mem::replace(&mut self.keys[index], key);
+    let value = mem::replace(&mut self.values[index], value);
+    right.push_front(key, value, edge);
+  }
+
+  /// Moves the first key of the child at `index + 1` up into this node, and the key it replaces
+  /// down into the back of the child at `index`.
+  fn rotate_left(&mut self, index: usize) {
+    // Safety: both edges are distinct and uniquely owned by this node
+    let (left, right) = unsafe { (self.edge(index).as_mut(), self.edge(index + 1).as_mut()) };
+
+    let (key, value, edge) = right.pop_front();
+    let key = mem::replace(&mut self.keys[index], key);
+    let value = mem::replace(&mut self.values[index], value);
+    left.push(key, value, edge);
+  }
+
+  /// Merges the child at `index + 1` and the key separating it into the child at `index`, freeing
+  /// the emptied child.
+  fn merge_edges(&mut self, index: usize) {
+    let mut right = self.edges.remove(index + 1);
+    self.correct_parent_links(index + 1);
+    // Safety: edges are uniquely owned by this node
+    let left = unsafe { self.edge(index).as_mut() };
+
+    let key = self.keys.remove(index);
+    let value = self.values.remove(index);
+    let edge = (!right.is_leaf()).then(|| right.edges.remove(0));
+    left.push(key, value, edge);
+    while right.len() > 0 {
+      let (key, value, edge) = right.pop_front();
+      left.push(key, value, edge);
+    }
+  }
+}
+
+impl<'a, K, V> Drop for BTreeNode<'a, K, V> {
+  fn drop(&mut self) {
+    self.keys.clear();
+    self.values.clear();
+    self.edges.clear();
+  }
+}
+
+/// A position of a key-value pair inside of a tree.
+struct Handle<'a, K, V> {
+  node: NodePtr<'a, K, V>,
+  index: usize,
+}
+
+impl<'a, K, V> Clone for Handle<'a, K, V> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'a, K, V> Copy for Handle<'a, K, V> {}
+
+impl<'a, K, V> PartialEq for Handle<'a, K, V> {
+  fn eq(&self, other: &Self) -> bool {
+    self.node == other.node && self.index == other.index
+  }
+}
+
+impl<'a, K, V> Handle<'a, K, V> {
+  /// Shortens the lifetime of the tree this handle points into.
+  fn cast<'b>(self) -> Handle<'b, K, V>
+  where
+    'a: 'b,
+  {
+    Handle {
+      node: self.node.cast(),
+      index: self.index,
+    }
+  }
+
+  /// Safety: the node must be alive
+  unsafe fn first_in(mut node: NodePtr<'a, K, V>) -> Self {
+    // Safety: upheld by caller, and edges of a live node are alive
+    unsafe {
+      while !node.as_ref().is_leaf() {
+        node = node.as_ref().edge(0);
+      }
+    }
+    Self { node, index: 0 }
+  }
+
+  /// Safety: the node must be alive
+  unsafe fn last_in(mut node: NodePtr<'a, K, V>) -> Self {
+    // Safety: upheld by caller, and edges of a live node are alive
+    unsafe {
+      while !node.as_ref().is_leaf() {
+        node = node.as_ref().edge(node.as_ref().len());
+      }
+      Self {
+        node,
+        index: node.as_ref().len() - 1,
+      }
+    }
+  }
+
+  /// Safety: the handle must point to a key-value pair in a live tree
+  unsafe fn next(self) -> Option<Self> {
+    // Safety: upheld by caller
+    unsafe {
+      let node = self.node.as_ref();
+      if !node.is_leaf() {
+        return Some(Self::first_in(node.edge(self.index + 1)));
+      }
+      if self.index + 1 < node.len() {
+        return Some(Self {
+          node: self.node,
+          index: self.index + 1,
+        });
+      }
+
+      let mut node = node;
+      while let Some(parent) = node.parent {
+        if node.parent_index < parent.as_ref().len() {
+          return Some(Self {
+            node: parent,
+            index: node.parent_index,
+          });
+        }
+        node = parent.as_ref();
+      }
+      None
+    }
+  }
+
+  /// Safety: the handle must point to a key-value pair in a live tree
+  unsafe fn next_back(self) -> Option<Self> {
+    // Safety: upheld by caller
+    unsafe {
+      let node = self.node.as_ref();
+      if !node.is_leaf() {
+        return Some(Self::last_in(node.edge(self.index)));
+      }
+      if self.index > 0 {
+        return Some(Self {
+          node: self.node,
+          index: self.index - 1,
+        });
+      }
+
+      let mut node = node;
+      while let Some(parent) = node.parent {
+        if node.parent_index > 0 {
+          return Some(Self {
+            node: parent,
+            index: node.parent_index - 1,
+          });
+        }
+        node = parent.as_ref();
+      }
+      None
+    }
+  }
+
+  /// Safety: the handle must point to a key-value pair in a live tree, and the references must not
+  /// outlive it.
+  unsafe fn key_value<'b>(self) -> (&'b K, &'b V)
+  where
+    'a: 'b,
+  {
+    // Safety: upheld by caller
+    unsafe {
+      let node = self.node.as_ref();
+      (&node.keys[self.index], &node.values[self.index])
+    }
+  }
+
+  /// Safety: the handle must point to a key-value pair in a live tree, and the references must not
+  /// outlive it or alias other references to the value.
+  unsafe fn key_value_mut<'b>(mut self) -> (&'b K, &'b mut V)
+  where
+    'a: 'b,
+  {
+    // Safety: upheld by caller
+    unsafe {
+      let node = self.node.as_mut();
+      (&node.keys[self.index], &mut node.values[self.index])
+    }
+  }
+}
+
+/// An ordered map based on a B-tree, with its nodes allocated from `A`.
+///
+/// All operations that can allocate are asynchronous and fallible; a failed insertion leaves the
+/// map unchanged.
+pub struct BTreeMap<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> {
+  allocator: &'a A,
+  root: Option<Unique<'a, BTreeNode<'a, K, V>>>,
+  length: usize,
+}
+
+impl<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> BTreeMap<'a, K, V, A> {
+  /// Creates an empty map. Nodes are only allocated once the first value is inserted.
+  pub fn new(allocator: &'a A) -> Self {
+    Self {
+      allocator,
+      root: None,
+      length: 0,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.length
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.length == 0
+  }
+
+  fn root_ptr(&self) -> Option<NodePtr<'a, K, V>> {
+    self
+      .root
+      .as_ref()
+      // Safety: value pointers of live handles are never null
+      .map(|root| unsafe { NonNull::new_unchecked(Unique::as_value_ptr(root)) })
+  }
+
+  fn find<Q: Ord + ?Sized>(&self, key: &Q) -> Option<Handle<'a, K, V>>
+  where
+    K: Borrow<Q>,
+  {
+    let mut node = self.root_ptr()?;
+    loop {
+      // Safety: nodes are alive as long as the map is borrowed
+      let current = unsafe { node.as_ref() };
+      match current.search(key) {
+        Ok(index) => return Some(Handle { node, index }),
+        Err(_) if current.is_leaf() => return None,
+        Err(index) => node = current.edge(index),
+      }
+    }
+  }
+
+  fn first_handle(&self) -> Option<Handle<'a, K, V>> {
+    // Safety: the root is alive, and never empty
+    self
+      .root_ptr()
+      .map(|root| unsafe { Handle::first_in(root) })
+  }
+
+  fn last_handle(&self) -> Option<Handle<'a, K, V>> {
+    // Safety: the root is alive, and never empty
+    self.root_ptr().map(|root| unsafe { Handle::last_in(root) })
+  }
+
+  /// Finds the first key-value pair that lies within the lower bound.
+  fn lower_bound<Q: Ord + ?Sized>(&self, bound: Bound<&Q>) -> Option<Handle<'a, K, V>>
+  where
+    K: Borrow<Q>,
+  {
+    let mut node = self.root_ptr()?;
+    let mut found = None;
+    loop {
+      // Safety: nodes are alive as long as the map is borrowed
+      let current = unsafe { node.as_ref() };
+      let index = current
+        .keys
+        .iter()
+        .position(|key| match bound {
+          Bound::Included(bound) => key.borrow() >= bound,
+          Bound::Excluded(bound) => key.borrow() > bound,
+          Bound::Unbounded => true,
+        })
+        .unwrap_or(current.len());
+      if index < current.len() {
+        found = Some(Handle { node, index });
+      }
+      if current.is_leaf() {
+        return found;
+      }
+      node = current.edge(index);
+    }
+  }
+
+  /// Finds the last key-value pair that lies within the upper bound.
+  fn upper_bound<Q: Ord + ?Sized>(&self, bound: Bound<&Q>) -> Option<Handle<'a, K, V>>
+  where
+    K: Borrow<Q>,
+  {
+    let mut node = self.root_ptr()?;
+    let mut found = None;
+    loop {
+      // Safety: nodes are alive as long as the map is borrowed
+      let current = unsafe { node.as_ref() };
+      let index = current
+        .keys
+        .iter()
+        .position(|key| match bound {
+          Bound::Included(bound) => key.borrow() > bound,
+          Bound::Excluded(bound) => key.borrow() >= bound,
+          Bound::Unbounded => false,
+        })
+        .unwrap_or(current.len());
+      if index > 0 {
+        found = Some(Handle {
+          node,
+          index: index - 1,
+        });
+      }
+      if current.is_leaf() {
+        return found;
+      }
+      node = current.edge(index);
+    }
+  }
+
+  fn raw_range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> RawRange<'a, K, V>
+  where
+    K: Borrow<Q>,
+  {
+    let front = self.lower_bound(range.start_bound());
+    let back = self.upper_bound(range.end_bound());
+    match (front, back) {
+      // Safety: both handles point into this map
+      (Some(front), Some(back))
+        if unsafe { front.key_value().0.borrow() <= back.key_value().0.borrow() as &Q } =>
+      {
+        RawRange {
+          front: Some(front),
+          back: Some(back),
+        }
+      }
+      _ => RawRange::empty(),
+    }
+  }
+
+  pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+  {
+    // Safety: the handle points into this map, which stays borrowed
+    self.find(key).map(|handle| unsafe { handle.key_value().1 })
+  }
+
+  pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+  where
+    K: Borrow<Q>,
+  {
+    // Safety: the handle points into this map, which stays borrowed
+    self.find(key).map(|handle| unsafe { handle.key_value() })
+  }
+
+  pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+  {
+    // Safety: the handle points into this map, which stays mutably borrowed
+    self
+      .find(key)
+      .map(|handle| unsafe { handle.key_value_mut().1 })
+  }
+
+  pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+  {
+    self.find(key).is_some()
+  }
+
+  pub fn first_key_value(&self) -> Option<(&K, &V)> {
+    // Safety: the handle points into this map, which stays borrowed
+    self
+      .first_handle()
+      .map(|handle| unsafe { handle.key_value() })
+  }
+
+  pub fn last_key_value(&self) -> Option<(&K, &V)> {
+    // Safety: the handle points into this map, which stays borrowed
+    self
+      .last_handle()
+      .map(|handle| unsafe { handle.key_value() })
+  }
+
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter {
+      range: Range {
+        raw: RawRange::between(self.first_handle(), self.last_handle()).cast(),
+        phantom: PhantomData,
+      },
+      length: self.length,
+    }
+  }
+
+  pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+    IterMut {
+      range: RangeMut {
+        raw: RawRange::between(self.first_handle(), self.last_handle()).cast(),
+        phantom: PhantomData,
+      },
+      length: self.length,
+    }
+  }
+
+  pub fn keys(&self) -> Keys<'_, K, V> {
+    Keys(self.iter())
+  }
+
+  pub fn values(&self) -> Values<'_, K, V> {
+    Values(self.iter())
+  }
+
+  pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+    ValuesMut(self.iter_mut())
+  }
+
+  /// Iterates over the key-value pairs whose keys lie within `range`, in ascending order.
+  pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> Range<'_, K, V>
+  where
+    K: Borrow<Q>,
+  {
+    Range {
+      raw: self.raw_range(range).cast(),
+      phantom: PhantomData,
+    }
+  }
+
+  pub fn range_mut<Q: Ord + ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> RangeMut<'_, K, V>
+  where
+    K: Borrow<Q>,
+  {
+    RangeMut {
+      raw: self.raw_range(range).cast(),
+      phantom: PhantomData,
+    }
+  }
+
+  pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+  {
+    self.remove_entry(key).map(|(_, value)| value)
+  }
+
+  pub fn remove_entry<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+  where
+    K: Borrow<Q>,
+  {
+    let handle = self.find(key)?;
+    Some(self.remove_handle(handle))
+  }
+
+  pub fn pop_first(&mut self) -> Option<(K, V)> {
+    let handle = self.first_handle()?;
+    Some(self.remove_handle(handle))
+  }
+
+  pub fn pop_last(&mut self) -> Option<(K, V)> {
+    let handle = self.last_handle()?;
+    Some(self.remove_handle(handle))
+  }
+
+  /// Removes every key-value pair, freeing all of the nodes.
+  pub fn clear(&mut self) {
+    self.root = None;
+    self.length = 0;
+  }
+
+  /// Removes the key-value pair at `handle`, then rebalances the tree from the leaf upwards.
+  fn remove_handle(&mut self, handle: Handle<'a, K, V>) -> (K, V) {
+    self.length -= 1;
+
+    // Safety: the handle points into this map, and nodes are only accessed one at a time
+    unsafe {
+      let (mut node_ptr, removed) = if handle.node.as_ref().is_leaf() {
+        let mut leaf = handle.node;
+        let leaf_node = leaf.as_mut();
+        let key = leaf_node.keys.remove(handle.index);
+        let value = leaf_node.values.remove(handle.index);
+        (leaf, (key, value))
+      } else {
+        // replace the pair with its predecessor, which always lives in a leaf
+        let Handle {
+          node: mut leaf,
+          index,
+        } = Handle::last_in(handle.node.as_ref().edge(handle.index));
+        let leaf_node = leaf.as_mut();
+        let key = leaf_node.keys.remove(index);
+        let value = leaf_node.values.remove(index);
+
+        let mut node = handle.node;
+        let node = node.as_mut();
+        let key = mem::replace(&mut node.keys[handle.index], key);
+        let value = mem::replace(&mut node.values[handle.index], value);
+        (leaf, (key, value))
+      };
+
+      loop {
+        let node = node_ptr.as_mut();
+        let Some(mut parent) = node.parent else {
+          if node.len() == 0 {
+            // the root is empty, so it's replaced by its only child (if any)
+            let child = node.edges.pop();
+            if let Some(mut child) = child {
+              child.parent = None;
+              child.parent_index = 0;
+              self.root = Some(child);
+            } else {
+              self.root = None;
+            }
+          }
+          break;
+        };
+
+        if node.len() >= MIN_LENGTH {
+          break;
+        }
+
+        let index = node.parent_index;
+        let parent_node = parent.as_mut();
+        if index > 0 && parent_node.edge(index - 1).as_ref().len() > MIN_LENGTH {
+          parent_node.rotate_right(index - 1);
+          break;
+        }
+        if index < parent_node.len() && parent_node.edge(index + 1).as_ref().len() > MIN_LENGTH {
+          parent_node.rotate_left(index);
+          break;
+        }
+
+        parent_node.merge_edges(if index > 0 { index - 1 } else { index });
+        node_ptr = parent;
+      }
+
+      removed
+    }
+  }
+
+  async fn allocate_node(&self) -> Result<Unique<'a, BTreeNode<'a, K, V>>, A::Error> {
+    self
+      .allocator
+      .take::<UniqueStrategy>(BTreeNode::new())
+      .await
+  }
+
+  /// Inserts a key that isn't in the map yet, splitting full nodes on the way down so the leaf it
+  /// ends up in always has room.
+  ///
+  /// Every split leaves the tree valid, so an allocation failure leaves the map unchanged.
+  async fn insert_vacant(&mut self, key: K, value: V) -> Result<NonNull<V>, A::Error>
+  where
+    K: Ord,
+  {
+    if self.root.is_none() {
+      self.root = Some(self.allocate_node().await?);
+    }
+
+    if self
+      .root
+      .as_ref()
+      .is_some_and(|root| root.len() == CAPACITY)
+    {
+      let mut new_root = self.allocate_node().await?;
+      let sibling = self.allocate_node().await?;
+      let old_root = self.root.take().unwrap();
+      new_root.push_edge_front(old_root);
+      new_root.split_edge(0, sibling);
+      self.root = Some(new_root);
+    }
+
+    let mut node = self.root_ptr().unwrap();
+    loop {
+      // Safety: nodes are alive as long as the map is borrowed, and only accessed one at a time
+      let current = unsafe { node.as_mut() };
+      let Err(mut index) = current.search(&key) else {
+        unreachable!("key is already in the map");
+      };
+
+      if current.is_leaf() {
+        let (Ok(()), Ok(())) = (
+          current.keys.insert(index, key),
+          current.values.insert(index, value),
+        ) else {
+          unreachable!("leaf is full");
+        };
+        self.length += 1;
+        return Ok(NonNull::from(&mut current.values[index]));
+      }
+
+      // Safety: edges are alive as long as their parent is
+      if unsafe { current.edge(index).as_ref() }.len() == CAPACITY {
+        let sibling = self.allocate_node().await?;
+        current.split_edge(index, sibling);
+        if key > current.keys[index] {
+          index += 1;
+        }
+      }
+      node = current.edge(index);
+    }
+  }
+
+  /// Inserts a key-value pair into the map, returning the previous value of the key.
+  pub async fn insert(&mut self, key: K, value: V) -> Result<Option<V>, A::Error>
+  where
+    K: Ord,
+  {
+    if let Some(existing) = self.get_mut(&key) {
+      return Ok(Some(mem::replace(existing, value)));
+    }
+
+    self.insert_vacant(key, value).await?;
+    Ok(None)
+  }
+
+  /// Gets the entry of `key`, for in-place manipulation.
+  pub fn entry(&mut self, key: K) -> Entry<'_, 'a, K, V, A>
+  where
+    K: Ord,
+  {
+    match self.find(&key) {
+      Some(handle) => Entry::Occupied(OccupiedEntry { map: self, handle }),
+      None => Entry::Vacant(VacantEntry { map: self, key }),
+    }
+  }
+
+  /// Inserts every key-value pair of `iter`, stopping at the first allocation failure.
+  pub async fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) -> Result<(), A::Error>
+  where
+    K: Ord,
+  {
+    for (key, value) in iter {
+      self.insert(key, value).await?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>, Q: Ord + ?Sized> Index<&Q>
+  for BTreeMap<'a, K, V, A>
+where
+  K: Borrow<Q>,
+{
+  type Output = V;
+
+  fn index(&self, key: &Q) -> &V {
+    self.get(key).expect("no entry found for key")
+  }
+}
+
+impl<'a, K: Debug + 'a, V: Debug + 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> Debug
+  for BTreeMap<'a, K, V, A>
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+impl<'b, 'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> IntoIterator
+  for &'b BTreeMap<'a, K, V, A>
+{
+  type Item = (&'b K, &'b V);
+  type IntoIter = Iter<'b, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'b, 'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> IntoIterator
+  for &'b mut BTreeMap<'a, K, V, A>
+{
+  type Item = (&'b K, &'b mut V);
+  type IntoIter = IterMut<'b, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> IntoIterator
+  for BTreeMap<'a, K, V, A>
+{
+  type Item = (K, V);
+  type IntoIter = IntoIter<'a, K, V, A>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter { map: self }
+  }
+}
+
+pub enum Entry<'b, 'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> {
+  Vacant(VacantEntry<'b, 'a, K, V, A>),
+  Occupied(OccupiedEntry<'b, 'a, K, V, A>),
+}
+
+pub struct VacantEntry<'b, 'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> {
+  map: &'b mut BTreeMap<'a, K, V, A>,
+  key: K,
+}
+
+pub struct OccupiedEntry<'b, 'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> {
+  map: &'b mut BTreeMap<'a, K, V, A>,
+  handle: Handle<'a, K, V>,
+}
+
+impl<'b, 'a, K: Ord + 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> Entry<'b, 'a, K, V, A> {
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Vacant(entry) => entry.key(),
+      Entry::Occupied(entry) => entry.key(),
+    }
+  }
+
+  pub async fn or_insert(self, default: V) -> Result<&'b mut V, A::Error> {
+    match self {
+      Entry::Vacant(entry) => entry.insert(default).await,
+      Entry::Occupied(entry) => Ok(entry.into_mut()),
+    }
+  }
+
+  pub async fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> Result<&'b mut V, A::Error> {
+    match self {
+      Entry::Vacant(entry) => entry.insert(default()).await,
+      Entry::Occupied(entry) => Ok(entry.into_mut()),
+    }
+  }
+
+  pub async fn or_default(self) -> Result<&'b mut V, A::Error>
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default).await
+  }
+
+  pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+    if let Entry::Occupied(entry) = &mut self {
+      f(entry.get_mut());
+    }
+    self
+  }
+}
+
+impl<'b, 'a, K: Ord + 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>>
+  VacantEntry<'b, 'a, K, V, A>
+{
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+
+  pub fn into_key(self) -> K {
+    self.key
+  }
+
+  pub async fn insert(self, value: V) -> Result<&'b mut V, A::Error> {
+    let mut value = self.map.insert_vacant(self.key, value).await?;
+    // Safety: the value lives in the map, which stays mutably borrowed
+    Ok(unsafe { value.as_mut() })
+  }
+}
+
+impl<'b, 'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> OccupiedEntry<'b, 'a, K, V, A> {
+  pub fn key(&self) -> &K {
+    // Safety: the handle points into the borrowed map
+    unsafe { self.handle.key_value().0 }
+  }
+
+  pub fn get(&self) -> &V {
+    // Safety: the handle points into the borrowed map
+    unsafe { self.handle.key_value().1 }
+  }
+
+  pub fn get_mut(&mut self) -> &mut V {
+    // Safety: the handle points into the mutably borrowed map
+    unsafe { self.handle.key_value_mut().1 }
+  }
+
+  pub fn into_mut(self) -> &'b mut V {
+    // Safety: the handle points into the mutably borrowed map
+    unsafe { self.handle.key_value_mut().1 }
+  }
+
+  pub fn insert(&mut self, value: V) -> V {
+    mem::replace(self.get_mut(), value)
+  }
+
+  pub fn remove(self) -> V {
+    self.remove_entry().1
+  }
+
+  pub fn remove_entry(self) -> (K, V) {
+    self.map.remove_handle(self.handle)
+  }
+}
+
+/// A pair of handles that are walked towards each other.
+struct RawRange<'a, K, V> {
+  front: Option<Handle<'a, K, V>>,
+  back: Option<Handle<'a, K, V>>,
+}
+
+impl<'a, K, V> RawRange<'a, K, V> {
+  fn empty() -> Self {
+    Self {
+      front: None,
+      back: None,
+    }
+  }
+
+  fn between(front: Option<Handle<'a, K, V>>, back: Option<Handle<'a, K, V>>) -> Self {
+    Self { front, back }
+  }
+
+  fn cast<'b>(self) -> RawRange<'b, K, V>
+  where
+    'a: 'b,
+  {
+    RawRange {
+      front: self.front.map(Handle::cast),
+      back: self.back.map(Handle::cast),
+    }
+  }
+
+  /// Safety: the handles must point into a live tree
+  unsafe fn next(&mut self) -> Option<Handle<'a, K, V>> {
+    let front = self.front?;
+    if self.back == Some(front) {
+      *self = Self::empty();
+    } else {
+      // Safety: upheld by caller
+      self.front = unsafe { front.next() };
+    }
+    Some(front)
+  }
+
+  /// Safety: the handles must point into a live tree
+  unsafe fn next_back(&mut self) -> Option<Handle<'a, K, V>> {
+    let back = self.back?;
+    if self.front == Some(back) {
+      *self = Self::empty();
+    } else {
+      // Safety: upheld by caller
+      self.back = unsafe { back.next_back() };
+    }
+    Some(back)
+  }
+}
+
+pub struct Range<'b, K, V> {
+  raw: RawRange<'b, K, V>,
+  phantom: PhantomData<(&'b K, &'b V)>,
+}
+
+impl<'b, K, V> Iterator for Range<'b, K, V> {
+  type Item = (&'b K, &'b V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    // Safety: the map is borrowed for 'b
+    unsafe { self.raw.next().map(|handle| handle.key_value()) }
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for Range<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    // Safety: the map is borrowed for 'b
+    unsafe { self.raw.next_back().map(|handle| handle.key_value()) }
+  }
+}
+
+impl<'b, K, V> FusedIterator for Range<'b, K, V> {}
+
+pub struct RangeMut<'b, K, V> {
+  raw: RawRange<'b, K, V>,
+  phantom: PhantomData<(&'b K, &'b mut V)>,
+}
+
+impl<'b, K, V> Iterator for RangeMut<'b, K, V> {
+  type Item = (&'b K, &'b mut V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    // Safety: the map is mutably borrowed for 'b, and every value is only yielded once
+    unsafe { self.raw.next().map(|handle| handle.key_value_mut()) }
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for RangeMut<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    // Safety: the map is mutably borrowed for 'b, and every value is only yielded once
+    unsafe { self.raw.next_back().map(|handle| handle.key_value_mut()) }
+  }
+}
+
+impl<'b, K, V> FusedIterator for RangeMut<'b, K, V> {}
+
+pub struct Iter<'b, K, V> {
+  range: Range<'b, K, V>,
+  length: usize,
+}
+
+impl<'b, K, V> Iterator for Iter<'b, K, V> {
+  type Item = (&'b K, &'b V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let item = self.range.next()?;
+    self.length -= 1;
+    Some(item)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.length, Some(self.length))
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for Iter<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let item = self.range.next_back()?;
+    self.length -= 1;
+    Some(item)
+  }
+}
+
+impl<'b, K, V> ExactSizeIterator for Iter<'b, K, V> {}
+impl<'b, K, V> FusedIterator for Iter<'b, K, V> {}
+
+pub struct IterMut<'b, K, V> {
+  range: RangeMut<'b, K, V>,
+  length: usize,
+}
+
+impl<'b, K, V> Iterator for IterMut<'b, K, V> {
+  type Item = (&'b K, &'b mut V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let item = self.range.next()?;
+    self.length -= 1;
+    Some(item)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.length, Some(self.length))
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for IterMut<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let item = self.range.next_back()?;
+    self.length -= 1;
+    Some(item)
+  }
+}
+
+impl<'b, K, V> ExactSizeIterator for IterMut<'b, K, V> {}
+impl<'b, K, V> FusedIterator for IterMut<'b, K, V> {}
+
+pub struct Keys<'b, K, V>(Iter<'b, K, V>);
+
+impl<'b, K, V> Iterator for Keys<'b, K, V> {
+  type Item = &'b K;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.0.next().map(|(key, _)| key)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.0.size_hint()
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for Keys<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.0.next_back().map(|(key, _)| key)
+  }
+}
+
+impl<'b, K, V> ExactSizeIterator for Keys<'b, K, V> {}
+
+pub struct Values<'b, K, V>(Iter<'b, K, V>);
+
+impl<'b, K, V> Iterator for Values<'b, K, V> {
+  type Item = &'b V;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.0.next().map(|(_, value)| value)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.0.size_hint()
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for Values<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.0.next_back().map(|(_, value)| value)
+  }
+}
+
+impl<'b, K, V> ExactSizeIterator for Values<'b, K, V> {}
+
+pub struct ValuesMut<'b, K, V>(IterMut<'b, K, V>);
+
+impl<'b, K, V> Iterator for ValuesMut<'b, K, V> {
+  type Item = &'b mut V;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.0.next().map(|(_, value)| value)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.0.size_hint()
+  }
+}
+
+impl<'b, K, V> DoubleEndedIterator for ValuesMut<'b, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.0.next_back().map(|(_, value)| value)
+  }
+}
+
+impl<'b, K, V> ExactSizeIterator for ValuesMut<'b, K, V> {}
+
+pub struct IntoIter<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> {
+  map: BTreeMap<'a, K, V, A>,
+}
+
+impl<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> Iterator for IntoIter<'a, K, V, A> {
+  type Item = (K, V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.map.pop_first()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.map.len(), Some(self.map.len()))
+  }
+}
+
+impl<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> DoubleEndedIterator
+  for IntoIter<'a, K, V, A>
+{
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.map.pop_last()
+  }
+}
+
+impl<'a, K: 'a, V: 'a, A: Allocator<'a, BTreeNode<'a, K, V>>> ExactSizeIterator
+  for IntoIter<'a, K, V, A>
+{
+}
+
+/// An ordered set based on a [BTreeMap].
+pub struct BTreeSet<'a, T: 'a, A: Allocator<'a, BTreeNode<'a, T, ()>>> {
+  map: BTreeMap<'a, T, (), A>,
+}
+
+impl<'a, T: 'a, A: Allocator<'a, BTreeNode<'a, T, ()>>> BTreeSet<'a, T, A> {
+  pub fn new(allocator: &'a A) -> Self {
+    Self {
+      map: BTreeMap::new(allocator),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.map.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.map.is_empty()
+  }
+
+  pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+  {
+    self.map.contains_key(value)
+  }
+
+  pub fn get<Q: Ord + ?Sized>(&self, value: &Q) -> Option<&T>
+  where
+    T: Borrow<Q>,
+  {
+    self.map.get_key_value(value).map(|(value, _)| value)
+  }
+
+  pub fn first(&self) -> Option<&T> {
+    self.map.first_key_value().map(|(value, _)| value)
+  }
+
+  pub fn last(&self) -> Option<&T> {
+    self.map.last_key_value().map(|(value, _)| value)
+  }
+
+  /// Adds a value to the set, returning whether it was newly inserted.
+  pub async fn insert(&mut self, value: T) -> Result<bool, A::Error>
+  where
+    T: Ord,
+  {
+    match self.map.entry(value) {
+      Entry::Vacant(entry) => entry.insert(()).await.map(|_| true),
+      Entry::Occupied(_) => Ok(false),
+    }
+  }
+
+  pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+  {
+    self.map.remove(value).is_some()
+  }
+
+  pub fn take<Q: Ord + ?Sized>(&mut self, value: &Q) -> Option<T>
+  where
+    T: Borrow<Q>,
+  {
+    self.map.remove_entry(value).map(|(value, _)| value)
+  }
+
+  pub fn pop_first(&mut self) -> Option<T> {
+    self.map.pop_first().map(|(value, _)| value)
+  }
+
+  pub fn pop_last(&mut self) -> Option<T> {
+    self.map.pop_last().map(|(value, _)| value)
+  }
+
+  pub fn clear(&mut self) {
+    self.map.clear();
+  }
+
+  pub fn iter(&self) -> Keys<'_, T, ()> {
+    self.map.keys()
+  }
+
+  pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> SetRange<'_, T>
+  where
+    T: Borrow<Q>,
+  {
+    SetRange(self.map.range(range))
+  }
+
+  /// Inserts every value of `iter`, stopping at the first allocation failure.
+  pub async fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), A::Error>
+  where
+    T: Ord,
+  {
+    for value in iter {
+      self.insert(value).await?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, T: Debug + 'a, A: Allocator<'a, BTreeNode<'a, T, ()>>> Debug for BTreeSet<'a, T, A> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.iter()).finish()
+  }
+}
+
+impl<'b, 'a, T: 'a, A: Allocator<'a, BTreeNode<'a, T, ()>>> IntoIterator
+  for &'b BTreeSet<'a, T, A>
+{
+  type Item = &'b T;
+  type IntoIter = Keys<'b, T, ()>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+pub struct SetRange<'b, T>(Range<'b, T, ()>);
+
+impl<'b, T> Iterator for SetRange<'b, T> {
+  type Item = &'b T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.0.next().map(|(value, _)| value)
+  }
+}
+
+impl<'b, T> DoubleEndedIterator for SetRange<'b, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.0.next_back().map(|(value, _)| value)
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::{
+    alloc::{
+      ForeignAllocator, OutOfMemory, StdAlloc,
+      btree::{BTreeMap, BTreeSet, Entry},
+      strategy::UniqueStrategy,
+    },
+    test_arena,
+  };
+
+  /// Walks keys in a scrambled but deterministic order
+  fn scrambled(count: u32) -> impl Iterator<Item = u32> {
+    (0..count).map(move |index| (index * 7919) % count)
+  }
+
+  #[pollster::test]
+  async fn insert_and_get() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut map = BTreeMap::new(&allocator);
+    for key in scrambled(500) {
+      assert_eq!(map.insert(key, key * 2).await.unwrap(), None);
+    }
+    assert_eq!(map.len(), 500);
+    assert_eq!(map.insert(42, 0).await.unwrap(), Some(84));
+    assert_eq!(map.get(&42), Some(&0));
+    assert_eq!(map.get(&500), None);
+    assert_eq!(map[&7], 14);
+  }
+
+  #[pollster::test]
+  async fn ordered_iteration() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut map = BTreeMap::new(&allocator);
+    map
+      .extend(scrambled(300).map(|key| (key, ())))
+      .await
+      .unwrap();
+
+    assert!(map.keys().copied().eq(0..300));
+    assert!(map.keys().rev().copied().eq((0..300).rev()));
+    assert_eq!(map.iter().len(), 300);
+    assert_eq!(map.first_key_value(), Some((&0, &())));
+    assert_eq!(map.last_key_value(), Some((&299, &())));
+  }
+
+  #[pollster::test]
+  async fn range() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut map = BTreeMap::new(&allocator);
+    map
+      .extend(scrambled(200).map(|key| (key * 2, ())))
+      .await
+      .unwrap();
+
+    assert!(
+      map
+        .range(10..20)
+        .map(|(key, _)| *key)
+        .eq([10, 12, 14, 16, 18])
+    );
+    assert!(
+      map
+        .range(11..=20)
+        .map(|(key, _)| *key)
+        .eq([12, 14, 16, 18, 20])
+    );
+    assert!(
+      map
+        .range(390..)
+        .map(|(key, _)| *key)
+        .eq([390, 392, 394, 396, 398])
+    );
+    assert!(map.range(..5).rev().map(|(key, _)| *key).eq([4, 2, 0]));
+    assert_eq!(map.range(11..12).count(), 0);
+    assert_eq!(map.range(1000..).count(), 0);
+  }
+
+  #[pollster::test]
+  async fn remove() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut map = BTreeMap::new(&allocator);
+    map
+      .extend(scrambled(400).map(|key| (key, key)))
+      .await
+      .unwrap();
+
+    for key in scrambled(400).filter(|key| key % 3 != 0) {
+      assert_eq!(map.remove(&key), Some(key));
+    }
+    assert_eq!(map.remove(&1), None);
+    assert!(map.keys().copied().eq((0..400).filter(|key| key % 3 == 0)));
+
+    assert_eq!(map.pop_first(), Some((0, 0)));
+    assert_eq!(map.pop_last(), Some((399, 399)));
+    while map.pop_first().is_some() {}
+    assert!(map.is_empty());
+    assert_eq!(map.iter().next(), None);
+  }
+
+  #[pollster::test]
+  async fn entry() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut map = BTreeMap::new(&allocator);
+    for word in ["b", "a", "b", "c", "b"] {
+      *map.entry(word).or_insert(0).await.unwrap() += 1;
+    }
+    assert!(map.iter().eq([(&"a", &1), (&"b", &3), (&"c", &1)]));
+
+    let Entry::Occupied(entry) = map.entry("b") else {
+      panic!("entry should be occupied");
+    };
+    assert_eq!(entry.remove(), 3);
+    assert!(matches!(map.entry("b"), Entry::Vacant(_)));
+  }
+
+  #[pollster::test]
+  async fn insert_out_of_memory() {
+    let arena = test_arena!(UniqueStrategy, 0).await;
+    let mut map = BTreeMap::new(&arena);
+    assert!(matches!(map.insert(1, 1).await, Err(OutOfMemory)));
+    assert!(map.is_empty());
+  }
+
+  #[pollster::test]
+  async fn set() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut set = BTreeSet::new(&allocator);
+    assert!(set.insert(3).await.unwrap());
+    assert!(set.insert(1).await.unwrap());
+    assert!(!set.insert(3).await.unwrap());
+    assert!(set.iter().copied().eq([1, 3]));
+    assert!(set.remove(&1));
+    assert!(!set.contains(&1));
+    assert_eq!(set.pop_last(), Some(3));
+  }
+}
@@ -0,0 +1,211 @@
+use core::{
+  alloc::Layout,
+  fmt::Debug,
+  mem,
+  ops::{Deref, DerefMut},
+};
+
+use crate::{
+  alloc::{GrowthStrategy, SliceAllocator, UnsizedMaybeUninit, strategy::Strategy},
+  types::deque::{BaseDequeHeader, SliceDeque},
+};
+
+#[repr(C)]
+pub struct VecDeque<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceDeque<T>>>
+where
+  S::Handle<'a, SliceDeque<T>>: Deref<Target = SliceDeque<T>>,
+{
+  allocator: &'a A,
+  growth_strategy: GrowthStrategy,
+  inner: S::Handle<'a, SliceDeque<T>>,
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceDeque<T>>> VecDeque<'a, T, S, A>
+where
+  S::Handle<'a, SliceDeque<T>>: Deref<Target = SliceDeque<T>>,
+{
+  pub async fn new(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceDeque<T>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceDeque<T>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceDeque<T>>>: SliceDst,
+  {
+    Self::with_capacity(allocator, strategy, 0).await
+  }
+
+  pub async fn with_capacity(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceDeque<T>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceDeque<T>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceDeque<T>>>: SliceDst,
+  {
+    let mut handle: S::UninitHandle<'a, UnsizedMaybeUninit<SliceDeque<T>>> =
+      allocator.reserve_slice::<S>(capacity).await?;
+
+    handle.header.write(BaseDequeHeader::new());
+
+    Ok(Self {
+      allocator,
+      growth_strategy: strategy,
+      inner: unsafe { S::UninitHandle::assume_init(handle) },
+    })
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceDeque<T>>> VecDeque<'a, T, S, A>
+where
+  S::Handle<'a, SliceDeque<T>>: DerefMut<Target = SliceDeque<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceDeque<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceDeque<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceDeque<T>>>: SliceDst,
+{
+  pub async fn grow(&mut self, additional: usize) -> Result<(), A::Error> {
+    let capacity = self
+      .growth_strategy
+      .calculate_new_capacity(self.inner.capacity(), additional)
+      .expect("deque is full");
+
+    self.resize(capacity).await
+  }
+
+  /// Moves the contents of the deque into a new buffer with room for `to_capacity` values.
+  ///
+  /// Panics if `to_capacity` is smaller than the length of the deque.
+  pub async fn resize(&mut self, to_capacity: usize) -> Result<(), A::Error> {
+    assert!(
+      to_capacity >= self.inner.len(),
+      "cannot resize deque below its length"
+    );
+
+    let new: S::UninitHandle<'a, UnsizedMaybeUninit<SliceDeque<T>>> =
+      self.allocator.reserve_slice::<S>(to_capacity).await?;
+
+    // the contents need to start at the beginning of the buffer, so they fit into the new one
+    self.inner.make_contiguous();
+
+    let old_ptr = S::Handle::as_value_ptr(&self.inner);
+    let new_ptr = S::UninitHandle::as_value_ptr(&new);
+
+    let new = unsafe {
+      // Safety: the old layout is at most as large as the new one, non-overlapping ptrs
+      let old_size = Layout::for_value_raw(old_ptr.cast_const()).size();
+      let new_size = Layout::for_value_raw(new_ptr.cast_const()).size();
+      old_ptr
+        .cast::<u8>()
+        .copy_to_nonoverlapping(new_ptr.cast(), old_size.min(new_size));
+      // Safety: initialized by copying from self
+      S::UninitHandle::assume_init(new)
+    };
+
+    mem::drop(mem::replace(&mut self.inner, new));
+
+    Ok(())
+  }
+
+  pub async fn push_back_resize(&mut self, value: T) -> Result<(), A::Error> {
+    if self.inner.is_full() {
+      self.grow(1).await?;
+    }
+
+    let Ok(_) = self.inner.push_back(value) else {
+      unreachable!("not enough space for value");
+    };
+
+    Ok(())
+  }
+
+  pub async fn push_front_resize(&mut self, value: T) -> Result<(), A::Error> {
+    if self.inner.is_full() {
+      self.grow(1).await?;
+    }
+
+    let Ok(_) = self.inner.push_front(value) else {
+      unreachable!("not enough space for value");
+    };
+
+    Ok(())
+  }
+
+  pub async fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), A::Error> {
+    let mut iter = iter.into_iter();
+
+    while let Some(item) = iter.next() {
+      if self.inner.is_full() {
+        let (lower_bound, _) = iter.size_hint();
+        self.grow(lower_bound + 1).await?;
+      }
+
+      let Ok(_) = self.inner.push_back(item) else {
+        unreachable!("not enough space for value");
+      };
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceDeque<T>>> Deref for VecDeque<'a, T, S, A>
+where
+  S::Handle<'a, SliceDeque<T>>: DerefMut<Target = SliceDeque<T>>,
+{
+  type Target = SliceDeque<T>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceDeque<T>>> DerefMut
+  for VecDeque<'a, T, S, A>
+where
+  S::Handle<'a, SliceDeque<T>>: DerefMut<Target = SliceDeque<T>>,
+{
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.inner
+  }
+}
+
+impl<'a, T: Debug + 'a, S: Strategy, A: SliceAllocator<'a, SliceDeque<T>>> Debug
+  for VecDeque<'a, T, S, A>
+where
+  S::Handle<'a, SliceDeque<T>>: DerefMut<Target = SliceDeque<T>>,
+{
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.deref().fmt(f)
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::alloc::{
+    ForeignAllocator, GrowthStrategy, StdAlloc, deque::VecDeque, strategy::UniqueStrategy,
+  };
+
+  #[pollster::test]
+  async fn grow_while_wrapped() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut deque =
+      VecDeque::<u32, UniqueStrategy, _>::with_capacity(&allocator, GrowthStrategy::Exponential, 3)
+        .await
+        .unwrap();
+    deque.push_back(2).unwrap();
+    deque.push_back(3).unwrap();
+    deque.push_front(1).unwrap();
+    assert!(deque.is_full());
+
+    deque.push_front_resize(0).await.unwrap();
+    deque.push_back_resize(4).await.unwrap();
+    assert_eq!(deque.capacity(), 6);
+    assert!(deque.iter().copied().eq(0..5));
+
+    deque.extend(5..20).await.unwrap();
+    assert!(deque.iter().copied().eq(0..20));
+    assert_eq!(deque.pop_front(), Some(0));
+    assert_eq!(deque.pop_back(), Some(19));
+  }
+}
@@ -0,0 +1,218 @@
+use core::{
+  fmt::{self, Debug},
+  mem,
+  ops::{Deref, DerefMut},
+  slice,
+};
+
+use crate::{
+  alloc::{
+    GrowthStrategy, LayoutAllocator, SliceAllocator, TryCloneIn, UnsizedMaybeUninit,
+    strategy::Strategy, vec::Vec,
+  },
+  types::{
+    heap::{Compare, NaturalOrder, PeekMut, heapify, sift_down, sift_up, sort_heap},
+    vec::SliceVec,
+  },
+};
+
+/// A priority queue stored in a [Vec], which pops the greatest value according to `C` first.
+pub struct BinaryHeap<
+  'a,
+  T: 'a,
+  S: Strategy,
+  A: SliceAllocator<'a, SliceVec<T>>,
+  C: Compare<T> = NaturalOrder,
+> where
+  S::Handle<'a, SliceVec<T>>: Deref<Target = SliceVec<T>>,
+{
+  data: Vec<'a, T, S, A>,
+  compare: C,
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>, C: Compare<T> + Default>
+  BinaryHeap<'a, T, S, A, C>
+where
+  S::Handle<'a, SliceVec<T>>: Deref<Target = SliceVec<T>>,
+{
+  pub async fn new(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+  {
+    Self::with_capacity(allocator, strategy, 0).await
+  }
+
+  pub async fn with_capacity(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+  {
+    Ok(Self {
+      data: Vec::with_capacity(allocator, strategy, capacity).await?,
+      compare: C::default(),
+    })
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>, C: Compare<T>>
+  BinaryHeap<'a, T, S, A, C>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+{
+  /// Turns the values of `data` into a heap.
+  pub fn from_vec(mut data: Vec<'a, T, S, A>, compare: C) -> Self {
+    heapify(&mut data, &compare);
+    Self { data, compare }
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.data.capacity()
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  /// Returns the greatest value in the heap.
+  pub fn peek(&self) -> Option<&T> {
+    self.data.first()
+  }
+
+  /// Returns a mutable reference to the greatest value in the heap, which is moved to its new
+  /// position once the reference is dropped.
+  pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+    if self.data.is_empty() {
+      return None;
+    }
+
+    // Safety: checked that the heap isn't empty
+    Some(unsafe { PeekMut::new(&mut self.data, &self.compare) })
+  }
+
+  /// Removes the greatest value from the heap.
+  pub fn pop(&mut self) -> Option<T> {
+    let mut value = self.data.pop()?;
+    if !self.data.is_empty() {
+      mem::swap(&mut value, &mut self.data[0]);
+      sift_down(&mut self.data, 0, &self.compare);
+    }
+
+    Some(value)
+  }
+
+  pub fn clear(&mut self) {
+    self.data.clear();
+  }
+
+  /// Iterates over the values of the heap in an arbitrary order.
+  pub fn iter(&self) -> slice::Iter<'_, T> {
+    self.data.iter()
+  }
+
+  pub fn as_slice(&self) -> &[T] {
+    &self.data
+  }
+
+  pub fn into_vec(self) -> Vec<'a, T, S, A> {
+    self.data
+  }
+
+  /// Returns the values of the heap sorted from least to greatest.
+  pub fn into_sorted_vec(mut self) -> Vec<'a, T, S, A> {
+    sort_heap(&mut self.data, &self.compare);
+    self.data
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>, C: Compare<T>>
+  BinaryHeap<'a, T, S, A, C>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  /// Pushes a value into the heap, growing it if it's full.
+  pub async fn push(&mut self, value: T) -> Result<(), A::Error> {
+    self.data.push_resize(value).await?;
+    let index = self.data.len() - 1;
+    sift_up(&mut self.data, index, &self.compare);
+
+    Ok(())
+  }
+
+  pub async fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), A::Error> {
+    for value in iter {
+      self.push(value).await?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, T: TryCloneIn<'a, A> + 'a, S: Strategy, A, C: Compare<T> + Clone> TryCloneIn<'a, A>
+  for BinaryHeap<'a, T, S, A, C>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    Ok(Self {
+      data: self.data.try_clone_in(allocator).await?,
+      compare: self.compare.clone(),
+    })
+  }
+}
+
+impl<'a, T: Debug + 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>, C: Compare<T>> Debug
+  for BinaryHeap<'a, T, S, A, C>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::{
+    alloc::{
+      ForeignAllocator, GrowthStrategy, StdAlloc, heap::BinaryHeap, strategy::UniqueStrategy,
+    },
+    types::heap::ReverseOrder,
+  };
+
+  #[pollster::test]
+  async fn push_pop() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut heap = BinaryHeap::<u32, UniqueStrategy, _, ReverseOrder>::new(
+      &allocator,
+      GrowthStrategy::Exponential,
+    )
+    .await
+    .unwrap();
+    heap.extend([5, 3, 8, 1, 9, 2]).await.unwrap();
+
+    assert_eq!(heap.peek(), Some(&1));
+    *heap.peek_mut().unwrap() = 7;
+    assert_eq!(heap.pop(), Some(2));
+    assert_eq!(heap.pop(), Some(3));
+    assert_eq!(&**heap.into_sorted_vec(), &[9, 8, 7, 5]);
+  }
+}
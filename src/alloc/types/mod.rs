@@ -1,8 +1,16 @@
 use core::cmp;
 
+pub mod bit_vec;
+pub mod btree;
+pub mod deque;
+pub mod heap;
+pub mod rope;
+pub mod slot_map;
+pub mod small_vec;
 pub mod string;
 pub mod vec;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum GrowthStrategy {
   /// Grow the capacity of the [Vec] by exactly the amount that is needed
   Exact,
@@ -0,0 +1,855 @@
+use core::{
+  fmt::{self, Debug, Display},
+  mem,
+  ops::{AddAssign, Deref, Range},
+  ptr::{self, NonNull},
+  str,
+};
+
+use crate::{
+  alloc::{
+    SliceAllocator, UnsizedMaybeUninit,
+    strategy::{Strategy, Unique, UniqueStrategy},
+  },
+  types::vec::FixedVec,
+};
+
+/// The most bytes stored in a single leaf.
+const MAX_LEAF: usize = 1024;
+/// Inserted text is split into chunks of at most this many bytes, so that a leaf with a chunk
+/// inserted into it can always be split into two leaves that fit.
+const MAX_CHUNK: usize = MAX_LEAF / 2;
+const MAX_CHILDREN: usize = 8;
+const MIN_CHILDREN: usize = MAX_CHILDREN / 2;
+/// Enough for `MIN_CHILDREN.pow(MAX_DEPTH - 1)` leaves.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+struct Summary {
+  bytes: usize,
+  chars: usize,
+  newlines: usize,
+}
+
+impl Summary {
+  fn of(text: &str) -> Self {
+    Self {
+      bytes: text.len(),
+      chars: text.chars().count(),
+      newlines: text.bytes().filter(|&byte| byte == b'\n').count(),
+    }
+  }
+}
+
+impl AddAssign for Summary {
+  fn add_assign(&mut self, other: Self) {
+    self.bytes += other.bytes;
+    self.chars += other.chars;
+    self.newlines += other.newlines;
+  }
+}
+
+/// Returns the byte offset of the char at `chars`, or the length of `text` if it's past the end.
+fn char_to_byte_in(text: &str, chars: usize) -> usize {
+  text
+    .char_indices()
+    .nth(chars)
+    .map_or(text.len(), |(byte, _)| byte)
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+  if index >= text.len() {
+    return text.len();
+  }
+
+  while !text.is_char_boundary(index) {
+    index -= 1;
+  }
+  index
+}
+
+/// A piece of the text of a [Rope]. Leaves are never modified once they're allocated, edits
+/// replace them instead.
+#[derive(SliceDst)]
+#[repr(C)]
+pub struct RopeLeaf {
+  summary: Summary,
+  text: str,
+}
+
+impl RopeLeaf {
+  pub fn as_str(&self) -> &str {
+    &self.text
+  }
+}
+
+impl Debug for RopeLeaf {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    Debug::fmt(&self.text, f)
+  }
+}
+
+enum Children<'a, S: Strategy> {
+  Leaves(FixedVec<S::Handle<'a, RopeLeaf>, MAX_CHILDREN>),
+  Nodes(FixedVec<Unique<'a, RopeNode<'a, S>>, MAX_CHILDREN>),
+}
+
+enum Child<'a, S: Strategy> {
+  Leaf(S::Handle<'a, RopeLeaf>),
+  Node(Unique<'a, RopeNode<'a, S>>),
+}
+
+/// Every leaf of a rope is at the same depth, so a node either holds only leaves or only other
+/// nodes.
+#[doc(hidden)]
+pub struct RopeNode<'a, S: Strategy> {
+  summaries: FixedVec<Summary, MAX_CHILDREN>,
+  children: Children<'a, S>,
+}
+
+impl<'a, S: Strategy> RopeNode<'a, S>
+where
+  S::Handle<'a, RopeLeaf>: Deref<Target = RopeLeaf>,
+{
+  fn new(leaves: bool) -> Self {
+    Self {
+      summaries: FixedVec::new(),
+      children: if leaves {
+        Children::Leaves(FixedVec::new())
+      } else {
+        Children::Nodes(FixedVec::new())
+      },
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.summaries.len()
+  }
+
+  fn is_full(&self) -> bool {
+    self.summaries.is_full()
+  }
+
+  fn total(&self) -> Summary {
+    let mut total = Summary::default();
+    for summary in self.summaries.iter() {
+      total += *summary;
+    }
+    total
+  }
+
+  /// Returns the first child whose end satisfies `is_in`, or the last one, along with the
+  /// summary of every child before it.
+  fn find_child(&self, is_in: impl Fn(Summary) -> bool) -> (usize, Summary) {
+    let mut skipped = Summary::default();
+    for (index, summary) in self.summaries.iter().enumerate() {
+      let mut end = skipped;
+      end += *summary;
+      if index + 1 == self.len() || is_in(end) {
+        return (index, skipped);
+      }
+      skipped = end;
+    }
+
+    (0, skipped)
+  }
+
+  fn leaf(&self, index: usize) -> &RopeLeaf {
+    let Children::Leaves(leaves) = &self.children else {
+      unreachable!("node doesn't hold leaves");
+    };
+    &leaves[index]
+  }
+
+  fn child_summary(child: &Child<'a, S>) -> Summary {
+    match child {
+      Child::Leaf(leaf) => leaf.summary,
+      Child::Node(node) => node.total(),
+    }
+  }
+
+  fn insert_child(&mut self, index: usize, child: Child<'a, S>) {
+    let Ok(_) = self.summaries.insert(index, Self::child_summary(&child)) else {
+      unreachable!("node is full");
+    };
+
+    let inserted = match (&mut self.children, child) {
+      (Children::Leaves(leaves), Child::Leaf(leaf)) => leaves.insert(index, leaf).is_ok(),
+      (Children::Nodes(nodes), Child::Node(node)) => nodes.insert(index, node).is_ok(),
+      _ => unreachable!("every leaf is at the same depth"),
+    };
+    debug_assert!(inserted);
+  }
+
+  fn push_child(&mut self, child: Child<'a, S>) {
+    self.insert_child(self.len(), child);
+  }
+
+  fn remove_child(&mut self, index: usize) -> Child<'a, S> {
+    self.summaries.remove(index);
+    match &mut self.children {
+      Children::Leaves(leaves) => Child::Leaf(leaves.remove(index)),
+      Children::Nodes(nodes) => Child::Node(nodes.remove(index)),
+    }
+  }
+
+  fn replace_leaf(&mut self, index: usize, leaf: S::Handle<'a, RopeLeaf>) {
+    let Children::Leaves(leaves) = &mut self.children else {
+      unreachable!("node doesn't hold leaves");
+    };
+    self.summaries[index] = leaf.summary;
+    mem::drop(mem::replace(&mut leaves[index], leaf));
+  }
+
+  /// Recalculates the summary of the child at `index`, after it was modified.
+  fn refresh(&mut self, index: usize) {
+    self.summaries[index] = match &self.children {
+      Children::Leaves(leaves) => leaves[index].summary,
+      Children::Nodes(nodes) => nodes[index].total(),
+    };
+  }
+
+  /// Moves every child from `at` onwards to the end of `other`.
+  fn split_off_into(&mut self, at: usize, other: &mut Self) {
+    while self.len() > at {
+      other.push_child(self.remove_child(at));
+    }
+  }
+
+  fn node_mut(&mut self, index: usize) -> NonNull<Self> {
+    let Children::Nodes(nodes) = &mut self.children else {
+      unreachable!("node holds leaves");
+    };
+    NonNull::from(&mut *nodes[index])
+  }
+
+  fn two_nodes_mut(&mut self, left: usize) -> (&mut Self, &mut Self) {
+    let Children::Nodes(nodes) = &mut self.children else {
+      unreachable!("node holds leaves");
+    };
+    let (left_nodes, right_nodes) = nodes.split_at_mut(left + 1);
+    (&mut left_nodes[left], &mut right_nodes[0])
+  }
+}
+
+impl<'a, S: Strategy> Drop for RopeNode<'a, S> {
+  fn drop(&mut self) {
+    // FixedVec doesn't drop its values
+    match &mut self.children {
+      Children::Leaves(leaves) => leaves.clear(),
+      Children::Nodes(nodes) => nodes.clear(),
+    }
+  }
+}
+
+/// An allocator for both the leaves and the nodes of a [Rope].
+pub trait RopeAllocator<'a, S: Strategy + 'a>:
+  SliceAllocator<'a, RopeLeaf>
+  + Allocator<'a, RopeNode<'a, S>, Error = <Self as SliceAllocator<'a, RopeLeaf>>::Error>
+{
+}
+
+impl<'a, S: Strategy + 'a, A> RopeAllocator<'a, S> for A where
+  A: SliceAllocator<'a, RopeLeaf>
+    + Allocator<'a, RopeNode<'a, S>, Error = <A as SliceAllocator<'a, RopeLeaf>>::Error>
+{
+}
+
+type RopeError<'a, A> = <A as SliceAllocator<'a, RopeLeaf>>::Error;
+
+type Path<'a, S> = FixedVec<(NonNull<RopeNode<'a, S>>, usize), MAX_DEPTH>;
+
+/// A string stored as a balanced tree of immutable leaves, which can be edited anywhere in
+/// logarithmic time without moving the rest of the text.
+///
+/// Leaves are allocated with `S`, and nodes are always [Unique].
+pub struct Rope<'a, S: Strategy + 'a, A: RopeAllocator<'a, S>> {
+  allocator: &'a A,
+  root: Option<Unique<'a, RopeNode<'a, S>>>,
+}
+
+impl<'a, S: Strategy + 'a, A: RopeAllocator<'a, S>> Rope<'a, S, A>
+where
+  S::Handle<'a, RopeLeaf>: Deref<Target = RopeLeaf>,
+{
+  pub fn new(allocator: &'a A) -> Self {
+    Self {
+      allocator,
+      root: None,
+    }
+  }
+
+  fn summary(&self) -> Summary {
+    self
+      .root
+      .as_ref()
+      .map(|root| root.total())
+      .unwrap_or_default()
+  }
+
+  pub fn len_bytes(&self) -> usize {
+    self.summary().bytes
+  }
+
+  pub fn len_chars(&self) -> usize {
+    self.summary().chars
+  }
+
+  /// Counts the lines of the rope, which is one more than the number of newlines.
+  pub fn len_lines(&self) -> usize {
+    self.summary().newlines + 1
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len_bytes() == 0
+  }
+
+  /// Walks down to the first leaf whose end satisfies `is_in`, returning it along with the
+  /// summary of the text before it.
+  fn find_leaf(&self, is_in: impl Fn(Summary) -> bool) -> Option<(&RopeLeaf, Summary)> {
+    let mut node = self.root.as_deref()?;
+    let mut before = Summary::default();
+    loop {
+      let (index, skipped) = node.find_child(|mut end| {
+        end += before;
+        is_in(end)
+      });
+      if node.len() == 0 {
+        return None;
+      }
+      before += skipped;
+
+      match &node.children {
+        Children::Leaves(leaves) => return Some((&leaves[index], before)),
+        Children::Nodes(nodes) => node = &nodes[index],
+      }
+    }
+  }
+
+  fn leaf_at_char(&self, char_index: usize) -> Option<(&RopeLeaf, Summary)> {
+    self.find_leaf(|end| char_index < end.chars)
+  }
+
+  /// Panics if `char_index` is out of bounds.
+  pub fn char(&self, char_index: usize) -> char {
+    let length = self.len_chars();
+    assert!(
+      char_index < length,
+      "char index {char_index} out of bounds for length {length}"
+    );
+
+    let (leaf, before) = self.leaf_at_char(char_index).unwrap();
+    leaf
+      .as_str()
+      .chars()
+      .nth(char_index - before.chars)
+      .unwrap()
+  }
+
+  /// Panics if `char_index > len_chars`.
+  pub fn char_to_byte(&self, char_index: usize) -> usize {
+    let length = self.len_chars();
+    assert!(
+      char_index <= length,
+      "char index {char_index} out of bounds for length {length}"
+    );
+
+    let Some((leaf, before)) = self.leaf_at_char(char_index) else {
+      return 0;
+    };
+    before.bytes + char_to_byte_in(leaf.as_str(), char_index - before.chars)
+  }
+
+  /// Returns the line that the char at `char_index` is on.
+  ///
+  /// Panics if `char_index > len_chars`.
+  pub fn char_to_line(&self, char_index: usize) -> usize {
+    let length = self.len_chars();
+    assert!(
+      char_index <= length,
+      "char index {char_index} out of bounds for length {length}"
+    );
+
+    let Some((leaf, before)) = self.leaf_at_char(char_index) else {
+      return 0;
+    };
+    let text = leaf.as_str();
+    let end = char_to_byte_in(text, char_index - before.chars);
+    before.newlines + text[..end].bytes().filter(|&byte| byte == b'\n').count()
+  }
+
+  /// Returns the char index that `line` starts at.
+  ///
+  /// Panics if `line >= len_lines`.
+  pub fn line_to_char(&self, line: usize) -> usize {
+    let lines = self.len_lines();
+    assert!(line < lines, "line {line} out of bounds for {lines} lines");
+    if line == 0 {
+      return 0;
+    }
+
+    let (leaf, before) = self.find_leaf(|end| line <= end.newlines).unwrap();
+    let newline = line - before.newlines;
+    let (chars, _) = leaf
+      .as_str()
+      .chars()
+      .enumerate()
+      .filter(|(_, char)| *char == '\n')
+      .nth(newline - 1)
+      .unwrap();
+    before.chars + chars + 1
+  }
+
+  /// Iterates over the leaves of the rope, in order.
+  pub fn chunks(&self) -> Chunks<'_, 'a, S> {
+    let mut stack = FixedVec::new();
+    if let Some(root) = &self.root {
+      let Ok(_) = stack.push((&**root, 0)) else {
+        unreachable!("stack is empty");
+      };
+    }
+
+    Chunks { stack }
+  }
+
+  pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+    self.chunks().flat_map(str::chars)
+  }
+
+  /// Walks down to the leaf containing `char_index`, returning every node on the way along with
+  /// the summary of the text before the leaf. With `bias_end`, an index between two leaves
+  /// resolves to the end of the first one.
+  ///
+  /// The root must exist.
+  fn path_to_char(&mut self, char_index: usize, bias_end: bool) -> (Path<'a, S>, Summary) {
+    let mut path = FixedVec::new();
+    let mut node = NonNull::from(&mut **self.root.as_mut().unwrap());
+    let mut before = Summary::default();
+    loop {
+      // Safety: every node in the path is owned by the rope, which is borrowed mutably
+      let node_ref = unsafe { node.as_mut() };
+      let (index, skipped) = node_ref.find_child(|end| {
+        let end = before.chars + end.chars;
+        char_index < end || (bias_end && char_index == end)
+      });
+      before += skipped;
+
+      let Ok(_) = path.push((node, index)) else {
+        unreachable!("rope is too deep");
+      };
+      match node_ref.children {
+        Children::Leaves(_) => return (path, before),
+        Children::Nodes(_) => node = node_ref.node_mut(index),
+      }
+    }
+  }
+
+  /// Recalculates the summaries on `path` above the bottom node.
+  fn refresh_path(path: &Path<'a, S>) {
+    for &(node, index) in path.iter().rev().skip(1) {
+      // Safety: every node in the path is owned by the rope, which is borrowed mutably
+      unsafe { (*node.as_ptr()).refresh(index) };
+    }
+  }
+
+  /// Merges or refills nodes on `path` that have too few children after one was removed from
+  /// the bottom node, then shrinks the root.
+  fn rebalance(&mut self, path: &Path<'a, S>) {
+    for level in (1..path.len()).rev() {
+      let (node, _) = path[level];
+      let (parent, index) = path[level - 1];
+      // Safety: every node in the path is owned by the rope, which is borrowed mutably, and the
+      // loop stops using a node once it's removed
+      let (node, parent) = unsafe { (&mut *node.as_ptr(), &mut *parent.as_ptr()) };
+      if node.len() >= MIN_CHILDREN {
+        break;
+      }
+
+      if node.len() == 0 {
+        parent.remove_child(index);
+        continue;
+      }
+      if parent.len() == 1 {
+        continue;
+      }
+
+      let left_index = if index + 1 < parent.len() {
+        index
+      } else {
+        index - 1
+      };
+      let (left, right) = parent.two_nodes_mut(left_index);
+      if left.len() + right.len() <= MAX_CHILDREN {
+        right.split_off_into(0, left);
+        parent.remove_child(left_index + 1);
+        parent.refresh(left_index);
+        continue;
+      }
+
+      if left.len() < MIN_CHILDREN {
+        left.push_child(right.remove_child(0));
+      } else {
+        right.insert_child(0, left.remove_child(left.len() - 1));
+      }
+      parent.refresh(left_index);
+      parent.refresh(left_index + 1);
+      break;
+    }
+
+    while let Some(root) = &mut self.root {
+      match &mut root.children {
+        Children::Leaves(leaves) if leaves.is_empty() => self.root = None,
+        Children::Nodes(nodes) if nodes.len() <= 1 => {
+          self.root = nodes.pop();
+        }
+        _ => break,
+      }
+    }
+  }
+
+  /// Unlinks every leaf of the rope.
+  pub fn clear(&mut self) {
+    self.root = None;
+  }
+}
+
+impl<'a, S: Strategy + 'a, A: RopeAllocator<'a, S>> Rope<'a, S, A>
+where
+  S::Handle<'a, RopeLeaf>: Deref<Target = RopeLeaf>,
+  S::Data<'a, UnsizedMaybeUninit<RopeLeaf>>: SliceDst,
+{
+  pub async fn from_str(allocator: &'a A, text: &str) -> Result<Self, RopeError<'a, A>> {
+    let mut rope = Self::new(allocator);
+    rope.insert(0, text).await?;
+
+    Ok(rope)
+  }
+
+  async fn allocate_node(
+    &self,
+    leaves: bool,
+  ) -> Result<Unique<'a, RopeNode<'a, S>>, RopeError<'a, A>> {
+    self
+      .allocator
+      .take::<UniqueStrategy>(RopeNode::new(leaves))
+      .await
+  }
+
+  async fn allocate_leaf(&self, text: &str) -> Result<S::Handle<'a, RopeLeaf>, RopeError<'a, A>> {
+    let handle = self.allocator.reserve_slice::<S>(text.len()).await?;
+    unsafe {
+      let ptr = S::UninitHandle::as_value_ptr(&handle);
+      ptr.cast::<RopeLeafHeader>().write(RopeLeafHeader {
+        summary: Summary::of(text),
+        text_header: (),
+      });
+      let (ptr, _) = ptr.to_raw_parts();
+      // Safety: the slice is as long as the text
+      RopeLeaf::addr_of_slice(ptr::from_raw_parts_mut(ptr, text.len()))
+        .cast::<u8>()
+        .copy_from_nonoverlapping(text.as_ptr(), text.len());
+    }
+
+    // Safety: the header and text were initialized above
+    Ok(unsafe { S::UninitHandle::assume_init(handle) })
+  }
+
+  /// Inserts `text` before the char at `char_index`.
+  ///
+  /// Panics if `char_index > len_chars`.
+  pub async fn insert(&mut self, char_index: usize, text: &str) -> Result<(), RopeError<'a, A>> {
+    let length = self.len_chars();
+    assert!(
+      char_index <= length,
+      "char index {char_index} out of bounds for length {length}"
+    );
+
+    let mut char_index = char_index;
+    let mut rest = text;
+    while !rest.is_empty() {
+      let chunk = &rest[..floor_char_boundary(rest, MAX_CHUNK)];
+      rest = &rest[chunk.len()..];
+
+      self.insert_chunk(char_index, chunk).await?;
+      char_index += chunk.chars().count();
+    }
+
+    Ok(())
+  }
+
+  /// Inserts at most [MAX_CHUNK] bytes of text. Everything is allocated before the tree is
+  /// modified, so the rope is left unchanged if allocation fails.
+  async fn insert_chunk(&mut self, char_index: usize, chunk: &str) -> Result<(), RopeError<'a, A>> {
+    if self.root.is_none() {
+      self.root = Some(self.allocate_node(true).await?);
+    }
+
+    let (path, before) = self.path_to_char(char_index, true);
+    let (bottom, index) = *path.last().unwrap();
+    // Safety: every node in the path is owned by the rope, which is borrowed mutably
+    let bottom = unsafe { &mut *bottom.as_ptr() };
+
+    let (left, right) = if bottom.len() == 0 {
+      (self.allocate_leaf(chunk).await?, None)
+    } else {
+      let old = bottom.leaf(index).as_str();
+      let split = char_to_byte_in(old, char_index - before.chars);
+      let length = old.len() + chunk.len();
+
+      let mut buffer = [0; MAX_LEAF + MAX_CHUNK];
+      buffer[..split].copy_from_slice(&old.as_bytes()[..split]);
+      buffer[split..split + chunk.len()].copy_from_slice(chunk.as_bytes());
+      buffer[split + chunk.len()..length].copy_from_slice(&old.as_bytes()[split..]);
+      // Safety: joined at char boundaries
+      let text = unsafe { str::from_utf8_unchecked(&buffer[..length]) };
+
+      if length <= MAX_LEAF {
+        (self.allocate_leaf(text).await?, None)
+      } else {
+        let middle = floor_char_boundary(text, length / 2);
+        let left = self.allocate_leaf(&text[..middle]).await?;
+        (left, Some(self.allocate_leaf(&text[middle..]).await?))
+      }
+    };
+
+    // a new sibling splits every full node on its way up
+    let mut splits = 0;
+    if right.is_some() {
+      for &(node, _) in path.iter().rev() {
+        // Safety: every node in the path is owned by the rope
+        if !unsafe { node.as_ref() }.is_full() {
+          break;
+        }
+        splits += 1;
+      }
+    }
+
+    let mut spare = FixedVec::<_, MAX_DEPTH>::new();
+    for level in (0..splits).rev() {
+      let Ok(_) = spare.push(self.allocate_node(level == 0).await?) else {
+        unreachable!("rope is too deep");
+      };
+    }
+    let new_root = if splits == path.len() {
+      Some(self.allocate_node(false).await?)
+    } else {
+      None
+    };
+
+    let mut sibling = None;
+    if bottom.len() == 0 {
+      bottom.push_child(Child::Leaf(left));
+    } else {
+      bottom.replace_leaf(index, left);
+      sibling = right.map(Child::Leaf);
+    }
+
+    for (level, &(node, index)) in path.iter().enumerate().rev() {
+      // Safety: every node in the path is owned by the rope, which is borrowed mutably
+      let node = unsafe { &mut *node.as_ptr() };
+      if level + 1 < path.len() {
+        node.refresh(index);
+      }
+
+      let Some(child) = sibling.take() else {
+        continue;
+      };
+      if !node.is_full() {
+        node.insert_child(index + 1, child);
+        continue;
+      }
+
+      let mut split = spare.pop().unwrap();
+      node.split_off_into(MIN_CHILDREN, &mut split);
+      if index < MIN_CHILDREN {
+        node.insert_child(index + 1, child);
+      } else {
+        split.insert_child(index + 1 - MIN_CHILDREN, child);
+      }
+      sibling = Some(Child::Node(split));
+    }
+
+    if let Some(sibling) = sibling {
+      let mut root = new_root.unwrap();
+      root.push_child(Child::Node(self.root.take().unwrap()));
+      root.push_child(sibling);
+      self.root = Some(root);
+    }
+
+    Ok(())
+  }
+
+  /// Removes the chars in `range`. Allocates a new leaf for each end of the range that falls in
+  /// the middle of one.
+  ///
+  /// Panics if the range is out of bounds.
+  pub async fn remove(&mut self, range: Range<usize>) -> Result<(), RopeError<'a, A>> {
+    let length = self.len_chars();
+    assert!(
+      range.start <= range.end && range.end <= length,
+      "range {range:?} out of bounds for length {length}"
+    );
+
+    let mut remaining = range.end - range.start;
+    while remaining > 0 {
+      let (path, before) = self.path_to_char(range.start, false);
+      let (bottom, index) = *path.last().unwrap();
+      // Safety: every node in the path is owned by the rope, which is borrowed mutably
+      let bottom = unsafe { &mut *bottom.as_ptr() };
+
+      let leaf = bottom.leaf(index);
+      let offset = range.start - before.chars;
+      let count = remaining.min(leaf.summary.chars - offset);
+      remaining -= count;
+
+      if count == leaf.summary.chars {
+        bottom.remove_child(index);
+        Self::refresh_path(&path);
+        self.rebalance(&path);
+        continue;
+      }
+
+      let old = leaf.as_str();
+      let start = char_to_byte_in(old, offset);
+      let end = start + char_to_byte_in(&old[start..], count);
+      let length = old.len() - (end - start);
+
+      let mut buffer = [0; MAX_LEAF];
+      buffer[..start].copy_from_slice(&old.as_bytes()[..start]);
+      buffer[start..length].copy_from_slice(&old.as_bytes()[end..]);
+      // Safety: joined at char boundaries
+      let text = unsafe { str::from_utf8_unchecked(&buffer[..length]) };
+
+      let leaf = self.allocate_leaf(text).await?;
+      bottom.replace_leaf(index, leaf);
+      Self::refresh_path(&path);
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, S: Strategy + 'a, A: RopeAllocator<'a, S>> Display for Rope<'a, S, A>
+where
+  S::Handle<'a, RopeLeaf>: Deref<Target = RopeLeaf>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for chunk in self.chunks() {
+      f.write_str(chunk)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, S: Strategy + 'a, A: RopeAllocator<'a, S>> Debug for Rope<'a, S, A>
+where
+  S::Handle<'a, RopeLeaf>: Deref<Target = RopeLeaf>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("\"")?;
+    for chunk in self.chunks() {
+      Display::fmt(&chunk.escape_debug(), f)?;
+    }
+    f.write_str("\"")
+  }
+}
+
+pub struct Chunks<'b, 'a, S: Strategy> {
+  stack: FixedVec<(&'b RopeNode<'a, S>, usize), MAX_DEPTH>,
+}
+
+impl<'b, 'a, S: Strategy> Iterator for Chunks<'b, 'a, S>
+where
+  S::Handle<'a, RopeLeaf>: Deref<Target = RopeLeaf>,
+{
+  type Item = &'b str;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let (node, index) = self.stack.last_mut()?;
+      let node: &'b RopeNode<'a, S> = node;
+
+      match &node.children {
+        Children::Leaves(leaves) if *index < leaves.len() => {
+          *index += 1;
+          return Some(leaves[*index - 1].as_str());
+        }
+        Children::Nodes(nodes) if *index < nodes.len() => {
+          *index += 1;
+          let child = &*nodes[*index - 1];
+          let Ok(_) = self.stack.push((child, 0)) else {
+            unreachable!("rope is too deep");
+          };
+        }
+        _ => {
+          self.stack.pop();
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::alloc::{ForeignAllocator, StdAlloc, rope::Rope, strategy::RcStrategy};
+
+  const LINE: &str = "abcdéfghij\n";
+
+  #[pollster::test]
+  async fn edit() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut rope = Rope::<RcStrategy, _>::from_str(&allocator, "hello world")
+      .await
+      .unwrap();
+    rope.insert(5, ",").await.unwrap();
+    rope.insert(12, "\nsecond line").await.unwrap();
+    assert!(rope.chars().eq("hello, world\nsecond line".chars()));
+
+    rope.remove(0..7).await.unwrap();
+    assert!(rope.chars().eq("world\nsecond line".chars()));
+    assert_eq!(rope.len_lines(), 2);
+    assert_eq!(rope.line_to_char(1), 6);
+    assert_eq!(rope.char_to_line(7), 1);
+    assert_eq!(rope.char(6), 's');
+
+    rope.remove(0..rope.len_chars()).await.unwrap();
+    assert!(rope.is_empty());
+  }
+
+  #[pollster::test]
+  async fn large() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut rope = Rope::<RcStrategy, _>::new(&allocator);
+    let lines = 5000;
+    for line in 0..lines {
+      rope.insert(line * 11, LINE).await.unwrap();
+    }
+    assert!(rope.chunks().count() > 2 * 8);
+    assert_eq!(rope.len_chars(), lines * 11);
+    assert_eq!(rope.len_bytes(), lines * 12);
+    assert_eq!(rope.len_lines(), lines + 1);
+    assert_eq!(rope.line_to_char(1234), 1234 * 11);
+    assert_eq!(rope.char_to_line(1234 * 11 + 3), 1234);
+    assert_eq!(rope.char_to_byte(1234 * 11 + 6), 1234 * 12 + 7);
+
+    // insert a large block in the middle, then take it back out again
+    let middle = 2500 * 11;
+    let mut block = [0; 4000];
+    for (index, byte) in block.iter_mut().enumerate() {
+      *byte = b'a' + (index % 26) as u8;
+    }
+    let block = str::from_utf8(&block).unwrap();
+    rope.insert(middle, block).await.unwrap();
+    assert_eq!(rope.char(middle + 27), 'b');
+    assert_eq!(rope.len_lines(), lines + 1);
+    rope.remove(middle..middle + 4000).await.unwrap();
+    assert!(rope.chars().eq(LINE.chars().cycle().take(lines * 11)));
+
+    // remove most of the text, so nodes have to be merged
+    rope.remove(11..lines * 11 - 11).await.unwrap();
+    assert!(rope.chars().eq(LINE.chars().chain(LINE.chars())));
+    assert_eq!(rope.line_to_char(2), 22);
+  }
+}
@@ -0,0 +1,490 @@
+use core::{
+  fmt::{self, Debug},
+  iter::{Enumerate, FusedIterator},
+  marker::PhantomData,
+  mem,
+  num::NonZeroU32,
+  ops::{Deref, DerefMut, Index, IndexMut},
+  slice,
+};
+
+use crate::{
+  alloc::{GrowthStrategy, SliceAllocator, UnsizedMaybeUninit, strategy::Strategy, vec::Vec},
+  types::vec::SliceVec,
+};
+
+/// The index and version that every [Key] is made of. Versions are odd while a slot is occupied,
+/// so they're never zero in a key.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct KeyData {
+  index: u32,
+  version: NonZeroU32,
+}
+
+impl KeyData {
+  pub fn index(&self) -> u32 {
+    self.index
+  }
+
+  pub fn version(&self) -> u32 {
+    self.version.get()
+  }
+}
+
+/// A key into a [SlotMap], which stops working once its value is removed, even if the slot is
+/// reused for another value.
+pub trait Key: Copy + From<KeyData> {
+  fn data(&self) -> KeyData;
+}
+
+/// Declares a new [Key](crate::alloc::slot_map::Key) type, so keys of different maps can't be
+/// mixed up.
+///
+/// ```ignore
+/// new_key_type! {
+///   pub struct EntityId;
+/// }
+/// ```
+#[macro_export]
+macro_rules! new_key_type {
+  ($(#[$attr:meta])* $vis:vis struct $name:ident;) => {
+    $(#[$attr])*
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+    #[repr(transparent)]
+    $vis struct $name($crate::alloc::slot_map::KeyData);
+
+    impl From<$crate::alloc::slot_map::KeyData> for $name {
+      fn from(data: $crate::alloc::slot_map::KeyData) -> Self {
+        Self(data)
+      }
+    }
+
+    impl $crate::alloc::slot_map::Key for $name {
+      fn data(&self) -> $crate::alloc::slot_map::KeyData {
+        self.0
+      }
+    }
+  };
+}
+
+new_key_type! {
+  /// The key used by a [SlotMap] unless another one is given.
+  pub struct DefaultKey;
+}
+
+#[doc(hidden)]
+pub enum Slot<V> {
+  Occupied {
+    version: NonZeroU32,
+    value: V,
+  },
+  Vacant {
+    version: u32,
+    next_free: Option<u32>,
+  },
+}
+
+/// Stores values in a [Vec], handing out [Key]s that detect when the value they pointed to was
+/// removed. Removed slots are reused by later insertions.
+pub struct SlotMap<'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: Deref<Target = SliceVec<Slot<V>>>,
+{
+  slots: Vec<'a, Slot<V>, S, A>,
+  free_head: Option<u32>,
+  length: usize,
+  phantom: PhantomData<K>,
+}
+
+impl<'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>>
+  SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: Deref<Target = SliceVec<Slot<V>>>,
+{
+  pub async fn new(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<Slot<V>>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<Slot<V>>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<Slot<V>>>>: SliceDst,
+  {
+    Self::with_capacity(allocator, strategy, 0).await
+  }
+
+  pub async fn with_capacity(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<Slot<V>>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<Slot<V>>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<Slot<V>>>>: SliceDst,
+  {
+    Ok(Self {
+      slots: Vec::with_capacity(allocator, strategy, capacity).await?,
+      free_head: None,
+      length: 0,
+      phantom: PhantomData,
+    })
+  }
+}
+
+impl<'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>>
+  SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+{
+  pub fn len(&self) -> usize {
+    self.length
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.length == 0
+  }
+
+  /// The number of values the map can hold before it has to grow, including removed slots that
+  /// haven't been reused yet.
+  pub fn capacity(&self) -> usize {
+    self.slots.capacity()
+  }
+
+  pub fn contains_key(&self, key: K) -> bool {
+    self.get(key).is_some()
+  }
+
+  pub fn get(&self, key: K) -> Option<&V> {
+    let KeyData { index, version } = key.data();
+    match self.slots.get(index as usize)? {
+      Slot::Occupied {
+        version: current,
+        value,
+      } if *current == version => Some(value),
+      _ => None,
+    }
+  }
+
+  pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+    let KeyData { index, version } = key.data();
+    match self.slots.get_mut(index as usize)? {
+      Slot::Occupied {
+        version: current,
+        value,
+      } if *current == version => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Removes the value for `key`, if the key is still valid. The slot will be reused, but `key`
+  /// will never be valid again.
+  pub fn remove(&mut self, key: K) -> Option<V> {
+    self.get(key)?;
+
+    let index = key.data().index;
+    let slot = &mut self.slots[index as usize];
+    let version = match slot {
+      Slot::Occupied { version, .. } => version.get().wrapping_add(1),
+      Slot::Vacant { .. } => unreachable!("slot was just checked"),
+    };
+    let vacant = Slot::Vacant {
+      version,
+      next_free: self.free_head,
+    };
+    let Slot::Occupied { value, .. } = mem::replace(slot, vacant) else {
+      unreachable!("slot was just checked");
+    };
+
+    self.free_head = Some(index);
+    self.length -= 1;
+
+    Some(value)
+  }
+
+  /// Keeps only the values that `keep` returns true for.
+  pub fn retain(&mut self, mut keep: impl FnMut(K, &mut V) -> bool) {
+    for index in 0..self.slots.len() {
+      let key = match &mut self.slots[index] {
+        Slot::Occupied { version, value } => {
+          let key = K::from(KeyData {
+            index: index as u32,
+            version: *version,
+          });
+          if keep(key, value) {
+            continue;
+          }
+          key
+        }
+        Slot::Vacant { .. } => continue,
+      };
+
+      self.remove(key);
+    }
+  }
+
+  /// Removes every value, invalidating every key.
+  pub fn clear(&mut self) {
+    self.retain(|_, _| false);
+  }
+
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter {
+      slots: self.slots.iter().enumerate(),
+      remaining: self.length,
+      phantom: PhantomData,
+    }
+  }
+
+  pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+    IterMut {
+      remaining: self.length,
+      slots: self.slots.iter_mut().enumerate(),
+      phantom: PhantomData,
+    }
+  }
+
+  pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+    self.iter().map(|(key, _)| key)
+  }
+
+  pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+    self.iter().map(|(_, value)| value)
+  }
+
+  pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> + '_ {
+    self.iter_mut().map(|(_, value)| value)
+  }
+}
+
+impl<'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>>
+  SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<Slot<V>>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<Slot<V>>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<Slot<V>>>>: SliceDst,
+{
+  /// Inserts `value`, reusing a removed slot if there is one.
+  pub async fn insert(&mut self, value: V) -> Result<K, A::Error> {
+    self.insert_with_key(|_| value).await
+  }
+
+  /// Inserts the value returned by `f`, which is given the key the value will have.
+  pub async fn insert_with_key(&mut self, f: impl FnOnce(K) -> V) -> Result<K, A::Error> {
+    if let Some(index) = self.free_head {
+      let slot = &mut self.slots[index as usize];
+      let Slot::Vacant { version, next_free } = *slot else {
+        unreachable!("free list only contains vacant slots");
+      };
+      // vacant versions are even, so this is odd and can't be zero
+      let version = NonZeroU32::new(version.wrapping_add(1)).unwrap();
+      let key = K::from(KeyData { index, version });
+
+      *slot = Slot::Occupied {
+        version,
+        value: f(key),
+      };
+      self.free_head = next_free;
+      self.length += 1;
+
+      return Ok(key);
+    }
+
+    let index = u32::try_from(self.slots.len()).expect("slot map is full");
+    let version = NonZeroU32::MIN;
+    let key = K::from(KeyData { index, version });
+
+    self
+      .slots
+      .push_resize(Slot::Occupied {
+        version,
+        value: f(key),
+      })
+      .await?;
+    self.length += 1;
+
+    Ok(key)
+  }
+}
+
+impl<'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>> Index<K>
+  for SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+{
+  type Output = V;
+
+  fn index(&self, key: K) -> &V {
+    self.get(key).expect("invalid slot map key")
+  }
+}
+
+impl<'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>> IndexMut<K>
+  for SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+{
+  fn index_mut(&mut self, key: K) -> &mut V {
+    self.get_mut(key).expect("invalid slot map key")
+  }
+}
+
+impl<'a, K: Key + Debug, V: Debug + 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>> Debug
+  for SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+impl<'b, 'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>> IntoIterator
+  for &'b SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+{
+  type Item = (K, &'b V);
+  type IntoIter = Iter<'b, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'b, 'a, K: Key, V: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<Slot<V>>>> IntoIterator
+  for &'b mut SlotMap<'a, K, V, S, A>
+where
+  S::Handle<'a, SliceVec<Slot<V>>>: DerefMut<Target = SliceVec<Slot<V>>>,
+{
+  type Item = (K, &'b mut V);
+  type IntoIter = IterMut<'b, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+pub struct Iter<'b, K: Key, V> {
+  slots: Enumerate<slice::Iter<'b, Slot<V>>>,
+  remaining: usize,
+  phantom: PhantomData<K>,
+}
+
+impl<'b, K: Key, V> Iterator for Iter<'b, K, V> {
+  type Item = (K, &'b V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    for (index, slot) in self.slots.by_ref() {
+      if let Slot::Occupied { version, value } = slot {
+        self.remaining -= 1;
+        let key = K::from(KeyData {
+          index: index as u32,
+          version: *version,
+        });
+        return Some((key, value));
+      }
+    }
+
+    None
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<'b, K: Key, V> ExactSizeIterator for Iter<'b, K, V> {}
+impl<'b, K: Key, V> FusedIterator for Iter<'b, K, V> {}
+
+pub struct IterMut<'b, K: Key, V> {
+  slots: Enumerate<slice::IterMut<'b, Slot<V>>>,
+  remaining: usize,
+  phantom: PhantomData<K>,
+}
+
+impl<'b, K: Key, V> Iterator for IterMut<'b, K, V> {
+  type Item = (K, &'b mut V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    for (index, slot) in self.slots.by_ref() {
+      if let Slot::Occupied { version, value } = slot {
+        self.remaining -= 1;
+        let key = K::from(KeyData {
+          index: index as u32,
+          version: *version,
+        });
+        return Some((key, value));
+      }
+    }
+
+    None
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<'b, K: Key, V> ExactSizeIterator for IterMut<'b, K, V> {}
+impl<'b, K: Key, V> FusedIterator for IterMut<'b, K, V> {}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::{
+    alloc::{
+      ForeignAllocator, GrowthStrategy, StdAlloc,
+      slot_map::{DefaultKey, SlotMap},
+      strategy::UniqueStrategy,
+    },
+    test_arena,
+  };
+
+  #[pollster::test]
+  async fn stale_keys() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut map =
+      SlotMap::<DefaultKey, &str, UniqueStrategy, _>::new(&allocator, GrowthStrategy::Exponential)
+        .await
+        .unwrap();
+
+    let a = map.insert("a").await.unwrap();
+    let b = map.insert("b").await.unwrap();
+    assert_eq!(map.remove(a), Some("a"));
+    assert_eq!(map.remove(a), None);
+
+    // the slot of `a` is reused, but `a` stays invalid
+    let c = map.insert("c").await.unwrap();
+    assert_ne!(a, c);
+    assert_eq!(map.get(a), None);
+    assert_eq!(map[c], "c");
+
+    map[b] = "B";
+    assert_eq!(map.len(), 2);
+    assert!(map.iter().eq([(b, &"B"), (c, &"c")].into_iter().rev()));
+
+    map.retain(|key, _| key == b);
+    assert!(map.keys().eq([b]));
+    map.clear();
+    assert!(map.is_empty());
+    assert!(!map.contains_key(b));
+  }
+
+  #[pollster::test]
+  async fn arena() {
+    crate::new_key_type! {
+      struct EntityId;
+    }
+
+    let arena = test_arena!(UniqueStrategy, 16).await;
+    let mut map =
+      SlotMap::<EntityId, u32, UniqueStrategy, _>::with_capacity(&arena, GrowthStrategy::Exact, 4)
+        .await
+        .unwrap();
+    assert_eq!(map.capacity(), 4);
+    for value in 0..4 {
+      map.insert(value).await.unwrap();
+    }
+    assert_eq!(map.capacity(), 4);
+    assert!(map.values().copied().eq(0..4));
+  }
+}
@@ -0,0 +1,318 @@
+use core::{
+  fmt::Debug,
+  mem,
+  ops::{Deref, DerefMut},
+};
+
+use crate::{
+  alloc::{
+    GrowthStrategy, LayoutAllocator, SliceAllocator, TryCloneIn, UnsizedMaybeUninit,
+    strategy::Strategy, vec::Vec,
+  },
+  types::vec::{FixedVec, SliceVec},
+};
+
+enum SmallVecData<'a, T: 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>>
+where
+  S::Handle<'a, SliceVec<T>>: Deref<Target = SliceVec<T>>,
+{
+  Inline(FixedVec<T, N>),
+  Spilled(Vec<'a, T, S, A>),
+}
+
+/// A vec that keeps up to `N` values inline, and only allocates from `A` once it grows beyond
+/// that.
+pub struct SmallVec<'a, T: 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>>
+where
+  S::Handle<'a, SliceVec<T>>: Deref<Target = SliceVec<T>>,
+{
+  allocator: &'a A,
+  growth_strategy: GrowthStrategy,
+  data: SmallVecData<'a, T, N, S, A>,
+}
+
+impl<'a, T: 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>>
+  SmallVec<'a, T, N, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+{
+  /// Creates an empty vec, which doesn't allocate until more than `N` values are pushed.
+  pub fn new(allocator: &'a A, strategy: GrowthStrategy) -> Self {
+    Self {
+      allocator,
+      growth_strategy: strategy,
+      data: SmallVecData::Inline(FixedVec::new()),
+    }
+  }
+
+  /// Whether the values have been moved into an allocation.
+  pub fn spilled(&self) -> bool {
+    matches!(self.data, SmallVecData::Spilled(_))
+  }
+
+  pub fn capacity(&self) -> usize {
+    match &self.data {
+      SmallVecData::Inline(inline) => inline.capacity(),
+      SmallVecData::Spilled(vec) => vec.capacity(),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    match &self.data {
+      SmallVecData::Inline(inline) => inline.len(),
+      SmallVecData::Spilled(vec) => vec.len(),
+    }
+  }
+
+  pub fn is_full(&self) -> bool {
+    self.len() >= self.capacity()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn pop(&mut self) -> Option<T> {
+    match &mut self.data {
+      SmallVecData::Inline(inline) => inline.pop(),
+      SmallVecData::Spilled(vec) => vec.pop(),
+    }
+  }
+
+  /// Removes the element at `index`, shifting every element after it to the left.
+  ///
+  /// Panics if `index >= len`.
+  pub fn remove(&mut self, index: usize) -> T {
+    match &mut self.data {
+      SmallVecData::Inline(inline) => inline.remove(index),
+      SmallVecData::Spilled(vec) => vec.remove(index),
+    }
+  }
+
+  /// Drops every element after the first `length` elements. Never moves the values back inline.
+  pub fn truncate(&mut self, length: usize) {
+    match &mut self.data {
+      SmallVecData::Inline(inline) => inline.truncate(length),
+      SmallVecData::Spilled(vec) => vec.truncate(length),
+    }
+  }
+
+  pub fn clear(&mut self) {
+    self.truncate(0);
+  }
+}
+
+impl<'a, T: 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>>
+  SmallVec<'a, T, N, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  /// Makes room for at least `additional` more values, moving them into an allocation if they
+  /// no longer fit inline.
+  pub async fn reserve(&mut self, additional: usize) -> Result<(), A::Error> {
+    let length = self.len();
+    if self.capacity() - length >= additional {
+      return Ok(());
+    }
+
+    let capacity = self
+      .growth_strategy
+      .calculate_new_capacity(self.capacity(), length + additional - self.capacity())
+      .expect("vec is full");
+
+    match &mut self.data {
+      SmallVecData::Inline(inline) => {
+        let mut vec = Vec::with_capacity(self.allocator, self.growth_strategy, capacity).await?;
+
+        // popping returns the values backwards, so they're reversed once they've been moved
+        while let Some(value) = inline.pop() {
+          let Ok(_) = vec.push(value) else {
+            unreachable!("not enough space for value");
+          };
+        }
+        vec.reverse();
+
+        self.data = SmallVecData::Spilled(vec);
+      }
+      SmallVecData::Spilled(vec) => vec.resize(capacity).await?,
+    }
+
+    Ok(())
+  }
+
+  pub async fn push(&mut self, value: T) -> Result<(), A::Error> {
+    if self.is_full() {
+      self.reserve(1).await?;
+    }
+
+    let pushed = match &mut self.data {
+      SmallVecData::Inline(inline) => inline.push(value),
+      SmallVecData::Spilled(vec) => vec.push(value),
+    };
+    let Ok(_) = pushed else {
+      unreachable!("not enough space for value");
+    };
+
+    Ok(())
+  }
+
+  /// Inserts `value` at `index`, shifting every element after it to the right.
+  ///
+  /// Panics if `index > len`.
+  pub async fn insert(&mut self, index: usize, value: T) -> Result<(), A::Error> {
+    let length = self.len();
+    assert!(
+      index <= length,
+      "insertion index {index} out of bounds for length {length}"
+    );
+    if self.is_full() {
+      self.reserve(1).await?;
+    }
+
+    let inserted = match &mut self.data {
+      SmallVecData::Inline(inline) => inline.insert(index, value),
+      SmallVecData::Spilled(vec) => vec.insert(index, value),
+    };
+    let Ok(_) = inserted else {
+      unreachable!("not enough space for value");
+    };
+
+    Ok(())
+  }
+
+  pub async fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), A::Error> {
+    let mut iter = iter.into_iter();
+
+    while let Some(item) = iter.next() {
+      if self.is_full() {
+        let (lower_bound, _) = iter.size_hint();
+        self.reserve(lower_bound + 1).await?;
+      }
+
+      self.push(item).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Moves the values into an allocation, returning it as a [Vec].
+  pub async fn into_vec(mut self) -> Result<Vec<'a, T, S, A>, A::Error> {
+    if !self.spilled() {
+      let additional = N - self.len() + 1;
+      self.reserve(additional).await?;
+    }
+
+    let SmallVecData::Spilled(vec) =
+      mem::replace(&mut self.data, SmallVecData::Inline(FixedVec::new()))
+    else {
+      unreachable!("values were just spilled");
+    };
+
+    Ok(vec)
+  }
+}
+
+impl<'a, T: TryCloneIn<'a, A> + 'a, const N: usize, S: Strategy, A> TryCloneIn<'a, A>
+  for SmallVec<'a, T, N, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  /// Clones the values into `allocator`, keeping them inline if they are now.
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let data = match &self.data {
+      SmallVecData::Inline(inline) => {
+        let mut clone = FixedVec::new();
+        for value in inline.iter() {
+          let Ok(_) = clone.push(value.try_clone_in(allocator).await?) else {
+            unreachable!("not enough space for value");
+          };
+        }
+        SmallVecData::Inline(clone)
+      }
+      SmallVecData::Spilled(vec) => SmallVecData::Spilled(vec.try_clone_in(allocator).await?),
+    };
+
+    Ok(Self {
+      allocator,
+      growth_strategy: self.growth_strategy,
+      data,
+    })
+  }
+}
+
+impl<'a, T: 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>> Deref
+  for SmallVec<'a, T, N, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+{
+  type Target = [T];
+
+  fn deref(&self) -> &[T] {
+    match &self.data {
+      SmallVecData::Inline(inline) => inline,
+      SmallVecData::Spilled(vec) => vec,
+    }
+  }
+}
+
+impl<'a, T: 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>> DerefMut
+  for SmallVec<'a, T, N, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+{
+  fn deref_mut(&mut self) -> &mut [T] {
+    match &mut self.data {
+      SmallVecData::Inline(inline) => inline,
+      SmallVecData::Spilled(vec) => vec,
+    }
+  }
+}
+
+impl<'a, T: Debug + 'a, const N: usize, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>> Debug
+  for SmallVec<'a, T, N, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+{
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.deref().fmt(f)
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::alloc::{
+    ForeignAllocator, GrowthStrategy, StdAlloc, small_vec::SmallVec, strategy::UniqueStrategy,
+  };
+
+  #[pollster::test]
+  async fn spill() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut vec =
+      SmallVec::<u32, 3, UniqueStrategy, _>::new(&allocator, GrowthStrategy::Exponential);
+    vec.push(1).await.unwrap();
+    vec.push(3).await.unwrap();
+    vec.insert(1, 2).await.unwrap();
+    assert!(!vec.spilled());
+    assert_eq!(&*vec, &[1, 2, 3]);
+
+    vec.push(4).await.unwrap();
+    assert!(vec.spilled());
+    assert_eq!(&*vec, &[1, 2, 3, 4]);
+
+    vec.extend(5..10).await.unwrap();
+    assert_eq!(vec.remove(0), 1);
+    assert_eq!(vec.pop(), Some(9));
+    assert!(vec.iter().copied().eq(2..9));
+
+    let vec = vec.into_vec().await.unwrap();
+    assert_eq!(vec.len(), 7);
+  }
+}
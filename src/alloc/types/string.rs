@@ -8,7 +8,8 @@ use thiserror::Error;
 
 use crate::{
   alloc::{
-    GrowthStrategy, SliceAllocator, UnsizedMaybeUninit, strategy::Strategy, types::vec::Vec,
+    FromIteratorIn, GrowthStrategy, LayoutAllocator, SliceAllocator, SyncSliceAllocator,
+    TryCloneIn, UnsizedMaybeUninit, strategy::Strategy, types::vec::Vec,
   },
   io::StreamWrite,
   types::vec::SliceVec,
@@ -59,6 +60,23 @@ where
     DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
   S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
 {
+  pub fn as_str(&self) -> &str {
+    // Safety: only valid utf-8 is ever pushed
+    unsafe { str::from_utf8_unchecked(&self.inner) }
+  }
+
+  pub fn len(&self) -> usize {
+    self.inner.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.inner.is_empty()
+  }
+
+  pub async fn push(&mut self, value: char) -> Result<(), A::Error> {
+    self.push_str(value.encode_utf8(&mut [0; 4])).await
+  }
+
   pub async fn push_str(&mut self, value: &str) -> Result<(), A::Error> {
     self.inner.extend(value.as_bytes().iter().cloned()).await?;
 
@@ -74,6 +92,108 @@ where
   }
 }
 
+impl<'a, S: Strategy, A: SyncSliceAllocator<'a, SliceVec<u8>>> String<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: Deref<Target = SliceVec<u8>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+{
+  pub fn new_sync(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error> {
+    Ok(Self {
+      inner: Vec::new_sync(allocator, strategy)?,
+    })
+  }
+
+  pub fn with_capacity_sync(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error> {
+    Ok(Self {
+      inner: Vec::with_capacity_sync(allocator, strategy, capacity)?,
+    })
+  }
+}
+
+impl<'a, S: Strategy, A: SyncSliceAllocator<'a, SliceVec<u8>>> String<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+{
+  pub fn push_sync(&mut self, value: char) -> Result<(), A::Error> {
+    self.push_str_sync(value.encode_utf8(&mut [0; 4]))
+  }
+
+  pub fn push_str_sync(&mut self, value: &str) -> Result<(), A::Error> {
+    self.inner.extend_sync(value.as_bytes().iter().cloned())
+  }
+}
+
+impl<'a, S: Strategy, A> FromIteratorIn<'a, char, A> for String<'a, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<u8>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+{
+  /// Reserves a byte for every char the iterator is expected to return, and grows with
+  /// [GrowthStrategy::Exponential] past that.
+  async fn from_iter_in<I: IntoIterator<Item = char>>(
+    iter: I,
+    allocator: &'a A,
+  ) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let iter = iter.into_iter();
+    let (lower_bound, _) = iter.size_hint();
+    let mut string =
+      Self::with_capacity(allocator, GrowthStrategy::Exponential, lower_bound).await?;
+    for value in iter {
+      string.push(value).await?;
+    }
+
+    Ok(string)
+  }
+}
+
+impl<'a, 'b, S: Strategy, A> FromIteratorIn<'a, &'b str, A> for String<'a, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<u8>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+{
+  async fn from_iter_in<I: IntoIterator<Item = &'b str>>(
+    iter: I,
+    allocator: &'a A,
+  ) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let mut string = Self::new(allocator, GrowthStrategy::Exponential).await?;
+    for value in iter {
+      string.push_str(value).await?;
+    }
+
+    Ok(string)
+  }
+}
+
+impl<'a, S: Strategy, A> TryCloneIn<'a, A> for String<'a, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<u8>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    Ok(Self {
+      inner: self.inner.try_clone_in(allocator).await?,
+    })
+  }
+}
+
 #[derive(Error)]
 #[error("{0}")]
 pub enum WriteError<'a, A: SliceAllocator<'a, SliceVec<u8>>> {
@@ -6,7 +6,10 @@ use core::{
 };
 
 use crate::{
-  alloc::{GrowthStrategy, SliceAllocator, UnsizedMaybeUninit, strategy::Strategy},
+  alloc::{
+    FromIteratorIn, GrowthStrategy, LayoutAllocator, SliceAllocator, SyncSliceAllocator,
+    TryCloneIn, UnsizedMaybeUninit, strategy::Strategy,
+  },
   types::vec::{BaseVecHeader, SliceVec},
 };
 
@@ -43,16 +46,50 @@ where
       DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
     S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
   {
-    let mut handle: S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>> =
-      allocator.reserve_slice::<S>(capacity).await?;
+    let handle = allocator.reserve_slice::<S>(capacity).await?;
 
+    Ok(Self::from_uninit(allocator, strategy, handle))
+  }
+
+  fn from_uninit(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    mut handle: S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>,
+  ) -> Self
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  {
     handle.header.write(BaseVecHeader::new());
 
-    Ok(Self {
+    Self {
       allocator,
       growth_strategy: strategy,
+      // Safety: the header was initialized, and the values are uninitialized while length is 0
       inner: unsafe { S::UninitHandle::assume_init(handle) },
-    })
+    }
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SyncSliceAllocator<'a, SliceVec<T>>> Vec<'a, T, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: Deref<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  pub fn new_sync(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error> {
+    Self::with_capacity_sync(allocator, strategy, 0)
+  }
+
+  pub fn with_capacity_sync(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error> {
+    let handle = allocator.reserve_slice_sync::<S>(capacity)?;
+
+    Ok(Self::from_uninit(allocator, strategy, handle))
   }
 }
 
@@ -64,18 +101,25 @@ where
   S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
 {
   pub async fn grow(&mut self, additional: usize) -> Result<(), A::Error> {
-    let capacity = self
-      .growth_strategy
-      .calculate_new_capacity(self.inner.len(), additional)
-      .expect("vec is full");
-
-    self.resize(capacity).await
+    self.resize(self.grown_capacity(additional)).await
   }
 
   pub async fn resize(&mut self, to_capacity: usize) -> Result<(), A::Error> {
-    let new: S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>> =
-      self.allocator.reserve_slice::<S>(to_capacity).await?;
+    let new = self.allocator.reserve_slice::<S>(to_capacity).await?;
+    self.move_into(new);
+
+    Ok(())
+  }
+
+  fn grown_capacity(&self, additional: usize) -> usize {
+    self
+      .growth_strategy
+      .calculate_new_capacity(self.inner.len(), additional)
+      .expect("vec is full")
+  }
 
+  /// Moves the values into `new`, and frees the old allocation.
+  fn move_into(&mut self, new: S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>) {
     let old_ptr = S::Handle::as_value_ptr(&self.inner);
     let new_ptr = S::UninitHandle::as_value_ptr(&new);
 
@@ -90,8 +134,6 @@ where
     };
 
     mem::drop(mem::replace(&mut self.inner, new));
-
-    Ok(())
   }
 
   pub async fn push_resize(&mut self, value: T) -> Result<(), A::Error> {
@@ -123,13 +165,104 @@ where
 
     while let Some(item) = iter.next() {
       let (lower_bound, _) = iter.size_hint();
-      self.push_resize_to(item, lower_bound).await?;
+      self.push_resize_to(item, lower_bound + 1).await?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a, T: 'a, S: Strategy, A: SyncSliceAllocator<'a, SliceVec<T>>> Vec<'a, T, S, A>
+where
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  pub fn grow_sync(&mut self, additional: usize) -> Result<(), A::Error> {
+    self.resize_sync(self.grown_capacity(additional))
+  }
+
+  pub fn resize_sync(&mut self, to_capacity: usize) -> Result<(), A::Error> {
+    let new = self.allocator.reserve_slice_sync::<S>(to_capacity)?;
+    self.move_into(new);
+
+    Ok(())
+  }
+
+  pub fn push_resize_sync(&mut self, value: T) -> Result<(), A::Error> {
+    if self.inner.capacity() < self.inner.len() + 1 {
+      self.grow_sync(1)?;
+    }
+
+    let Ok(_) = self.inner.push(value) else {
+      unreachable!("not enough space for value");
+    };
+
+    Ok(())
+  }
+
+  pub fn extend_sync<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), A::Error> {
+    let mut iter = iter.into_iter();
+
+    while let Some(item) = iter.next() {
+      if self.inner.capacity() < self.inner.len() + 1 {
+        let (lower_bound, _) = iter.size_hint();
+        self.grow_sync(lower_bound + 1)?;
+      }
+
+      let Ok(_) = self.inner.push(item) else {
+        unreachable!("not enough space for value");
+      };
     }
 
     Ok(())
   }
 }
 
+impl<'a, T: 'a, S: Strategy, A> FromIteratorIn<'a, T, A> for Vec<'a, T, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  /// Reserves the iterator's lower size hint up front, and grows with
+  /// [GrowthStrategy::Exponential] past that.
+  async fn from_iter_in<I: IntoIterator<Item = T>>(
+    iter: I,
+    allocator: &'a A,
+  ) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let iter = iter.into_iter();
+    let (lower_bound, _) = iter.size_hint();
+    let mut vec = Self::with_capacity(allocator, GrowthStrategy::Exponential, lower_bound).await?;
+    vec.extend(iter).await?;
+
+    Ok(vec)
+  }
+}
+
+impl<'a, T: TryCloneIn<'a, A> + 'a, S: Strategy, A> TryCloneIn<'a, A> for Vec<'a, T, S, A>
+where
+  A: LayoutAllocator + SliceAllocator<'a, SliceVec<T>, Error = <A as LayoutAllocator>::Error>,
+  S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<T>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<T>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<T>>>: SliceDst,
+{
+  async fn try_clone_in(&self, allocator: &'a A) -> Result<Self, <A as LayoutAllocator>::Error> {
+    let mut vec = Self::with_capacity(allocator, self.growth_strategy, self.capacity()).await?;
+    for value in self.iter() {
+      let Ok(_) = vec.push(value.try_clone_in(allocator).await?) else {
+        unreachable!("not enough space for value");
+      };
+    }
+
+    Ok(vec)
+  }
+}
+
 impl<'a, T: 'a, S: Strategy, A: SliceAllocator<'a, SliceVec<T>>> Deref for Vec<'a, T, S, A>
 where
   S::Handle<'a, SliceVec<T>>: DerefMut<Target = SliceVec<T>>,
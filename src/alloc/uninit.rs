@@ -1,9 +1,7 @@
-use aubystd_macros::slice_dst;
-
 use crate::alloc::SliceDst;
 use core::{mem::MaybeUninit, ptr};
 
-#[slice_dst(header = UnsizedMaybeUninitHeader)]
+#[derive(SliceDst)]
 #[repr(C)]
 pub struct UnsizedMaybeUninit<T: SliceDst + ?Sized> {
   pub header: MaybeUninit<T::Header>,
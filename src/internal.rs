@@ -1,34 +1,133 @@
-use core::fmt;
+use core::{
+  fmt,
+  mem::MaybeUninit,
+  ptr::{self, Pointee},
+};
 
-use syscalls::{Sysno, syscall};
+use crate::{
+  alloc::SliceDst,
+  platform::active::io::stdio::{self, LineBufferFormat},
+};
 
+/// Room for a zero length `T`, which `#[derive(SliceDst)]` uses to check layouts at compile time.
 #[doc(hidden)]
-pub struct StdoutFormat;
+pub struct SliceDstProbe<T: SliceDst + ?Sized>(MaybeUninit<(T::Header, T::Header, T::Element)>);
 
-impl StdoutFormat {
-  fn write_byte_string(&self, s: &[u8]) {
-    unsafe { syscall!(Sysno::write, 1i32, s.as_ptr(), s.len()) }.unwrap();
+impl<T: SliceDst + ?Sized> SliceDstProbe<T> {
+  /// Where the elements start, if `T` is laid out like its header followed by its elements.
+  pub const ELEMENTS_OFFSET: usize =
+    size_of::<T::Header>().next_multiple_of(align_of::<T::Element>());
+
+  pub const fn new() -> Self {
+    Self(MaybeUninit::uninit())
   }
-}
 
-impl fmt::Write for StdoutFormat {
-  fn write_str(&mut self, s: &str) -> core::fmt::Result {
-    self.write_byte_string(s.as_bytes());
+  pub const fn as_ptr(&self) -> *const T {
+    ptr::from_raw_parts(ptr::from_ref(self), 0)
+  }
 
-    Ok(())
+  /// Safety: `field` must point into the probe
+  pub const unsafe fn offset_of<F: ?Sized>(&self, field: *const F) -> usize {
+    unsafe {
+      field
+        .cast::<u8>()
+        .offset_from_unsigned(ptr::from_ref(self).cast())
+    }
   }
 }
 
-#[macro_export]
+impl<T: SliceDst + ?Sized> Default for SliceDstProbe<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Implemented for types with the metadata of a slice DST, which `#[derive(SliceDst)]` bounds its
+/// implementation on instead of leaving it to the supertrait of [SliceDst].
+#[doc(hidden)]
+pub trait SliceDstMetadata: Pointee<Metadata = usize> {}
+
+impl<T: Pointee<Metadata = usize> + ?Sized> SliceDstMetadata for T {}
+
+/// Returns a pointer to the field `offset` bytes into `ptr`, with the same length, which
+/// `#[derive(SliceDst)]` uses to find the last field without dereferencing `ptr`.
 #[doc(hidden)]
+pub const fn slice_dst_field<T: SliceDst + ?Sized, F: SliceDst + ?Sized>(
+  ptr: *mut T,
+  offset: usize,
+) -> *mut F {
+  ptr::from_raw_parts_mut(ptr.cast::<u8>().wrapping_add(offset), ptr::metadata(ptr))
+}
+
+/// Writes formatted output to the standard output, for [print!](crate::print).
+///
+/// Errors are ignored, like when the output is a closed pipe, so printing never panics.
+#[doc(hidden)]
+pub fn print(args: fmt::Arguments) {
+  let _ = fmt::write(
+    &mut LineBufferFormat {
+      buffer: &stdio::STDOUT,
+      error: None,
+    },
+    args,
+  );
+}
+
+/// Writes formatted output to the standard error, for [eprint!](crate::eprint). It's flushed
+/// right away, even if it doesn't end a line.
+#[doc(hidden)]
+pub fn eprint(args: fmt::Arguments) {
+  let _ = fmt::write(
+    &mut LineBufferFormat {
+      buffer: &stdio::STDERR,
+      error: None,
+    },
+    args,
+  );
+  let _ = stdio::STDERR.flush();
+}
+
+/// Writes the output [print!](crate::print) is holding until a line ends.
+#[doc(hidden)]
+pub fn flush_stdout() {
+  let _ = stdio::STDOUT.flush();
+}
+
+/// Prints to the standard output. Output is written a line at a time, so it only shows up once a
+/// line ends, unless the buffer fills up first.
+#[macro_export]
+macro_rules! print {
+  ($($arg: tt)*) => {
+    $crate::internal::print(::core::format_args!($($arg)*))
+  };
+}
+
+/// Prints to the standard output, followed by a newline.
+#[macro_export]
 macro_rules! println {
   () => {
-    self.write_byte_string(b"\n");
+    $crate::internal::print(::core::format_args!("\n"))
   };
   ($($arg: tt)*) => {
-    {
-      use ::core::fmt::Write;
-      writeln!(&mut $crate::internal::StdoutFormat, $($arg)*).expect("failed to print message");
-    }
+    $crate::internal::print(::core::format_args!("{}\n", ::core::format_args!($($arg)*)))
+  };
+}
+
+/// Prints to the standard error.
+#[macro_export]
+macro_rules! eprint {
+  ($($arg: tt)*) => {
+    $crate::internal::eprint(::core::format_args!($($arg)*))
+  };
+}
+
+/// Prints to the standard error, followed by a newline.
+#[macro_export]
+macro_rules! eprintln {
+  () => {
+    $crate::internal::eprint(::core::format_args!("\n"))
+  };
+  ($($arg: tt)*) => {
+    $crate::internal::eprint(::core::format_args!("{}\n", ::core::format_args!($($arg)*)))
   };
 }
@@ -0,0 +1,473 @@
+use core::{
+  error::Error,
+  ops::DerefMut,
+  str::{self, Utf8Error},
+};
+
+use thiserror::Error;
+
+use crate::{
+  alloc::{SliceAllocator, strategy::Strategy, types::vec::Vec},
+  io::{IncompleteError, StreamRead, StreamWrite},
+  types::vec::{FixedVec, SliceVec},
+};
+
+/// Storage for a [BufReader] or [BufWriter]. Its whole capacity is used, whatever it held before.
+pub trait Buffer {
+  fn as_slice_vec(&self) -> &SliceVec<u8>;
+  fn as_slice_vec_mut(&mut self) -> &mut SliceVec<u8>;
+}
+
+impl<const CAPACITY: usize> Buffer for FixedVec<u8, CAPACITY> {
+  fn as_slice_vec(&self) -> &SliceVec<u8> {
+    self
+  }
+
+  fn as_slice_vec_mut(&mut self) -> &mut SliceVec<u8> {
+    self
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>> Buffer for Vec<'a, u8, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+{
+  fn as_slice_vec(&self) -> &SliceVec<u8> {
+    self
+  }
+
+  fn as_slice_vec_mut(&mut self) -> &mut SliceVec<u8> {
+    self
+  }
+}
+
+/// Fills the rest of the buffer's capacity with zeros, so all of it can be read into.
+///
+/// Panics if the buffer has no capacity, since nothing could be buffered in it.
+fn initialize<B: Buffer>(buffer: &mut B) {
+  let vec = buffer.as_slice_vec_mut();
+  assert!(vec.capacity() > 0, "buffer has no capacity");
+  while vec.push(0).is_ok() {}
+}
+
+#[derive(Error, Debug)]
+pub enum BufReadError<E: Error> {
+  #[error("{0}")]
+  Read(E),
+  /// The buffer filled up before the delimiter was found.
+  #[error("line doesn't fit in the buffer")]
+  TooLong,
+  #[error("line isn't valid utf-8: {0}")]
+  Utf8(#[from] Utf8Error),
+}
+
+/// Reads from `R` through a buffer, so small reads don't each need a call to `R`.
+pub struct BufReader<R, B> {
+  reader: R,
+  buffer: B,
+  position: usize,
+  filled: usize,
+}
+
+impl<R: StreamRead, B: Buffer> BufReader<R, B> {
+  /// Panics if `buffer` has no capacity, which would make every read look like the end.
+  pub fn new(reader: R, mut buffer: B) -> Self {
+    initialize(&mut buffer);
+    Self {
+      reader,
+      buffer,
+      position: 0,
+      filled: 0,
+    }
+  }
+
+  pub fn get_ref(&self) -> &R {
+    &self.reader
+  }
+
+  /// Returns the reader. Reading from it directly skips the data that's already buffered.
+  pub fn get_mut(&mut self) -> &mut R {
+    &mut self.reader
+  }
+
+  /// Returns the reader and the buffer, losing the data that's buffered.
+  pub fn into_parts(self) -> (R, B) {
+    (self.reader, self.buffer)
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.buffer.as_slice_vec().len()
+  }
+
+  /// Returns the data that's buffered and hasn't been consumed.
+  pub fn buffer(&self) -> &[u8] {
+    &self.buffer.as_slice_vec()[self.position..self.filled]
+  }
+
+  /// Returns the buffered data, reading more first if it's all been consumed. An empty slice
+  /// means the reader has ended.
+  pub async fn fill_buf(&mut self) -> Result<&[u8], R::Error> {
+    if self.position == self.filled {
+      let buffer = self.buffer.as_slice_vec_mut();
+      self.filled = self.reader.read(buffer).await?.len();
+      self.position = 0;
+    }
+
+    Ok(self.buffer())
+  }
+
+  /// Marks `amount` bytes returned by [fill_buf](Self::fill_buf) as read.
+  pub fn consume(&mut self, amount: usize) {
+    self.position = (self.position + amount).min(self.filled);
+  }
+
+  /// Reads until `delimiter`, and returns what was read including the delimiter. At the end of the
+  /// reader, the rest of the data is returned without it, and then an empty slice.
+  ///
+  /// The data is returned from the buffer, so it fails with [BufReadError::TooLong] if the buffer
+  /// fills up first. What was read is kept in the buffer, and can be read with
+  /// [fill_buf](Self::fill_buf).
+  pub async fn read_until(&mut self, delimiter: u8) -> Result<&[u8], BufReadError<R::Error>> {
+    let mut searched = 0;
+    let end = loop {
+      let buffered = &self.buffer.as_slice_vec()[self.position + searched..self.filled];
+      if let Some(index) = buffered.iter().position(|&byte| byte == delimiter) {
+        break self.position + searched + index + 1;
+      }
+      searched = self.filled - self.position;
+
+      let buffer = self.buffer.as_slice_vec_mut();
+      if self.position > 0 {
+        buffer.copy_within(self.position..self.filled, 0);
+        self.filled -= self.position;
+        self.position = 0;
+      }
+      if self.filled == buffer.len() {
+        return Err(BufReadError::TooLong);
+      }
+
+      let length = self
+        .reader
+        .read(&mut buffer[self.filled..])
+        .await
+        .map_err(BufReadError::Read)?
+        .len();
+      if length == 0 {
+        break self.filled;
+      }
+      self.filled += length;
+    };
+
+    let start = self.position;
+    self.position = end;
+    Ok(&self.buffer.as_slice_vec()[start..end])
+  }
+
+  /// Reads a line, like [read_until](Self::read_until) with `\n`, and checks that it's utf-8.
+  pub async fn read_line(&mut self) -> Result<&str, BufReadError<R::Error>> {
+    Ok(str::from_utf8(self.read_until(b'\n').await?)?)
+  }
+
+  /// Returns the lines that are left, without their `\n` or `\r\n`.
+  pub fn lines(&mut self) -> Lines<'_, R, B> {
+    Lines { reader: self }
+  }
+}
+
+impl<R: StreamRead, B: Buffer> StreamRead for BufReader<R, B> {
+  type Error = R::Error;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], R::Error> {
+    // buffering a read that's at least as big as the buffer wouldn't save any calls
+    if self.position == self.filled && buf.len() >= self.capacity() {
+      return self.reader.read(buf).await;
+    }
+
+    let buffered = self.fill_buf().await?;
+    let length = buffered.len().min(buf.len());
+    buf[..length].copy_from_slice(&buffered[..length]);
+    self.consume(length);
+
+    Ok(&buf[..length])
+  }
+}
+
+/// The lines of a [BufReader]. Each line borrows the reader, so this can't be an [Iterator].
+pub struct Lines<'r, R, B> {
+  reader: &'r mut BufReader<R, B>,
+}
+
+impl<R: StreamRead, B: Buffer> Lines<'_, R, B> {
+  /// Returns the next line, or `None` at the end of the reader.
+  #[allow(clippy::should_implement_trait)]
+  pub async fn next(&mut self) -> Option<Result<&str, BufReadError<R::Error>>> {
+    let line = match self.reader.read_line().await {
+      Ok("") => return None,
+      Ok(line) => line,
+      Err(error) => return Some(Err(error)),
+    };
+
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    Some(Ok(line.strip_suffix('\r').unwrap_or(line)))
+  }
+}
+
+/// Writes to `W` through a buffer, so small writes don't each need a call to `W`.
+///
+/// The buffer isn't flushed when the writer is dropped, so [flush](StreamWrite::flush) has to be
+/// called before then.
+pub struct BufWriter<W, B> {
+  writer: W,
+  buffer: B,
+  filled: usize,
+}
+
+impl<W: StreamWrite, B: Buffer> BufWriter<W, B> {
+  /// Panics if `buffer` has no capacity.
+  pub fn new(writer: W, mut buffer: B) -> Self {
+    initialize(&mut buffer);
+    Self {
+      writer,
+      buffer,
+      filled: 0,
+    }
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.writer
+  }
+
+  /// Returns the writer. Writing to it directly writes before the data that's already buffered.
+  pub fn get_mut(&mut self) -> &mut W {
+    &mut self.writer
+  }
+
+  /// Returns the writer and the buffer, losing the data that hasn't been flushed.
+  pub fn into_parts(self) -> (W, B) {
+    (self.writer, self.buffer)
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.buffer.as_slice_vec().len()
+  }
+
+  /// Returns the data that's buffered and hasn't been written.
+  pub fn buffer(&self) -> &[u8] {
+    &self.buffer.as_slice_vec()[..self.filled]
+  }
+
+  /// Writes all of the buffered data. If that fails, or the writer stops accepting data, what
+  /// wasn't written stays buffered.
+  async fn flush_buffer(&mut self) -> Result<(), IncompleteError<W::Error>> {
+    let mut written = 0;
+    let result = loop {
+      if written == self.filled {
+        break Ok(());
+      }
+
+      match self
+        .writer
+        .write(&self.buffer.as_slice_vec()[written..self.filled])
+        .await
+      {
+        Ok((0, _)) => break Err(IncompleteError::Ended),
+        Ok((length, _)) => written += length,
+        Err(error) => break Err(IncompleteError::Stream(error)),
+      }
+    };
+
+    self
+      .buffer
+      .as_slice_vec_mut()
+      .copy_within(written..self.filled, 0);
+    self.filled -= written;
+    result
+  }
+}
+
+impl<W: StreamWrite, B: Buffer> StreamWrite for BufWriter<W, B> {
+  type Error = IncompleteError<W::Error>;
+
+  /// Buffers all of `data` if it fits, after flushing the buffer if needed. Data that's at least
+  /// as big as the buffer is written directly instead. Fails with [IncompleteError::Ended] if the
+  /// writer stops accepting data while flushing.
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), Self::Error> {
+    if self.filled + data.len() > self.capacity() {
+      self.flush_buffer().await?;
+    }
+    if data.len() >= self.capacity() {
+      return self
+        .writer
+        .write(data)
+        .await
+        .map_err(IncompleteError::Stream);
+    }
+
+    let filled = self.filled;
+    self.buffer.as_slice_vec_mut()[filled..filled + data.len()].copy_from_slice(data);
+    self.filled += data.len();
+
+    Ok((data.len(), &[]))
+  }
+
+  /// Writes all of the buffered data, and flushes the writer. If that fails, what wasn't written
+  /// stays buffered.
+  async fn flush(&mut self) -> Result<(), Self::Error> {
+    self.flush_buffer().await?;
+    self.writer.flush().await.map_err(IncompleteError::Stream)
+  }
+
+  async fn close(&mut self) -> Result<(), Self::Error> {
+    self.flush_buffer().await?;
+    self.writer.close().await.map_err(IncompleteError::Stream)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::convert::Infallible;
+
+  use crate::{
+    alloc::{ForeignAllocator, GrowthStrategy, StdAlloc, strategy::UniqueStrategy, vec::Vec},
+    io::{
+      IncompleteError, StreamRead, StreamWrite,
+      buf::{BufReadError, BufReader, BufWriter},
+    },
+    types::vec::FixedVec,
+  };
+
+  /// Reads `data` at most `chunk` bytes at a time.
+  struct Chunks<'a> {
+    data: &'a [u8],
+    chunk: usize,
+  }
+
+  impl StreamRead for Chunks<'_> {
+    type Error = Infallible;
+
+    async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Infallible> {
+      let length = self.chunk.min(buf.len()).min(self.data.len());
+      buf[..length].copy_from_slice(&self.data[..length]);
+      self.data = &self.data[length..];
+      Ok(&buf[..length])
+    }
+  }
+
+  /// Writes into `data` at most `chunk` bytes at a time, counting the calls.
+  struct Sink {
+    data: FixedVec<u8, 64>,
+    chunk: usize,
+    writes: usize,
+  }
+
+  impl StreamWrite for Sink {
+    type Error = Infallible;
+
+    async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), Infallible> {
+      self.writes += 1;
+      let length = self.chunk.min(data.len());
+      for &byte in &data[..length] {
+        self.data.push(byte).unwrap();
+      }
+      Ok((length, &data[length..]))
+    }
+  }
+
+  #[pollster::test]
+  async fn lines() {
+    let reader = Chunks {
+      data: b"first\r\nsecond line\n\nlast",
+      chunk: 3,
+    };
+    let mut reader = BufReader::new(reader, FixedVec::<u8, 16>::new());
+
+    let mut lines = reader.lines();
+    for expected in ["first", "second line", "", "last"] {
+      assert_eq!(lines.next().await.unwrap().unwrap(), expected);
+    }
+    assert!(lines.next().await.is_none());
+  }
+
+  #[pollster::test]
+  async fn read_until() {
+    let reader = Chunks {
+      data: b"a,bcdefgh,\xff\n",
+      chunk: 4,
+    };
+    let mut reader = BufReader::new(reader, FixedVec::<u8, 4>::new());
+
+    assert_eq!(reader.read_until(b',').await.unwrap(), b"a,");
+    assert!(matches!(
+      reader.read_until(b',').await,
+      Err(BufReadError::TooLong)
+    ));
+    assert_eq!(reader.fill_buf().await.unwrap(), b"bcde");
+    reader.consume(4);
+    assert_eq!(reader.read_until(b',').await.unwrap(), b"fgh,");
+    assert!(matches!(
+      reader.read_line().await,
+      Err(BufReadError::Utf8(_))
+    ));
+    assert_eq!(reader.read_until(b',').await.unwrap(), b"");
+  }
+
+  #[pollster::test]
+  async fn writer() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let buffer = Vec::<u8, UniqueStrategy, _>::with_capacity(&allocator, GrowthStrategy::Exact, 8)
+      .await
+      .unwrap();
+    let sink = Sink {
+      data: FixedVec::new(),
+      chunk: 3,
+      writes: 0,
+    };
+    let mut writer = BufWriter::new(sink, buffer);
+
+    writer.write(b"ab").await.unwrap();
+    writer.write(b"cd").await.unwrap();
+    assert_eq!(writer.get_ref().writes, 0);
+    assert_eq!(writer.buffer(), b"abcd");
+
+    writer.write(b"efghijkl").await.unwrap();
+    assert_eq!(&*writer.get_ref().data, b"abcdefg");
+    writer.flush().await.unwrap();
+    assert_eq!(writer.buffer(), b"");
+    assert_eq!(&*writer.get_ref().data, b"abcdefg");
+  }
+
+  #[pollster::test]
+  async fn writer_ended() {
+    let sink = Sink {
+      data: FixedVec::new(),
+      chunk: 0,
+      writes: 0,
+    };
+    let mut writer = BufWriter::new(sink, FixedVec::<u8, 4>::new());
+
+    writer.write(b"abc").await.unwrap();
+    assert!(matches!(
+      writer.write(b"de").await,
+      Err(IncompleteError::Ended)
+    ));
+    assert!(matches!(writer.flush().await, Err(IncompleteError::Ended)));
+    assert_eq!(writer.buffer(), b"abc");
+    assert_eq!(writer.get_ref().writes, 2);
+  }
+
+  #[pollster::test]
+  #[should_panic = "buffer has no capacity"]
+  async fn empty_buffer() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let buffer = Vec::<u8, UniqueStrategy, _>::new(&allocator, GrowthStrategy::Exponential)
+      .await
+      .unwrap();
+    BufReader::new(
+      Chunks {
+        data: b"",
+        chunk: 1,
+      },
+      buffer,
+    );
+  }
+}
@@ -0,0 +1,105 @@
+use core::convert::Infallible;
+
+use crate::io::{StreamPeek, StreamRead, StreamWrite};
+
+/// A stream over bytes in memory, which reads and writes at a position, like for testing.
+///
+/// Writes overwrite the bytes and never grow them, so they end when the position reaches the end.
+#[derive(Clone, Default, Debug)]
+pub struct Cursor<T> {
+  inner: T,
+  position: usize,
+}
+
+impl<T> Cursor<T> {
+  pub const fn new(inner: T) -> Self {
+    Self { inner, position: 0 }
+  }
+
+  pub fn position(&self) -> usize {
+    self.position
+  }
+
+  /// Moves the position, which can be past the end of the bytes.
+  pub fn set_position(&mut self, position: usize) {
+    self.position = position;
+  }
+
+  pub fn get_ref(&self) -> &T {
+    &self.inner
+  }
+
+  pub fn get_mut(&mut self) -> &mut T {
+    &mut self.inner
+  }
+
+  pub fn into_inner(self) -> T {
+    self.inner
+  }
+}
+
+impl<T: AsRef<[u8]>> Cursor<T> {
+  /// Returns the bytes after the position.
+  pub fn remaining(&self) -> &[u8] {
+    let bytes = self.inner.as_ref();
+    &bytes[self.position.min(bytes.len())..]
+  }
+}
+
+impl<T: AsRef<[u8]>> StreamRead for Cursor<T> {
+  type Error = Infallible;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Infallible> {
+    let data = self.peek(buf).await?;
+    self.position += data.len();
+    Ok(data)
+  }
+}
+
+impl<T: AsRef<[u8]>> StreamPeek for Cursor<T> {
+  type Error = Infallible;
+
+  async fn peek<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Infallible> {
+    let remaining = self.remaining();
+    let length = remaining.len().min(buf.len());
+    buf[..length].copy_from_slice(&remaining[..length]);
+    Ok(&buf[..length])
+  }
+}
+
+impl<T: AsMut<[u8]>> StreamWrite for Cursor<T> {
+  type Error = Infallible;
+
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), Infallible> {
+    let bytes = self.inner.as_mut();
+    let start = self.position.min(bytes.len());
+    let length = (bytes.len() - start).min(data.len());
+    bytes[start..start + length].copy_from_slice(&data[..length]);
+    self.position = start + length;
+
+    Ok((length, &data[length..]))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::io::{Cursor, StreamPeek, StreamRead, StreamWrite};
+
+  #[pollster::test]
+  async fn read_write_peek() {
+    let mut cursor = Cursor::new([0; 8]);
+    assert_eq!(cursor.write(b"hello").await.unwrap().0, 5);
+    assert_eq!(cursor.position(), 5);
+
+    cursor.set_position(0);
+    let mut buf = [0; 4];
+    assert_eq!(cursor.peek(&mut buf).await.unwrap(), b"hell");
+    assert_eq!(cursor.read(&mut buf).await.unwrap(), b"hell");
+    assert_eq!(cursor.remaining(), b"o\0\0\0");
+
+    cursor.set_position(6);
+    let (written, remaining) = cursor.write(b"world").await.unwrap();
+    assert_eq!((written, remaining), (2, &b"rld"[..]));
+    assert_eq!(cursor.read(&mut buf).await.unwrap(), b"");
+  }
+}
@@ -0,0 +1,203 @@
+use core::{error::Error, ffi::CStr};
+
+use crate::io::{Io, StreamRead, StreamWrite};
+
+/// How a file is opened, like `open`'s flags.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OpenOptions {
+  pub(crate) read: bool,
+  pub(crate) write: bool,
+  pub(crate) append: bool,
+  pub(crate) truncate: bool,
+  pub(crate) create: bool,
+  pub(crate) create_new: bool,
+  pub(crate) mode: u32,
+}
+
+impl OpenOptions {
+  /// Options that open nothing until access is set with [read](Self::read), [write](Self::write)
+  /// or [append](Self::append). Created files get the mode `0o666`, minus the umask.
+  pub const fn new() -> Self {
+    Self {
+      read: false,
+      write: false,
+      append: false,
+      truncate: false,
+      create: false,
+      create_new: false,
+      mode: 0o666,
+    }
+  }
+
+  pub const fn read(mut self, value: bool) -> Self {
+    self.read = value;
+    self
+  }
+
+  pub const fn write(mut self, value: bool) -> Self {
+    self.write = value;
+    self
+  }
+
+  /// Writes at the end of the file, even after seeking. Implies [write](Self::write).
+  pub const fn append(mut self, value: bool) -> Self {
+    self.append = value;
+    self
+  }
+
+  /// Truncates the file to 0 bytes when it's opened.
+  pub const fn truncate(mut self, value: bool) -> Self {
+    self.truncate = value;
+    self
+  }
+
+  /// Creates the file if it doesn't exist.
+  pub const fn create(mut self, value: bool) -> Self {
+    self.create = value;
+    self
+  }
+
+  /// Creates the file, failing if it already exists.
+  pub const fn create_new(mut self, value: bool) -> Self {
+    self.create_new = value;
+    self
+  }
+
+  /// The permissions of created files, before the umask is applied.
+  pub const fn mode(mut self, mode: u32) -> Self {
+    self.mode = mode;
+    self
+  }
+}
+
+impl Default for OpenOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SeekFrom {
+  Start(u64),
+  End(i64),
+  Current(i64),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileType {
+  File,
+  Directory,
+  Symlink,
+  BlockDevice,
+  CharacterDevice,
+  Fifo,
+  Socket,
+  Unknown,
+}
+
+/// A point in time, as the time since the Unix epoch.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Timestamp {
+  pub seconds: i64,
+  pub nanoseconds: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Metadata {
+  pub file_type: FileType,
+  /// The permission bits of the file's mode.
+  pub permissions: u32,
+  pub size: u64,
+  pub inode: u64,
+  pub links: u64,
+  pub user_id: u32,
+  pub group_id: u32,
+  pub accessed: Timestamp,
+  pub modified: Timestamp,
+  /// When the file was created, if the filesystem records it.
+  pub created: Option<Timestamp>,
+}
+
+pub trait FileSystem: Io {
+  type Error: Error;
+
+  async fn open<'a>(
+    &'a self,
+    path: &CStr,
+    options: &OpenOptions,
+  ) -> Result<impl File + 'a, Self::Error>;
+
+  /// Returns the metadata of the file at `path`, following symlinks.
+  async fn metadata(&self, path: &CStr) -> Result<Metadata, Self::Error>;
+
+  /// Returns the metadata of the file at `path`, or of the symlink itself if it is one.
+  async fn symlink_metadata(&self, path: &CStr) -> Result<Metadata, Self::Error>;
+
+  async fn create_directory(&self, path: &CStr, mode: u32) -> Result<(), Self::Error>;
+
+  /// Removes the empty directory at `path`.
+  async fn remove_directory(&self, path: &CStr) -> Result<(), Self::Error>;
+
+  async fn remove_file(&self, path: &CStr) -> Result<(), Self::Error>;
+
+  /// Moves `from` to `to`, replacing `to` if it exists.
+  async fn rename(&self, from: &CStr, to: &CStr) -> Result<(), Self::Error>;
+
+  /// Creates a symlink at `link` that points to `target`.
+  async fn symlink(&self, target: &CStr, link: &CStr) -> Result<(), Self::Error>;
+
+  /// Reads the target of the symlink at `path` into `buf`, and returns the part of `buf` it was
+  /// written to. The target is truncated if it doesn't fit.
+  async fn read_link<'a>(&self, path: &CStr, buf: &'a mut [u8]) -> Result<&'a [u8], Self::Error>;
+
+  async fn read_directory<'a>(&'a self, path: &CStr) -> Result<impl Directory + 'a, Self::Error>;
+}
+
+/// An open file. As a [StreamRead] and [StreamWrite], it reads and writes at its position.
+pub trait File: StreamRead + StreamWrite {
+  type Error: Error;
+
+  /// Reads into `buf` at `offset`, without moving the file's position.
+  async fn read_at<'a>(
+    &mut self,
+    buf: &'a mut [u8],
+    offset: u64,
+  ) -> Result<&'a [u8], <Self as File>::Error>;
+
+  /// Writes `data` at `offset`, without moving the file's position. Returns the amount of bytes
+  /// that have been written, and the remaining slice of `data`.
+  async fn write_at<'a>(
+    &mut self,
+    data: &'a [u8],
+    offset: u64,
+  ) -> Result<(usize, &'a [u8]), <Self as File>::Error>;
+
+  /// Moves the file's position, and returns the new position from the start of the file.
+  async fn seek(&mut self, position: SeekFrom) -> Result<u64, <Self as File>::Error>;
+
+  async fn metadata(&self) -> Result<Metadata, <Self as File>::Error>;
+
+  /// Truncates or extends the file to `length` bytes.
+  async fn set_len(&mut self, length: u64) -> Result<(), <Self as File>::Error>;
+
+  /// Waits for the file's data and metadata to reach the disk.
+  async fn sync_all(&mut self) -> Result<(), <Self as File>::Error>;
+
+  /// Waits for the file's data to reach the disk, along with the metadata needed to read it.
+  async fn sync_data(&mut self) -> Result<(), <Self as File>::Error>;
+}
+
+/// An entry read from a [Directory], borrowed until the next one is read.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DirectoryEntry<'a> {
+  pub name: &'a CStr,
+  pub inode: u64,
+  pub file_type: FileType,
+}
+
+pub trait Directory {
+  type Error: Error;
+
+  /// Reads the next entry, skipping `.` and `..`. Returns `None` once every entry has been read.
+  async fn next_entry(&mut self) -> Result<Option<DirectoryEntry<'_>>, Self::Error>;
+}
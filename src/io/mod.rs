@@ -1,12 +1,20 @@
 use core::error::Error;
 
-// pub mod fs;
+use thiserror::Error;
+
+pub mod buf;
+mod cursor;
+pub mod fs;
 pub mod net;
 mod platform;
 pub mod stdio;
+mod util;
 
+pub use buf::{BufReader, BufWriter};
+pub use cursor::Cursor;
 #[cfg(target_os = "linux")]
 pub use platform::TargetIo;
+pub use util::{Chain, CopyError, ReadToEndError, StreamReadExt, Take, Tee, copy};
 
 pub trait Io {}
 
@@ -17,13 +25,41 @@ pub trait StreamWrite {
   /// Returns the amount of bytes that have been written, and the remaining slice of `data`.
   async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), Self::Error>;
 
-  /// Writes all of `data` to the stream.
-  async fn write_all<'a>(&mut self, mut data: &'a [u8]) -> Result<(), Self::Error> {
-    loop {
-      let (_, remaining) = self.write(data).await?;
+  /// Writes all of `data` to the stream. Fails with [IncompleteError::Ended] if the stream stops
+  /// accepting data first.
+  async fn write_all(&mut self, mut data: &[u8]) -> Result<(), IncompleteError<Self::Error>> {
+    while !data.is_empty() {
+      let (written, remaining) = self.write(data).await.map_err(IncompleteError::Stream)?;
+      if written == 0 {
+        return Err(IncompleteError::Ended);
+      }
 
       data = remaining;
     }
+
+    Ok(())
+  }
+
+  /// Writes the buffers in order, and returns the amount of bytes that have been written. Like
+  /// [write](Self::write), this can end without writing all of them.
+  ///
+  /// By default, only the first buffer that isn't empty is written.
+  async fn write_vectored(&mut self, bufs: &[&[u8]]) -> Result<usize, Self::Error> {
+    match bufs.iter().find(|buf| !buf.is_empty()) {
+      Some(buf) => Ok(self.write(buf).await?.0),
+      None => Ok(0),
+    }
+  }
+
+  /// Writes any data the stream is holding on to, like in a buffer.
+  async fn flush(&mut self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  /// Flushes the stream, and tells the other end that nothing more will be written, if the stream
+  /// has a way to. The stream shouldn't be written to afterwards.
+  async fn close(&mut self) -> Result<(), Self::Error> {
+    self.flush().await
   }
 }
 
@@ -31,6 +67,26 @@ pub trait StreamRead {
   type Error: Error;
 
   async fn read<'a, 'b>(&'b mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Self::Error>;
+
+  /// Reads into the buffers in order, and returns the amount of bytes that have been read.
+  ///
+  /// By default, only the first buffer that isn't empty is read into.
+  async fn read_vectored(&mut self, bufs: &mut [&mut [u8]]) -> Result<usize, Self::Error> {
+    match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+      Some(buf) => Ok(self.read(buf).await?.len()),
+      None => Ok(0),
+    }
+  }
+}
+
+/// The error of an operation that has to transfer all of its data, like [StreamWrite::write_all].
+#[derive(Error, Debug)]
+pub enum IncompleteError<E: Error> {
+  #[error("{0}")]
+  Stream(E),
+  /// The stream ended, or stopped accepting data, before all of it was transferred.
+  #[error("stream ended before all of the data was transferred")]
+  Ended,
 }
 
 pub trait StreamPeek {
@@ -47,7 +103,9 @@ pub trait StreamSplit {
 pub trait DatagramReader {
   type Error: Error;
 
-  async fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+  /// Reads one datagram into `buf`, and returns the part of `buf` it was written to. The rest of
+  /// a datagram that doesn't fit in `buf` is discarded.
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Self::Error>;
 }
 
 pub trait DatagramWriter {
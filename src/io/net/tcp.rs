@@ -33,3 +33,19 @@ pub trait TcpSocketNagle: TcpSocket {
 
   fn set_nagle_algorithm(&self, value: bool) -> Result<(), <Self as TcpSocketNagle>::Error>;
 }
+
+/// Which halves of a connection [TcpSocketShutdown::shutdown] closes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Shutdown {
+  Read,
+  Write,
+  Both,
+}
+
+pub trait TcpSocketShutdown: TcpSocket {
+  type Error: Error;
+
+  /// Closes the read half, write half, or both halves of the connection. Reads after the read half
+  /// is closed return no data, and the peer's reads do after the write half is closed.
+  fn shutdown(&self, how: Shutdown) -> Result<(), <Self as TcpSocketShutdown>::Error>;
+}
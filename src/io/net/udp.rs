@@ -0,0 +1,72 @@
+use core::{
+  error::Error,
+  net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use crate::io::{DatagramReader, DatagramWriter, Io};
+
+pub trait UdpIo: Io {
+  type Error: Error;
+
+  async fn bind<'a>(&'a self, address: SocketAddr) -> Result<impl UdpSocket + 'a, Self::Error>;
+}
+
+/// A UDP socket. As a [DatagramReader] and [DatagramWriter], it reads from and writes to the
+/// address it's connected to.
+pub trait UdpSocket: DatagramReader + DatagramWriter {
+  type Error: Error;
+
+  fn local_address(&self) -> SocketAddr;
+
+  /// Sets the address datagrams are written to, and the only address they're read from.
+  async fn connect(&mut self, address: SocketAddr) -> Result<(), <Self as UdpSocket>::Error>;
+
+  /// Sends `data` as one datagram to `address`.
+  async fn send_to(
+    &mut self,
+    data: &[u8],
+    address: SocketAddr,
+  ) -> Result<(), <Self as UdpSocket>::Error>;
+
+  /// Receives one datagram into `buf`, and returns the part of `buf` it was written to along with
+  /// the address it came from.
+  async fn recv_from<'a>(
+    &mut self,
+    buf: &'a mut [u8],
+  ) -> Result<(&'a [u8], SocketAddr), <Self as UdpSocket>::Error>;
+
+  /// Allows sending datagrams to broadcast addresses.
+  fn set_broadcast(&self, value: bool) -> Result<(), <Self as UdpSocket>::Error>;
+}
+
+pub trait UdpSocketMulticast: UdpSocket {
+  type Error: Error;
+
+  /// Joins the IPv4 multicast `group` on the interface with the address `interface`, or any
+  /// interface if it's [Ipv4Addr::UNSPECIFIED].
+  fn join_multicast_v4(
+    &self,
+    group: Ipv4Addr,
+    interface: Ipv4Addr,
+  ) -> Result<(), <Self as UdpSocketMulticast>::Error>;
+
+  fn leave_multicast_v4(
+    &self,
+    group: Ipv4Addr,
+    interface: Ipv4Addr,
+  ) -> Result<(), <Self as UdpSocketMulticast>::Error>;
+
+  /// Joins the IPv6 multicast `group` on the interface with the index `interface`, or any
+  /// interface if it's 0.
+  fn join_multicast_v6(
+    &self,
+    group: Ipv6Addr,
+    interface: u32,
+  ) -> Result<(), <Self as UdpSocketMulticast>::Error>;
+
+  fn leave_multicast_v6(
+    &self,
+    group: Ipv6Addr,
+    interface: u32,
+  ) -> Result<(), <Self as UdpSocketMulticast>::Error>;
+}
@@ -0,0 +1,163 @@
+use core::error::Error;
+
+use crate::io::{DatagramReader, DatagramWriter, Io, StreamRead, StreamWrite};
+
+/// The longest path or abstract name a [UnixSocketAddress] can hold.
+pub const MAX_ADDRESS_LENGTH: usize = 107;
+
+/// The address of a Unix socket, which is unnamed, a path in the filesystem, or a name in the
+/// abstract namespace that isn't visible in the filesystem.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnixSocketAddress {
+  kind: UnixAddressKind,
+  bytes: [u8; MAX_ADDRESS_LENGTH],
+  length: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum UnixAddressKind {
+  Unnamed,
+  Pathname,
+  Abstract,
+}
+
+impl UnixSocketAddress {
+  pub const fn unnamed() -> Self {
+    Self {
+      kind: UnixAddressKind::Unnamed,
+      bytes: [0; MAX_ADDRESS_LENGTH],
+      length: 0,
+    }
+  }
+
+  const fn new(kind: UnixAddressKind, name: &[u8]) -> Option<Self> {
+    if name.len() > MAX_ADDRESS_LENGTH {
+      return None;
+    }
+
+    let mut bytes = [0; MAX_ADDRESS_LENGTH];
+    bytes.split_at_mut(name.len()).0.copy_from_slice(name);
+    Some(Self {
+      kind,
+      bytes,
+      length: name.len() as u8,
+    })
+  }
+
+  /// An address at `path` in the filesystem. Returns `None` if the path is empty, too long, or
+  /// contains a nul byte.
+  pub fn from_pathname(path: &[u8]) -> Option<Self> {
+    if path.is_empty() || path.contains(&0) {
+      return None;
+    }
+
+    Self::new(UnixAddressKind::Pathname, path)
+  }
+
+  /// An address in the abstract namespace. Returns `None` if the name is too long.
+  pub const fn from_abstract(name: &[u8]) -> Option<Self> {
+    Self::new(UnixAddressKind::Abstract, name)
+  }
+
+  pub fn is_unnamed(&self) -> bool {
+    self.kind == UnixAddressKind::Unnamed
+  }
+
+  pub fn as_pathname(&self) -> Option<&[u8]> {
+    (self.kind == UnixAddressKind::Pathname).then(|| self.name())
+  }
+
+  pub fn as_abstract(&self) -> Option<&[u8]> {
+    (self.kind == UnixAddressKind::Abstract).then(|| self.name())
+  }
+
+  fn name(&self) -> &[u8] {
+    &self.bytes[..self.length as usize]
+  }
+}
+
+impl core::fmt::Debug for UnixSocketAddress {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self.kind {
+      UnixAddressKind::Unnamed => f.write_str("(unnamed)"),
+      UnixAddressKind::Pathname => write!(f, "{:?} (pathname)", self.name().escape_ascii()),
+      UnixAddressKind::Abstract => write!(f, "{:?} (abstract)", self.name().escape_ascii()),
+    }
+  }
+}
+
+/// The credentials of the process on the other end of a Unix socket, from when it connected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UnixCredentials {
+  pub process_id: i32,
+  pub user_id: u32,
+  pub group_id: u32,
+}
+
+pub trait UnixIo: Io {
+  type Error: Error;
+
+  async fn connect<'a>(
+    &'a self,
+    address: &UnixSocketAddress,
+  ) -> Result<impl UnixStream + 'a, Self::Error>;
+
+  async fn listen<'a>(
+    &'a self,
+    address: &UnixSocketAddress,
+  ) -> Result<impl UnixListener + 'a, Self::Error>;
+
+  async fn bind_datagram<'a>(
+    &'a self,
+    address: &UnixSocketAddress,
+  ) -> Result<impl UnixDatagram + 'a, Self::Error>;
+
+  /// Creates a pair of unnamed streams connected to each other.
+  fn stream_pair<'a>(&'a self)
+  -> Result<(impl UnixStream + 'a, impl UnixStream + 'a), Self::Error>;
+
+  /// Creates a pair of unnamed datagram sockets connected to each other.
+  fn datagram_pair<'a>(
+    &'a self,
+  ) -> Result<(impl UnixDatagram + 'a, impl UnixDatagram + 'a), Self::Error>;
+}
+
+pub trait UnixListener {
+  type Error: Error;
+
+  fn local_address(&self) -> Result<UnixSocketAddress, Self::Error>;
+
+  async fn accept(&mut self) -> Result<impl UnixStream, Self::Error>;
+}
+
+pub trait UnixStream: StreamRead + StreamWrite {
+  type Error: Error;
+
+  fn local_address(&self) -> Result<UnixSocketAddress, <Self as UnixStream>::Error>;
+  fn peer_address(&self) -> Result<UnixSocketAddress, <Self as UnixStream>::Error>;
+  fn peer_credentials(&self) -> Result<UnixCredentials, <Self as UnixStream>::Error>;
+}
+
+/// A Unix datagram socket. As a [DatagramReader] and [DatagramWriter], it reads from and writes to
+/// the address it's connected to.
+pub trait UnixDatagram: DatagramReader + DatagramWriter {
+  type Error: Error;
+
+  fn local_address(&self) -> Result<UnixSocketAddress, <Self as UnixDatagram>::Error>;
+
+  async fn connect(
+    &mut self,
+    address: &UnixSocketAddress,
+  ) -> Result<(), <Self as UnixDatagram>::Error>;
+
+  async fn send_to(
+    &mut self,
+    data: &[u8],
+    address: &UnixSocketAddress,
+  ) -> Result<(), <Self as UnixDatagram>::Error>;
+
+  async fn recv_from<'a>(
+    &mut self,
+    buf: &'a mut [u8],
+  ) -> Result<(&'a [u8], UnixSocketAddress), <Self as UnixDatagram>::Error>;
+}
@@ -0,0 +1,348 @@
+use core::{error::Error, mem, ops::DerefMut};
+
+use thiserror::Error;
+
+use crate::{
+  alloc::{SliceAllocator, SliceDst, UnsizedMaybeUninit, strategy::Strategy, types::vec::Vec},
+  io::{IncompleteError, StreamRead, StreamWrite},
+  types::vec::SliceVec,
+};
+
+/// How many bytes [StreamReadExt::read_to_end] grows its vec by when it's full.
+const READ_TO_END_CHUNK: usize = 256;
+
+#[derive(Error, Debug)]
+pub enum ReadToEndError<R: Error, A: Error> {
+  #[error("{0}")]
+  Read(R),
+  #[error("{0}")]
+  Allocator(A),
+}
+
+/// The error of [copy] or a [Tee], from either side.
+#[derive(Error, Debug)]
+pub enum CopyError<R: Error, W: Error> {
+  #[error("{0}")]
+  Read(R),
+  #[error("{0}")]
+  Write(W),
+  /// The writer stopped accepting data before all of it was written.
+  #[error("writer ended before all of the data was written")]
+  WriterEnded,
+}
+
+impl<R: Error, W: Error> CopyError<R, W> {
+  fn from_write(error: IncompleteError<W>) -> Self {
+    match error {
+      IncompleteError::Stream(error) => CopyError::Write(error),
+      IncompleteError::Ended => CopyError::WriterEnded,
+    }
+  }
+}
+
+pub trait StreamReadExt: StreamRead {
+  /// Reads until `buf` is full. Fails with [IncompleteError::Ended] if the stream ends first, in
+  /// which case what was read is in `buf` but it's unknown how much.
+  async fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<(), IncompleteError<Self::Error>> {
+    while !buf.is_empty() {
+      let length = self.read(buf).await.map_err(IncompleteError::Stream)?.len();
+      if length == 0 {
+        return Err(IncompleteError::Ended);
+      }
+
+      buf = &mut mem::take(&mut buf)[length..];
+    }
+
+    Ok(())
+  }
+
+  /// Reads until the stream ends, and appends what was read to `vec`, growing it as needed.
+  /// Returns the amount of bytes that have been read.
+  async fn read_to_end<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>>(
+    &mut self,
+    vec: &mut Vec<'a, u8, S, A>,
+  ) -> Result<usize, ReadToEndError<Self::Error, A::Error>>
+  where
+    S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+  {
+    let start = vec.len();
+    loop {
+      if vec.is_full() {
+        vec
+          .grow(READ_TO_END_CHUNK)
+          .await
+          .map_err(ReadToEndError::Allocator)?;
+      }
+
+      // the spare capacity is zeroed so it can be read into
+      let length = vec.len();
+      while vec.push(0).is_ok() {}
+      let read = match self.read(&mut vec[length..]).await {
+        Ok(data) => data.len(),
+        Err(error) => {
+          vec.truncate(length);
+          return Err(ReadToEndError::Read(error));
+        }
+      };
+      vec.truncate(length + read);
+
+      if read == 0 {
+        return Ok(vec.len() - start);
+      }
+    }
+  }
+
+  /// Returns a reader that ends after `limit` bytes have been read.
+  fn take(self, limit: u64) -> Take<Self>
+  where
+    Self: Sized,
+  {
+    Take {
+      reader: self,
+      limit,
+    }
+  }
+
+  /// Returns a reader that reads from `next` once this one has ended.
+  fn chain<N: StreamRead<Error = Self::Error>>(self, next: N) -> Chain<Self, N>
+  where
+    Self: Sized,
+  {
+    Chain {
+      first: self,
+      second: next,
+      first_ended: false,
+    }
+  }
+
+  /// Returns a reader that writes everything it reads to `writer`.
+  fn tee<W: StreamWrite>(self, writer: W) -> Tee<Self, W>
+  where
+    Self: Sized,
+  {
+    Tee {
+      reader: self,
+      writer,
+    }
+  }
+}
+
+impl<R: StreamRead + ?Sized> StreamReadExt for R {}
+
+/// Writes everything `reader` reads to `writer`, using `buf` in between. Returns the amount of
+/// bytes that have been copied.
+pub async fn copy<R: StreamRead + ?Sized, W: StreamWrite + ?Sized>(
+  reader: &mut R,
+  writer: &mut W,
+  buf: &mut [u8],
+) -> Result<u64, CopyError<R::Error, W::Error>> {
+  let mut copied = 0;
+  loop {
+    let data = reader.read(buf).await.map_err(CopyError::Read)?;
+    if data.is_empty() {
+      return Ok(copied);
+    }
+
+    writer
+      .write_all(data)
+      .await
+      .map_err(CopyError::from_write)?;
+    copied += data.len() as u64;
+  }
+}
+
+/// A reader that ends after a limit, from [StreamReadExt::take].
+pub struct Take<R> {
+  reader: R,
+  limit: u64,
+}
+
+impl<R> Take<R> {
+  /// Returns how many bytes can still be read.
+  pub fn limit(&self) -> u64 {
+    self.limit
+  }
+
+  pub fn set_limit(&mut self, limit: u64) {
+    self.limit = limit;
+  }
+
+  pub fn get_ref(&self) -> &R {
+    &self.reader
+  }
+
+  pub fn get_mut(&mut self) -> &mut R {
+    &mut self.reader
+  }
+
+  pub fn into_inner(self) -> R {
+    self.reader
+  }
+}
+
+impl<R: StreamRead> StreamRead for Take<R> {
+  type Error = R::Error;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], R::Error> {
+    let length = buf.len().min(self.limit.try_into().unwrap_or(usize::MAX));
+    if length == 0 {
+      return Ok(&buf[..0]);
+    }
+
+    let data = self.reader.read(&mut buf[..length]).await?;
+    self.limit -= data.len() as u64;
+    Ok(data)
+  }
+}
+
+/// Two readers read one after the other, from [StreamReadExt::chain].
+pub struct Chain<A, B> {
+  first: A,
+  second: B,
+  first_ended: bool,
+}
+
+impl<A, B> Chain<A, B> {
+  pub fn get_ref(&self) -> (&A, &B) {
+    (&self.first, &self.second)
+  }
+
+  pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+    (&mut self.first, &mut self.second)
+  }
+
+  pub fn into_inner(self) -> (A, B) {
+    (self.first, self.second)
+  }
+}
+
+impl<A: StreamRead, B: StreamRead<Error = A::Error>> StreamRead for Chain<A, B> {
+  type Error = A::Error;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], A::Error> {
+    if !self.first_ended {
+      let length = self.first.read(buf).await?.len();
+      // an empty read into an empty buffer doesn't mean the reader has ended
+      if length > 0 || buf.is_empty() {
+        return Ok(&buf[..length]);
+      }
+
+      self.first_ended = true;
+    }
+
+    self.second.read(buf).await
+  }
+}
+
+/// A reader that writes everything it reads to a writer, from [StreamReadExt::tee].
+pub struct Tee<R, W> {
+  reader: R,
+  writer: W,
+}
+
+impl<R, W> Tee<R, W> {
+  pub fn get_ref(&self) -> (&R, &W) {
+    (&self.reader, &self.writer)
+  }
+
+  pub fn get_mut(&mut self) -> (&mut R, &mut W) {
+    (&mut self.reader, &mut self.writer)
+  }
+
+  pub fn into_inner(self) -> (R, W) {
+    (self.reader, self.writer)
+  }
+}
+
+impl<R: StreamRead, W: StreamWrite> StreamRead for Tee<R, W> {
+  type Error = CopyError<R::Error, W::Error>;
+
+  /// Reads from the reader, and writes all of what was read to the writer before returning it.
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Self::Error> {
+    let data = self.reader.read(buf).await.map_err(CopyError::Read)?;
+    self
+      .writer
+      .write_all(data)
+      .await
+      .map_err(CopyError::from_write)?;
+
+    Ok(data)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    alloc::{ForeignAllocator, GrowthStrategy, StdAlloc, strategy::UniqueStrategy, vec::Vec},
+    io::{CopyError, Cursor, IncompleteError, StreamRead, StreamReadExt, StreamWrite, copy},
+  };
+
+  #[pollster::test]
+  async fn write_all_ends() {
+    let mut buf = [0; 4];
+    let mut cursor = Cursor::new(&mut buf[..]);
+    cursor.write_all(b"ab").await.unwrap();
+    assert!(matches!(
+      cursor.write_all(b"cde").await,
+      Err(IncompleteError::Ended)
+    ));
+    assert_eq!(&buf, b"abcd");
+  }
+
+  #[pollster::test]
+  async fn read_exact() {
+    let mut reader = Cursor::new(b"hello");
+    let mut buf = [0; 3];
+    reader.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hel");
+    assert!(matches!(
+      reader.read_exact(&mut buf).await,
+      Err(IncompleteError::Ended)
+    ));
+  }
+
+  #[pollster::test]
+  async fn read_to_end() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut vec = Vec::<u8, UniqueStrategy, _>::new(&allocator, GrowthStrategy::Exponential)
+      .await
+      .unwrap();
+    vec.extend(*b"> ").await.unwrap();
+
+    let data = [7; 1000];
+    let read = Cursor::new(&data).read_to_end(&mut vec).await.unwrap();
+    assert_eq!(read, 1000);
+    assert_eq!(&vec[..2], b"> ");
+    assert_eq!(&vec[2..], &data);
+  }
+
+  #[pollster::test]
+  async fn combinators() {
+    let mut teed = [0; 16];
+    let mut reader = Cursor::new(b"first")
+      .take(3)
+      .chain(Cursor::new(b"second"))
+      .tee(Cursor::new(&mut teed[..]));
+
+    let mut buf = [0; 16];
+    assert_eq!(reader.read(&mut buf).await.unwrap(), b"fir");
+    assert_eq!(reader.read(&mut buf).await.unwrap(), b"second");
+    assert_eq!(reader.read(&mut buf).await.unwrap(), b"");
+    let (_, teed) = reader.into_inner();
+    assert_eq!(&teed.into_inner()[..9], b"firsecond");
+
+    let mut output = [0; 4];
+    let mut writer = Cursor::new(&mut output[..]);
+    let copied = copy(&mut Cursor::new(b"abc"), &mut writer, &mut [0; 2])
+      .await
+      .unwrap();
+    assert_eq!(copied, 3);
+    assert!(matches!(
+      copy(&mut Cursor::new(b"def"), &mut writer, &mut [0; 2]).await,
+      Err(CopyError::WriterEnded)
+    ));
+  }
+}
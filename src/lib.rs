@@ -4,10 +4,11 @@
 #![allow(dead_code)]
 #![allow(async_fn_in_trait)]
 #![allow(refining_impl_trait)]
+#![allow(incomplete_features)]
 #![feature(derive_coerce_pointee, phantom_variance_markers, ptr_metadata)]
 #![feature(more_maybe_bounds, trusted_len, prelude_import)]
 #![feature(never_type, layout_for_ptr, deref_pure_trait, sync_unsafe_cell)]
-#![feature(lang_items)]
+#![feature(lang_items, generic_const_exprs, clone_to_uninit)]
 #![feature(linkage)]
 #![cfg_attr(test, feature(assert_matches))]
 
@@ -35,7 +36,7 @@ pub mod prelude {
     Allocator, SliceDst,
     strategy::{PinStrategyHandle, Rc, StrategyHandle, UninitStrategyHandleExt, Unique},
   };
-  pub use crate::println;
+  pub use crate::{eprint, eprintln, print, println};
   #[doc(hidden)]
   pub(crate) use aubystd_macros::aubystd_bikeshed_name;
   pub use core::prelude::rust_2024::*;
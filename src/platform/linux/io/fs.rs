@@ -0,0 +1,564 @@
+use core::{ffi::CStr, mem::MaybeUninit};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::{
+  io::{
+    StreamRead, StreamWrite,
+    fs::{
+      Directory, DirectoryEntry, File, FileSystem, FileType, Metadata, OpenOptions, SeekFrom,
+      Timestamp,
+    },
+  },
+  platform::linux::{
+    FileDescriptor, OwnedFileDescriptor,
+    io::{LinuxError, LinuxIo, stream::IoVectors},
+  },
+};
+
+/// How many bytes of entries are read from a directory at once.
+const DIRECTORY_BUFFER_SIZE: usize = 4096;
+
+/// The fields of `linux_dirent64` before its name.
+const DIRECTORY_ENTRY_HEADER: usize = 19;
+
+const STATX_MASK: u32 = libc::STATX_BASIC_STATS | libc::STATX_BTIME;
+
+// epoll can't wait for regular files or directories, which are always ready, so everything here is
+// a plain blocking syscall
+impl FileSystem for LinuxIo {
+  type Error = LinuxError;
+
+  async fn open(&self, path: &CStr, options: &OpenOptions) -> Result<LinuxFile, LinuxError> {
+    let access = match (options.read, options.write || options.append) {
+      (true, false) => libc::O_RDONLY,
+      (false, true) => libc::O_WRONLY,
+      (true, true) => libc::O_RDWR,
+      (false, false) => return Err(LinuxError(Errno::EINVAL)),
+    };
+
+    let mut flags = access | libc::O_CLOEXEC;
+    if options.append {
+      flags |= libc::O_APPEND;
+    }
+    if options.truncate {
+      flags |= libc::O_TRUNC;
+    }
+    if options.create_new {
+      flags |= libc::O_CREAT | libc::O_EXCL;
+    } else if options.create {
+      flags |= libc::O_CREAT;
+    }
+
+    let file_descriptor = open(path, flags, options.mode).map_err(LinuxError)?;
+    Ok(LinuxFile { file_descriptor })
+  }
+
+  async fn metadata(&self, path: &CStr) -> Result<Metadata, LinuxError> {
+    statx(libc::AT_FDCWD, path, 0).map_err(LinuxError)
+  }
+
+  async fn symlink_metadata(&self, path: &CStr) -> Result<Metadata, LinuxError> {
+    statx(libc::AT_FDCWD, path, libc::AT_SYMLINK_NOFOLLOW).map_err(LinuxError)
+  }
+
+  async fn create_directory(&self, path: &CStr, mode: u32) -> Result<(), LinuxError> {
+    unsafe { syscall!(Sysno::mkdirat, libc::AT_FDCWD, path.as_ptr(), mode) }.map_err(LinuxError)?;
+    Ok(())
+  }
+
+  async fn remove_directory(&self, path: &CStr) -> Result<(), LinuxError> {
+    unlink(path, libc::AT_REMOVEDIR).map_err(LinuxError)
+  }
+
+  async fn remove_file(&self, path: &CStr) -> Result<(), LinuxError> {
+    unlink(path, 0).map_err(LinuxError)
+  }
+
+  async fn rename(&self, from: &CStr, to: &CStr) -> Result<(), LinuxError> {
+    unsafe {
+      syscall!(
+        Sysno::renameat2,
+        libc::AT_FDCWD,
+        from.as_ptr(),
+        libc::AT_FDCWD,
+        to.as_ptr(),
+        0
+      )
+    }
+    .map_err(LinuxError)?;
+    Ok(())
+  }
+
+  async fn symlink(&self, target: &CStr, link: &CStr) -> Result<(), LinuxError> {
+    unsafe {
+      syscall!(
+        Sysno::symlinkat,
+        target.as_ptr(),
+        libc::AT_FDCWD,
+        link.as_ptr()
+      )
+    }
+    .map_err(LinuxError)?;
+    Ok(())
+  }
+
+  async fn read_link<'a>(&self, path: &CStr, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    let length = unsafe {
+      syscall!(
+        Sysno::readlinkat,
+        libc::AT_FDCWD,
+        path.as_ptr(),
+        buf.as_mut_ptr(),
+        buf.len()
+      )
+    }
+    .map_err(LinuxError)?;
+
+    Ok(&buf[..length])
+  }
+
+  async fn read_directory(&self, path: &CStr) -> Result<LinuxDirectory, LinuxError> {
+    let file_descriptor = open(
+      path,
+      libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC,
+      0,
+    )
+    .map_err(LinuxError)?;
+
+    Ok(LinuxDirectory {
+      file_descriptor,
+      buffer: DirectoryBuffer([0; DIRECTORY_BUFFER_SIZE]),
+      position: 0,
+      length: 0,
+    })
+  }
+}
+
+/// Runs `syscall` until it isn't interrupted by a signal, which reads and writes to FIFOs and
+/// terminals can be while they block.
+fn retry_interrupted(mut syscall: impl FnMut() -> Result<usize, Errno>) -> Result<usize, Errno> {
+  loop {
+    match syscall() {
+      Err(error) if error == Errno::EINTR => {}
+      result => return result,
+    }
+  }
+}
+
+fn open(path: &CStr, flags: i32, mode: u32) -> Result<OwnedFileDescriptor, Errno> {
+  // opening a FIFO blocks until the other end is opened too
+  let file_descriptor = retry_interrupted(|| unsafe {
+    syscall!(Sysno::openat, libc::AT_FDCWD, path.as_ptr(), flags, mode)
+  })?;
+  Ok(OwnedFileDescriptor::new(file_descriptor as _))
+}
+
+fn unlink(path: &CStr, flags: i32) -> Result<(), Errno> {
+  unsafe { syscall!(Sysno::unlinkat, libc::AT_FDCWD, path.as_ptr(), flags) }?;
+  Ok(())
+}
+
+fn statx(directory: i32, path: &CStr, flags: i32) -> Result<Metadata, Errno> {
+  let mut statx = MaybeUninit::<libc::statx>::uninit();
+  unsafe {
+    syscall!(
+      Sysno::statx,
+      directory,
+      path.as_ptr(),
+      flags,
+      STATX_MASK,
+      statx.as_mut_ptr()
+    )
+  }?;
+  // Safety: statx succeeded, so the kernel filled the struct
+  let statx = unsafe { statx.assume_init() };
+
+  let timestamp = |timestamp: libc::statx_timestamp| Timestamp {
+    seconds: timestamp.tv_sec,
+    nanoseconds: timestamp.tv_nsec,
+  };
+  let mode = u32::from(statx.stx_mode);
+  Ok(Metadata {
+    file_type: file_type_from_mode(mode),
+    permissions: mode & !libc::S_IFMT,
+    size: statx.stx_size,
+    inode: statx.stx_ino,
+    links: u64::from(statx.stx_nlink),
+    user_id: statx.stx_uid,
+    group_id: statx.stx_gid,
+    accessed: timestamp(statx.stx_atime),
+    modified: timestamp(statx.stx_mtime),
+    created: (statx.stx_mask & libc::STATX_BTIME != 0).then(|| timestamp(statx.stx_btime)),
+  })
+}
+
+fn file_type_from_mode(mode: u32) -> FileType {
+  match mode & libc::S_IFMT {
+    libc::S_IFREG => FileType::File,
+    libc::S_IFDIR => FileType::Directory,
+    libc::S_IFLNK => FileType::Symlink,
+    libc::S_IFBLK => FileType::BlockDevice,
+    libc::S_IFCHR => FileType::CharacterDevice,
+    libc::S_IFIFO => FileType::Fifo,
+    libc::S_IFSOCK => FileType::Socket,
+    _ => FileType::Unknown,
+  }
+}
+
+fn file_type_from_directory_entry(kind: u8) -> FileType {
+  match kind {
+    libc::DT_REG => FileType::File,
+    libc::DT_DIR => FileType::Directory,
+    libc::DT_LNK => FileType::Symlink,
+    libc::DT_BLK => FileType::BlockDevice,
+    libc::DT_CHR => FileType::CharacterDevice,
+    libc::DT_FIFO => FileType::Fifo,
+    libc::DT_SOCK => FileType::Socket,
+    _ => FileType::Unknown,
+  }
+}
+
+/// An open file, which is closed when dropped.
+pub struct LinuxFile {
+  file_descriptor: OwnedFileDescriptor,
+}
+
+impl LinuxFile {
+  pub fn file_descriptor(&self) -> FileDescriptor {
+    self.file_descriptor.get()
+  }
+}
+
+impl StreamRead for LinuxFile {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    let length = retry_interrupted(|| unsafe {
+      syscall!(
+        Sysno::read,
+        self.file_descriptor(),
+        buf.as_mut_ptr(),
+        buf.len()
+      )
+    })
+    .map_err(LinuxError)?;
+
+    Ok(&buf[..length])
+  }
+
+  async fn read_vectored(&mut self, bufs: &mut [&mut [u8]]) -> Result<usize, LinuxError> {
+    let vectors = IoVectors::from_mut(bufs);
+    retry_interrupted(|| unsafe {
+      syscall!(
+        Sysno::readv,
+        self.file_descriptor(),
+        vectors.as_ptr(),
+        vectors.len()
+      )
+    })
+    .map_err(LinuxError)
+  }
+}
+
+impl StreamWrite for LinuxFile {
+  type Error = LinuxError;
+
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), LinuxError> {
+    let length = retry_interrupted(|| unsafe {
+      syscall!(
+        Sysno::write,
+        self.file_descriptor(),
+        data.as_ptr(),
+        data.len()
+      )
+    })
+    .map_err(LinuxError)?;
+
+    Ok((length, &data[length..]))
+  }
+
+  async fn write_vectored(&mut self, bufs: &[&[u8]]) -> Result<usize, LinuxError> {
+    let vectors = IoVectors::from_ref(bufs);
+    retry_interrupted(|| unsafe {
+      syscall!(
+        Sysno::writev,
+        self.file_descriptor(),
+        vectors.as_ptr(),
+        vectors.len()
+      )
+    })
+    .map_err(LinuxError)
+  }
+}
+
+impl File for LinuxFile {
+  type Error = LinuxError;
+
+  async fn read_at<'a>(&mut self, buf: &'a mut [u8], offset: u64) -> Result<&'a [u8], LinuxError> {
+    let length = retry_interrupted(|| unsafe {
+      syscall!(
+        Sysno::pread64,
+        self.file_descriptor(),
+        buf.as_mut_ptr(),
+        buf.len(),
+        offset
+      )
+    })
+    .map_err(LinuxError)?;
+
+    Ok(&buf[..length])
+  }
+
+  async fn write_at<'a>(
+    &mut self,
+    data: &'a [u8],
+    offset: u64,
+  ) -> Result<(usize, &'a [u8]), LinuxError> {
+    let length = retry_interrupted(|| unsafe {
+      syscall!(
+        Sysno::pwrite64,
+        self.file_descriptor(),
+        data.as_ptr(),
+        data.len(),
+        offset
+      )
+    })
+    .map_err(LinuxError)?;
+
+    Ok((length, &data[length..]))
+  }
+
+  async fn seek(&mut self, position: SeekFrom) -> Result<u64, LinuxError> {
+    let (offset, whence) = match position {
+      SeekFrom::Start(offset) => (offset as i64, libc::SEEK_SET),
+      SeekFrom::End(offset) => (offset, libc::SEEK_END),
+      SeekFrom::Current(offset) => (offset, libc::SEEK_CUR),
+    };
+    let position = unsafe { syscall!(Sysno::lseek, self.file_descriptor(), offset, whence) }
+      .map_err(LinuxError)?;
+
+    Ok(position as u64)
+  }
+
+  async fn metadata(&self) -> Result<Metadata, LinuxError> {
+    statx(self.file_descriptor() as _, c"", libc::AT_EMPTY_PATH).map_err(LinuxError)
+  }
+
+  async fn set_len(&mut self, length: u64) -> Result<(), LinuxError> {
+    unsafe { syscall!(Sysno::ftruncate, self.file_descriptor(), length) }.map_err(LinuxError)?;
+    Ok(())
+  }
+
+  async fn sync_all(&mut self) -> Result<(), LinuxError> {
+    unsafe { syscall!(Sysno::fsync, self.file_descriptor()) }.map_err(LinuxError)?;
+    Ok(())
+  }
+
+  async fn sync_data(&mut self) -> Result<(), LinuxError> {
+    unsafe { syscall!(Sysno::fdatasync, self.file_descriptor()) }.map_err(LinuxError)?;
+    Ok(())
+  }
+}
+
+#[repr(C, align(8))]
+struct DirectoryBuffer([u8; DIRECTORY_BUFFER_SIZE]);
+
+/// An open directory, which reads its entries with `getdents64`.
+pub struct LinuxDirectory {
+  file_descriptor: OwnedFileDescriptor,
+  buffer: DirectoryBuffer,
+  position: usize,
+  length: usize,
+}
+
+impl LinuxDirectory {
+  /// The name of the entry starting at `position` in the buffer.
+  fn name(&self, position: usize) -> &CStr {
+    let record_length =
+      u16::from_ne_bytes([self.buffer.0[position + 16], self.buffer.0[position + 17]]) as usize;
+    CStr::from_bytes_until_nul(
+      &self.buffer.0[position + DIRECTORY_ENTRY_HEADER..position + record_length],
+    )
+    .expect("directory entry name isn't nul-terminated")
+  }
+}
+
+impl Directory for LinuxDirectory {
+  type Error = LinuxError;
+
+  async fn next_entry(&mut self) -> Result<Option<DirectoryEntry<'_>>, LinuxError> {
+    let position = loop {
+      if self.position == self.length {
+        let length = unsafe {
+          syscall!(
+            Sysno::getdents64,
+            self.file_descriptor.get(),
+            self.buffer.0.as_mut_ptr(),
+            DIRECTORY_BUFFER_SIZE
+          )
+        }
+        .map_err(LinuxError)?;
+        if length == 0 {
+          return Ok(None);
+        }
+
+        self.position = 0;
+        self.length = length;
+      }
+
+      let position = self.position;
+      let record_length =
+        u16::from_ne_bytes([self.buffer.0[position + 16], self.buffer.0[position + 17]]);
+      self.position += record_length as usize;
+
+      if !matches!(self.name(position).to_bytes(), b"." | b"..") {
+        break position;
+      }
+    };
+
+    let bytes = &self.buffer.0[position..];
+    Ok(Some(DirectoryEntry {
+      name: self.name(position),
+      inode: u64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+      file_type: file_type_from_directory_entry(bytes[18]),
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::ffi::CStr;
+
+  use syscalls::{Errno, Sysno, syscall};
+
+  use crate::{
+    io::{
+      StreamRead, StreamWrite,
+      fs::{Directory, File, FileSystem, FileType, OpenOptions, SeekFrom},
+    },
+    platform::linux::io::LinuxIo,
+  };
+
+  /// Writes a path unique to this process and `name` into `buf`.
+  fn scratch_path<'a>(buf: &'a mut [u8; 64], name: &str) -> &'a CStr {
+    let mut length = 0;
+    let mut push = |bytes: &[u8]| {
+      buf[length..length + bytes.len()].copy_from_slice(bytes);
+      length += bytes.len();
+    };
+
+    push(b"/tmp/aubystd-fs-");
+    let process_id = unsafe { syscall!(Sysno::getpid) }.unwrap();
+    let mut digits = [0; 20];
+    let mut start = digits.len();
+    let mut remaining = process_id;
+    loop {
+      start -= 1;
+      digits[start] = b'0' + (remaining % 10) as u8;
+      remaining /= 10;
+      if remaining == 0 {
+        break;
+      }
+    }
+    push(&digits[start..]);
+    push(b"-");
+    push(name.as_bytes());
+    push(b"\0");
+
+    CStr::from_bytes_with_nul(&buf[..length]).unwrap()
+  }
+
+  #[test]
+  fn file() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let mut buf = [0; 64];
+      let path = scratch_path(&mut buf, "file");
+      let options = OpenOptions::new().read(true).write(true).create_new(true);
+
+      let mut file = io.open(path, &options).await.unwrap();
+      assert_eq!(
+        io.open(path, &options).await.err().unwrap().errno(),
+        Errno::EEXIST
+      );
+
+      assert_eq!(file.write(b"hello world").await.unwrap().0, 11);
+      assert_eq!(file.seek(SeekFrom::Start(6)).await.unwrap(), 6);
+      let mut data = [0; 16];
+      assert_eq!(file.read(&mut data).await.unwrap(), b"world");
+      assert_eq!(file.read(&mut data).await.unwrap(), b"");
+
+      file.write_at(b"HELLO", 0).await.unwrap();
+      assert_eq!(file.read_at(&mut data[..5], 0).await.unwrap(), b"HELLO");
+      assert_eq!(file.seek(SeekFrom::Current(0)).await.unwrap(), 11);
+
+      file.set_len(5).await.unwrap();
+      file.sync_all().await.unwrap();
+      let metadata = file.metadata().await.unwrap();
+      assert_eq!(metadata.file_type, FileType::File);
+      assert_eq!(metadata.size, 5);
+      assert_eq!(io.metadata(path).await.unwrap().inode, metadata.inode);
+
+      io.remove_file(path).await.unwrap();
+      assert_eq!(
+        io.metadata(path).await.err().unwrap().errno(),
+        Errno::ENOENT
+      );
+    });
+  }
+
+  #[test]
+  fn directory() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut directory_buf, mut file_buf, mut link_buf, mut renamed_buf) =
+        ([0; 64], [0; 64], [0; 64], [0; 64]);
+      let directory = scratch_path(&mut directory_buf, "directory");
+      let file = scratch_path(&mut file_buf, "directory/file");
+      let link = scratch_path(&mut link_buf, "directory/link");
+      let renamed = scratch_path(&mut renamed_buf, "directory/renamed");
+
+      io.create_directory(directory, 0o700).await.unwrap();
+      io.open(file, &OpenOptions::new().write(true).create(true))
+        .await
+        .unwrap();
+      io.symlink(c"file", link).await.unwrap();
+
+      let mut target = [0; 16];
+      assert_eq!(io.read_link(link, &mut target).await.unwrap(), b"file");
+      assert_eq!(
+        io.symlink_metadata(link).await.unwrap().file_type,
+        FileType::Symlink
+      );
+      assert_eq!(io.metadata(link).await.unwrap().file_type, FileType::File);
+
+      io.rename(file, renamed).await.unwrap();
+
+      let mut entries = io.read_directory(directory).await.unwrap();
+      let (mut found_link, mut found_renamed) = (false, false);
+      while let Some(entry) = entries.next_entry().await.unwrap() {
+        match entry.name.to_bytes() {
+          b"link" => {
+            assert_eq!(entry.file_type, FileType::Symlink);
+            found_link = true;
+          }
+          b"renamed" => {
+            assert_eq!(entry.file_type, FileType::File);
+            found_renamed = true;
+          }
+          name => panic!("unexpected entry {:?}", name.escape_ascii()),
+        }
+      }
+      assert!(found_link && found_renamed);
+
+      assert_eq!(
+        io.remove_directory(directory).await.err().unwrap().errno(),
+        Errno::ENOTEMPTY
+      );
+      io.remove_file(link).await.unwrap();
+      io.remove_file(renamed).await.unwrap();
+      io.remove_directory(directory).await.unwrap();
+    });
+  }
+}
@@ -1,16 +1,189 @@
-use syscalls::Errno;
+use core::{
+  hint,
+  mem::MaybeUninit,
+  sync::atomic::{AtomicI32, Ordering},
+  time::Duration,
+};
+
+use syscalls::{Errno, Sysno, syscall};
 use thiserror::Error;
 
-use crate::io::Io;
+use crate::{
+  io::Io,
+  platform::linux::{FileDescriptor, MaybeFileDescriptor, OwnedFileDescriptor},
+};
 
+mod fs;
+mod net;
+mod reactor;
+pub(crate) mod stdio;
+mod stream;
 mod tcp;
+mod udp;
+mod unix;
+#[cfg(feature = "io-uring")]
+pub mod uring;
+mod wake;
+
+pub use fs::{LinuxDirectory, LinuxFile};
+pub use reactor::{Interest, MAX_REGISTRATIONS, Registration};
+pub use stdio::{LinuxLineWriter, LinuxStdin};
+pub use stream::LinuxStream;
+pub use tcp::{LinuxListener, LinuxSocket};
+pub use udp::LinuxUdpSocket;
+pub use unix::{
+  LinuxUnixDatagram, LinuxUnixListener, LinuxUnixStream, MAX_PASSED_FILE_DESCRIPTORS,
+};
+
+use reactor::{RegistrationSlot, WAKE_TOKEN};
+use wake::Wake;
 
+const UNINITIALIZED: MaybeFileDescriptor = -1;
+const INITIALIZING: MaybeFileDescriptor = -2;
+
+/// How many events are handled per `epoll_pwait` call.
+const EVENT_BATCH: usize = 64;
+
+/// Linux's [Io], which waits for file descriptors to be ready with epoll.
+///
+/// The epoll instance is created the first time it's needed. Nothing waits for events on its own,
+/// so the reactor has to be driven with [LinuxIo::turn], or futures run with [LinuxIo::block_on].
+///
+/// At most [MAX_REGISTRATIONS] file descriptors can be registered at once, and registering more
+/// fails with ENOSPC until one is dropped.
 pub struct LinuxIo {
-  a: bool,
+  epoll: AtomicI32,
+  wake: Wake,
+  slots: [RegistrationSlot; MAX_REGISTRATIONS],
 }
 
+static INSTANCE: LinuxIo = LinuxIo::new();
+
 impl LinuxIo {
-  fn instance() -> &'static Self {
+  const fn new() -> Self {
+    Self {
+      epoll: AtomicI32::new(UNINITIALIZED),
+      wake: Wake::new(),
+      slots: [const { RegistrationSlot::new() }; MAX_REGISTRATIONS],
+    }
+  }
+
+  pub fn instance() -> &'static Self {
+    &INSTANCE
+  }
+
+  /// Returns the epoll instance, creating it if this is the first time it's needed.
+  fn epoll(&self) -> Result<FileDescriptor, LinuxError> {
+    loop {
+      match self.epoll.compare_exchange(
+        UNINITIALIZED,
+        INITIALIZING,
+        Ordering::Acquire,
+        Ordering::Acquire,
+      ) {
+        Ok(_) => break,
+        Err(INITIALIZING) => hint::spin_loop(),
+        Err(epoll) => return Ok(epoll as _),
+      }
+    }
+
+    match Self::create_epoll() {
+      Ok((epoll, wake)) => {
+        self.wake.set_eventfd(wake);
+        self.epoll.store(epoll as _, Ordering::Release);
+        Ok(epoll)
+      }
+      Err(error) => {
+        self.epoll.store(UNINITIALIZED, Ordering::Release);
+        Err(error)
+      }
+    }
+  }
+
+  fn create_epoll() -> Result<(FileDescriptor, FileDescriptor), LinuxError> {
+    let epoll = OwnedFileDescriptor::new(
+      unsafe { syscall!(Sysno::epoll_create1, libc::EPOLL_CLOEXEC) }.map_err(LinuxError)? as _,
+    );
+    let wake = OwnedFileDescriptor::new(
+      unsafe { syscall!(Sysno::eventfd2, 0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) }
+        .map_err(LinuxError)? as _,
+    );
+
+    let mut event = libc::epoll_event {
+      events: (libc::EPOLLIN | libc::EPOLLET) as u32,
+      u64: WAKE_TOKEN,
+    };
+    unsafe {
+      syscall!(
+        Sysno::epoll_ctl,
+        epoll.get(),
+        libc::EPOLL_CTL_ADD,
+        wake.get(),
+        &raw mut event
+      )
+    }
+    .map_err(LinuxError)?;
+
+    Ok((epoll.into_raw(), wake.into_raw()))
+  }
+
+  /// Wakes a thread blocked in [LinuxIo::turn].
+  pub fn wake(&self) {
+    self.wake.signal();
+  }
+
+  /// Waits until a registered file descriptor is ready, or `timeout` passes, and wakes the tasks
+  /// waiting on it. Returns how many events were handled.
+  pub fn turn(&self, timeout: Option<Duration>) -> Result<usize, LinuxError> {
+    let epoll = self.epoll()?;
+    let timeout: i32 = match timeout {
+      None => -1,
+      Some(timeout) => timeout.as_millis().try_into().unwrap_or(i32::MAX),
+    };
+
+    let mut events = [const { MaybeUninit::<libc::epoll_event>::uninit() }; EVENT_BATCH];
+    let count = match unsafe {
+      syscall!(
+        Sysno::epoll_pwait,
+        epoll,
+        events.as_mut_ptr(),
+        EVENT_BATCH,
+        timeout,
+        0,
+        0
+      )
+    } {
+      Ok(count) => count,
+      Err(error) if error == Errno::EINTR => 0,
+      Err(error) => return Err(LinuxError(error)),
+    };
+
+    for event in &events[..count] {
+      // Safety: the kernel initialized the first `count` events
+      let event = unsafe { event.assume_init_read() };
+      match event.u64 {
+        WAKE_TOKEN => self.drain_wake(),
+        index => self.slots[index as usize].dispatch(event.events),
+      }
+    }
+
+    Ok(count)
+  }
+
+  fn drain_wake(&self) {
+    self.wake.drain();
+  }
+
+  /// Runs `future` to completion on this thread, driving the reactor while it's pending.
+  pub fn block_on<F: Future>(&'static self, future: F) -> F::Output {
+    // the wake eventfd has to exist before a waker can signal it
+    self.epoll().expect("failed to create epoll instance");
+    self.wake.block_on(
+      || {
+        self.turn(None).expect("failed to wait for io events");
+      },
+      future,
+    )
   }
 }
 
@@ -22,3 +195,89 @@ impl Io for LinuxIo {}
 #[non_exhaustive]
 #[error("Unhandled error: {0}")]
 pub struct LinuxError(Errno);
+
+impl LinuxError {
+  pub fn errno(&self) -> Errno {
+    self.0
+  }
+}
+
+/// Polls both futures until they're both done, so a test can accept and connect at once.
+#[cfg(test)]
+async fn join<A: Future, B: Future>(first: A, second: B) -> (A::Output, B::Output) {
+  use core::{future::poll_fn, pin::pin, task::Poll};
+
+  let (mut first, mut second) = (pin!(first), pin!(second));
+  let (mut first_output, mut second_output) = (None, None);
+  poll_fn(|cx| {
+    if first_output.is_none()
+      && let Poll::Ready(output) = first.as_mut().poll(cx)
+    {
+      first_output = Some(output);
+    }
+    if second_output.is_none()
+      && let Poll::Ready(output) = second.as_mut().poll(cx)
+    {
+      second_output = Some(output);
+    }
+    match first_output.is_some() && second_output.is_some() {
+      true => Poll::Ready(()),
+      false => Poll::Pending,
+    }
+  })
+  .await;
+
+  (first_output.unwrap(), second_output.unwrap())
+}
+
+/// Runs `f` with SIGPIPE blocked on this thread, and returns whether it raised SIGPIPE. The test
+/// harness ignores SIGPIPE, but a blocked signal stays pending even then, so it can be checked.
+#[cfg(test)]
+fn raises_sigpipe(f: impl FnOnce()) -> bool {
+  let sigpipe: u64 = 1 << (libc::SIGPIPE - 1);
+  let mut previous = 0u64;
+  unsafe {
+    syscall!(
+      Sysno::rt_sigprocmask,
+      libc::SIG_BLOCK,
+      &raw const sigpipe,
+      &raw mut previous,
+      size_of::<u64>()
+    )
+  }
+  .unwrap();
+
+  f();
+
+  let mut pending = 0u64;
+  unsafe { syscall!(Sysno::rt_sigpending, &raw mut pending, size_of::<u64>()) }.unwrap();
+  let raised = pending & sigpipe != 0;
+  if raised {
+    let timeout = libc::timespec {
+      tv_sec: 0,
+      tv_nsec: 0,
+    };
+    unsafe {
+      syscall!(
+        Sysno::rt_sigtimedwait,
+        &raw const sigpipe,
+        0,
+        &raw const timeout,
+        size_of::<u64>()
+      )
+    }
+    .unwrap();
+  }
+
+  unsafe {
+    syscall!(
+      Sysno::rt_sigprocmask,
+      libc::SIG_SETMASK,
+      &raw const previous,
+      0,
+      size_of::<u64>()
+    )
+  }
+  .unwrap();
+  raised
+}
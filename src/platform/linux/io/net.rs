@@ -0,0 +1,348 @@
+use core::{
+  mem,
+  net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+  ptr,
+};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::{
+  io::net::{tcp::Shutdown, unix::UnixSocketAddress},
+  platform::linux::{
+    FileDescriptor, OwnedFileDescriptor,
+    io::{Interest, LinuxError, Registration},
+  },
+};
+
+/// A socket address in the layout the kernel expects.
+pub struct RawSocketAddress {
+  storage: libc::sockaddr_storage,
+  length: libc::socklen_t,
+}
+
+impl RawSocketAddress {
+  /// An empty address, for the kernel to write to.
+  pub fn empty() -> Self {
+    Self {
+      // Safety: sockaddr_storage is plain data
+      storage: unsafe { mem::zeroed() },
+      length: size_of::<libc::sockaddr_storage>() as _,
+    }
+  }
+
+  pub fn as_ptr(&self) -> *const libc::sockaddr {
+    (&raw const self.storage).cast()
+  }
+
+  pub fn as_mut_ptr(&mut self) -> *mut libc::sockaddr {
+    (&raw mut self.storage).cast()
+  }
+
+  pub fn len(&self) -> libc::socklen_t {
+    self.length
+  }
+
+  pub fn len_mut(&mut self) -> &mut libc::socklen_t {
+    &mut self.length
+  }
+
+  /// Returns the address if it's an IPv4 or IPv6 address.
+  pub fn to_socket_address(&self) -> Option<SocketAddr> {
+    match self.storage.ss_family as i32 {
+      libc::AF_INET => {
+        // Safety: the family says the storage holds a sockaddr_in
+        let address = unsafe { &*(&raw const self.storage).cast::<libc::sockaddr_in>() };
+        Some(SocketAddr::V4(SocketAddrV4::new(
+          Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr)),
+          u16::from_be(address.sin_port),
+        )))
+      }
+      libc::AF_INET6 => {
+        // Safety: the family says the storage holds a sockaddr_in6
+        let address = unsafe { &*(&raw const self.storage).cast::<libc::sockaddr_in6>() };
+        Some(SocketAddr::V6(SocketAddrV6::new(
+          Ipv6Addr::from(address.sin6_addr.s6_addr),
+          u16::from_be(address.sin6_port),
+          address.sin6_flowinfo,
+          address.sin6_scope_id,
+        )))
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns the address if it's a Unix socket address.
+  pub fn to_unix_address(&self) -> Option<UnixSocketAddress> {
+    if self.storage.ss_family as i32 != libc::AF_UNIX {
+      return None;
+    }
+
+    // Safety: the family says the storage holds a sockaddr_un
+    let address = unsafe { &*(&raw const self.storage).cast::<libc::sockaddr_un>() };
+    let path = address.sun_path.map(|byte| byte as u8);
+    let length = (self.length as usize).saturating_sub(size_of::<libc::sa_family_t>());
+    let path = &path[..length.min(path.len())];
+
+    match path {
+      [] => Some(UnixSocketAddress::unnamed()),
+      [0, name @ ..] => UnixSocketAddress::from_abstract(name),
+      path => {
+        // the kernel can include the terminating nul in the length
+        let end = path
+          .iter()
+          .position(|&byte| byte == 0)
+          .unwrap_or(path.len());
+        UnixSocketAddress::from_pathname(&path[..end])
+      }
+    }
+  }
+}
+
+impl From<SocketAddr> for RawSocketAddress {
+  fn from(address: SocketAddr) -> Self {
+    let mut raw = Self::empty();
+    match address {
+      SocketAddr::V4(address) => {
+        // Safety: sockaddr_storage is large and aligned enough for every address
+        let storage = unsafe { &mut *raw.as_mut_ptr().cast::<libc::sockaddr_in>() };
+        storage.sin_family = libc::AF_INET as _;
+        storage.sin_port = address.port().to_be();
+        storage.sin_addr.s_addr = u32::from(*address.ip()).to_be();
+        raw.length = size_of::<libc::sockaddr_in>() as _;
+      }
+      SocketAddr::V6(address) => {
+        // Safety: sockaddr_storage is large and aligned enough for every address
+        let storage = unsafe { &mut *raw.as_mut_ptr().cast::<libc::sockaddr_in6>() };
+        storage.sin6_family = libc::AF_INET6 as _;
+        storage.sin6_port = address.port().to_be();
+        storage.sin6_addr.s6_addr = address.ip().octets();
+        storage.sin6_flowinfo = address.flowinfo();
+        storage.sin6_scope_id = address.scope_id();
+        raw.length = size_of::<libc::sockaddr_in6>() as _;
+      }
+    }
+    raw
+  }
+}
+
+impl From<&UnixSocketAddress> for RawSocketAddress {
+  fn from(address: &UnixSocketAddress) -> Self {
+    let mut raw = Self::empty();
+    // Safety: sockaddr_storage is large and aligned enough for every address
+    let storage = unsafe { &mut *raw.as_mut_ptr().cast::<libc::sockaddr_un>() };
+    storage.sun_family = libc::AF_UNIX as _;
+
+    let (offset, name) = match (address.as_pathname(), address.as_abstract()) {
+      (Some(path), _) => (0, path),
+      // abstract names start with a nul byte
+      (_, Some(name)) => (1, name),
+      _ => (0, &[][..]),
+    };
+    for (target, &byte) in storage.sun_path[offset..].iter_mut().zip(name) {
+      *target = byte as _;
+    }
+
+    let mut length = size_of::<libc::sa_family_t>() + offset + name.len();
+    if address.as_pathname().is_some() {
+      length += 1;
+    }
+    raw.length = length as _;
+    raw
+  }
+}
+
+pub fn address_family(address: &SocketAddr) -> i32 {
+  match address {
+    SocketAddr::V4(_) => libc::AF_INET,
+    SocketAddr::V6(_) => libc::AF_INET6,
+  }
+}
+
+/// Creates a socket that's closed on exec. `kind` can include flags like `SOCK_NONBLOCK`.
+pub fn socket(family: i32, kind: i32, protocol: i32) -> Result<FileDescriptor, Errno> {
+  unsafe { syscall!(Sysno::socket, family, kind | libc::SOCK_CLOEXEC, protocol) }
+    .map(|file_descriptor| file_descriptor as _)
+}
+
+pub fn bind(
+  file_descriptor: FileDescriptor,
+  address: impl Into<RawSocketAddress>,
+) -> Result<(), Errno> {
+  let address = address.into();
+  unsafe {
+    syscall!(
+      Sysno::bind,
+      file_descriptor,
+      address.as_ptr(),
+      address.len()
+    )
+  }
+  .map(|_| ())
+}
+
+pub fn connect(
+  file_descriptor: FileDescriptor,
+  address: impl Into<RawSocketAddress>,
+) -> Result<(), Errno> {
+  let address = address.into();
+  unsafe {
+    syscall!(
+      Sysno::connect,
+      file_descriptor,
+      address.as_ptr(),
+      address.len()
+    )
+  }
+  .map(|_| ())
+}
+
+/// Connects the registered nonblocking socket, waiting for the connection to be established.
+pub async fn connect_registered(
+  registration: &Registration<'_>,
+  address: impl Into<RawSocketAddress>,
+) -> Result<(), LinuxError> {
+  match connect(registration.file_descriptor(), address) {
+    Ok(_) => Ok(()),
+    Err(error) if error == Errno::EINPROGRESS => {
+      // the socket becomes writable once the connection is established or has failed
+      registration
+        .io_operation(Interest::Write, |file_descriptor| {
+          take_error(file_descriptor)?;
+          match raw_peer_address(file_descriptor) {
+            Err(error) if error == Errno::ENOTCONN => Err(Errno::EAGAIN),
+            result => result.map(|_| ()),
+          }
+        })
+        .await
+    }
+    Err(error) => Err(LinuxError(error)),
+  }
+}
+
+/// Accepts a connection on the registered listening socket, as a nonblocking socket.
+pub async fn accept_registered(
+  registration: &Registration<'_>,
+) -> Result<OwnedFileDescriptor, LinuxError> {
+  let socket = registration
+    .io_operation(Interest::Read, |file_descriptor| unsafe {
+      syscall!(
+        Sysno::accept4,
+        file_descriptor,
+        0,
+        0,
+        libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC
+      )
+    })
+    .await?;
+
+  Ok(OwnedFileDescriptor::new(socket as _))
+}
+
+pub fn listen(file_descriptor: FileDescriptor, backlog: i32) -> Result<(), Errno> {
+  unsafe { syscall!(Sysno::listen, file_descriptor, backlog) }.map(|_| ())
+}
+
+pub fn raw_local_address(file_descriptor: FileDescriptor) -> Result<RawSocketAddress, Errno> {
+  let mut address = RawSocketAddress::empty();
+  unsafe {
+    syscall!(
+      Sysno::getsockname,
+      file_descriptor,
+      address.as_mut_ptr(),
+      ptr::from_mut(address.len_mut())
+    )
+  }?;
+  Ok(address)
+}
+
+pub fn raw_peer_address(file_descriptor: FileDescriptor) -> Result<RawSocketAddress, Errno> {
+  let mut address = RawSocketAddress::empty();
+  unsafe {
+    syscall!(
+      Sysno::getpeername,
+      file_descriptor,
+      address.as_mut_ptr(),
+      ptr::from_mut(address.len_mut())
+    )
+  }?;
+  Ok(address)
+}
+
+pub fn local_address(file_descriptor: FileDescriptor) -> Result<SocketAddr, Errno> {
+  raw_local_address(file_descriptor)?
+    .to_socket_address()
+    .ok_or(Errno::EAFNOSUPPORT)
+}
+
+pub fn peer_address(file_descriptor: FileDescriptor) -> Result<SocketAddr, Errno> {
+  raw_peer_address(file_descriptor)?
+    .to_socket_address()
+    .ok_or(Errno::EAFNOSUPPORT)
+}
+
+pub fn get_option<T: Copy>(
+  file_descriptor: FileDescriptor,
+  level: i32,
+  name: i32,
+) -> Result<T, Errno> {
+  let mut value = mem::MaybeUninit::<T>::uninit();
+  let mut length = size_of::<T>() as libc::socklen_t;
+  unsafe {
+    syscall!(
+      Sysno::getsockopt,
+      file_descriptor,
+      level,
+      name,
+      value.as_mut_ptr(),
+      &raw mut length
+    )?;
+    // Safety: the kernel wrote the option
+    Ok(value.assume_init())
+  }
+}
+
+pub fn set_option<T: Copy>(
+  file_descriptor: FileDescriptor,
+  level: i32,
+  name: i32,
+  value: T,
+) -> Result<(), Errno> {
+  unsafe {
+    syscall!(
+      Sysno::setsockopt,
+      file_descriptor,
+      level,
+      name,
+      &raw const value,
+      size_of::<T>()
+    )
+  }
+  .map(|_| ())
+}
+
+/// Returns the error of a socket's asynchronous operation, like a nonblocking connect.
+pub fn take_error(file_descriptor: FileDescriptor) -> Result<(), Errno> {
+  match get_option::<i32>(file_descriptor, libc::SOL_SOCKET, libc::SO_ERROR)? {
+    0 => Ok(()),
+    error => Err(Errno::new(error)),
+  }
+}
+
+pub fn shutdown(file_descriptor: FileDescriptor, how: Shutdown) -> Result<(), Errno> {
+  let how = match how {
+    Shutdown::Read => libc::SHUT_RD,
+    Shutdown::Write => libc::SHUT_WR,
+    Shutdown::Both => libc::SHUT_RDWR,
+  };
+  unsafe { syscall!(Sysno::shutdown, file_descriptor, how) }.map(|_| ())
+}
+
+/// Enables or disables Nagle's algorithm, which delays small writes to send them together.
+pub fn set_nagle_algorithm(file_descriptor: FileDescriptor, value: bool) -> Result<(), Errno> {
+  set_option(
+    file_descriptor,
+    libc::IPPROTO_TCP,
+    libc::TCP_NODELAY,
+    i32::from(!value),
+  )
+}
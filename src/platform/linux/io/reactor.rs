@@ -0,0 +1,361 @@
+use core::{
+  cell::UnsafeCell,
+  future::poll_fn,
+  hint,
+  sync::atomic::{AtomicBool, AtomicU64, Ordering},
+  task::{Context, Poll, Waker},
+};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::platform::linux::{
+  FileDescriptor, OwnedFileDescriptor,
+  io::{LinuxError, LinuxIo},
+};
+
+/// How many file descriptors can be registered with a [LinuxIo] at once.
+pub const MAX_REGISTRATIONS: usize = 1024;
+
+/// The epoll data of the eventfd that wakes the reactor, which isn't a registration.
+pub(super) const WAKE_TOKEN: u64 = u64::MAX;
+
+const READABLE: u64 = 1 << 0;
+const WRITABLE: u64 = 1 << 1;
+const READINESS_MASK: u64 = READABLE | WRITABLE;
+/// Every readiness event increments the tick in the upper bits, so readiness that arrived while an
+/// operation was running isn't cleared.
+const TICK_SHIFT: u32 = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Interest {
+  Read,
+  Write,
+}
+
+impl Interest {
+  fn mask(self) -> u64 {
+    match self {
+      Interest::Read => READABLE,
+      Interest::Write => WRITABLE,
+    }
+  }
+}
+
+/// A waker that can be stored by a task and taken by the reactor from different threads.
+pub(super) struct WakerCell {
+  locked: AtomicBool,
+  waker: UnsafeCell<Option<Waker>>,
+}
+
+// Safety: the waker is only accessed while locked
+unsafe impl Sync for WakerCell {}
+
+impl WakerCell {
+  pub(super) const fn new() -> Self {
+    Self {
+      locked: AtomicBool::new(false),
+      waker: UnsafeCell::new(None),
+    }
+  }
+
+  pub(super) fn with<R>(&self, f: impl FnOnce(&mut Option<Waker>) -> R) -> R {
+    while self
+      .locked
+      .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+      .is_err()
+    {
+      hint::spin_loop();
+    }
+    // Safety: the lock is held
+    let result = f(unsafe { &mut *self.waker.get() });
+    self.locked.store(false, Ordering::Release);
+    result
+  }
+
+  pub(super) fn register(&self, waker: &Waker) {
+    self.with(|slot| match slot {
+      Some(current) if current.will_wake(waker) => {}
+      _ => *slot = Some(waker.clone()),
+    });
+  }
+
+  pub(super) fn wake(&self) {
+    if let Some(waker) = self.with(Option::take) {
+      waker.wake();
+    }
+  }
+}
+
+/// The readiness of a registered file descriptor, and the tasks waiting on it.
+pub(super) struct RegistrationSlot {
+  in_use: AtomicBool,
+  readiness: AtomicU64,
+  readers: WakerCell,
+  writers: WakerCell,
+}
+
+impl RegistrationSlot {
+  pub(super) const fn new() -> Self {
+    Self {
+      in_use: AtomicBool::new(false),
+      readiness: AtomicU64::new(0),
+      readers: WakerCell::new(),
+      writers: WakerCell::new(),
+    }
+  }
+
+  fn waiters(&self, interest: Interest) -> &WakerCell {
+    match interest {
+      Interest::Read => &self.readers,
+      Interest::Write => &self.writers,
+    }
+  }
+
+  /// Marks the slot ready for the epoll `events`, and wakes the tasks waiting on them.
+  pub(super) fn dispatch(&self, events: u32) {
+    let mut ready = 0;
+    if events & (libc::EPOLLIN | libc::EPOLLRDHUP | libc::EPOLLHUP | libc::EPOLLERR) as u32 != 0 {
+      ready |= READABLE;
+    }
+    if events & (libc::EPOLLOUT | libc::EPOLLHUP | libc::EPOLLERR) as u32 != 0 {
+      ready |= WRITABLE;
+    }
+
+    let _ = self
+      .readiness
+      .fetch_update(Ordering::AcqRel, Ordering::Acquire, |readiness| {
+        Some((readiness + (1 << TICK_SHIFT)) | ready)
+      });
+
+    if ready & READABLE != 0 {
+      self.readers.wake();
+    }
+    if ready & WRITABLE != 0 {
+      self.writers.wake();
+    }
+  }
+
+  /// Resolves with the current readiness once the slot is ready for `interest`.
+  fn poll_ready(&self, interest: Interest, cx: &mut Context<'_>) -> Poll<u64> {
+    let readiness = self.readiness.load(Ordering::Acquire);
+    if readiness & interest.mask() != 0 {
+      return Poll::Ready(readiness);
+    }
+
+    self.waiters(interest).register(cx.waker());
+
+    // readiness could have arrived before the waker was stored
+    let readiness = self.readiness.load(Ordering::Acquire);
+    if readiness & interest.mask() != 0 {
+      return Poll::Ready(readiness);
+    }
+
+    Poll::Pending
+  }
+
+  /// Clears the readiness for `interest`, unless there has been a new event since `observed`.
+  fn clear_ready(&self, interest: Interest, observed: u64) {
+    let _ = self.readiness.compare_exchange(
+      observed,
+      observed & !interest.mask(),
+      Ordering::AcqRel,
+      Ordering::Acquire,
+    );
+  }
+}
+
+/// A nonblocking file descriptor registered with a [LinuxIo], which is deregistered and closed
+/// when dropped.
+pub struct Registration<'a> {
+  io: &'a LinuxIo,
+  index: usize,
+  file_descriptor: OwnedFileDescriptor,
+}
+
+impl<'a> Registration<'a> {
+  /// Registers the nonblocking `file_descriptor` for readiness events. Fails with ENOSPC, like
+  /// epoll's own limit on watched file descriptors, if [MAX_REGISTRATIONS] are already registered.
+  pub fn new(io: &'a LinuxIo, file_descriptor: OwnedFileDescriptor) -> Result<Self, LinuxError> {
+    let epoll = io.epoll()?;
+    let Some(index) = io.slots.iter().position(|slot| {
+      slot
+        .in_use
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_ok()
+    }) else {
+      return Err(LinuxError(Errno::ENOSPC));
+    };
+
+    // new file descriptors are assumed ready, until an operation would block
+    io.slots[index]
+      .readiness
+      .store(READINESS_MASK, Ordering::Release);
+
+    let mut event = libc::epoll_event {
+      events: (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLET) as u32,
+      u64: index as u64,
+    };
+    let registration = Self {
+      io,
+      index,
+      file_descriptor,
+    };
+    unsafe {
+      syscall!(
+        Sysno::epoll_ctl,
+        epoll,
+        libc::EPOLL_CTL_ADD,
+        registration.file_descriptor(),
+        &raw mut event
+      )
+    }
+    .map_err(LinuxError)?;
+
+    Ok(registration)
+  }
+
+  pub fn file_descriptor(&self) -> FileDescriptor {
+    self.file_descriptor.get()
+  }
+
+  pub fn io(&self) -> &'a LinuxIo {
+    self.io
+  }
+
+  fn slot(&self) -> &'a RegistrationSlot {
+    &self.io.slots[self.index]
+  }
+
+  /// Runs `operation` until it doesn't fail with `EAGAIN` or `EINTR`, waiting for the file
+  /// descriptor to be ready for `interest` in between.
+  pub async fn io_operation<T>(
+    &self,
+    interest: Interest,
+    mut operation: impl FnMut(FileDescriptor) -> Result<T, Errno>,
+  ) -> Result<T, LinuxError> {
+    loop {
+      let observed = poll_fn(|cx| self.slot().poll_ready(interest, cx)).await;
+
+      match operation(self.file_descriptor()) {
+        Err(error) if error == Errno::EAGAIN => self.slot().clear_ready(interest, observed),
+        Err(error) if error == Errno::EINTR => {}
+        result => return result.map_err(LinuxError),
+      }
+    }
+  }
+}
+
+impl Drop for Registration<'_> {
+  fn drop(&mut self) {
+    if let Ok(epoll) = self.io.epoll() {
+      // failing only means the file descriptor is already gone
+      let _ = unsafe {
+        syscall!(
+          Sysno::epoll_ctl,
+          epoll,
+          libc::EPOLL_CTL_DEL,
+          self.file_descriptor(),
+          0
+        )
+      };
+    }
+
+    let slot = self.slot();
+    slot.readers.with(Option::take);
+    slot.writers.with(Option::take);
+    slot.in_use.store(false, Ordering::Release);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate std;
+
+  use core::{
+    pin::pin,
+    sync::atomic::Ordering,
+    task::{Context, Poll, Waker},
+    time::Duration,
+  };
+
+  use syscalls::{Errno, Sysno, syscall};
+
+  use crate::platform::linux::{
+    OwnedFileDescriptor,
+    io::{Interest, LinuxError, LinuxIo, MAX_REGISTRATIONS, Registration},
+  };
+
+  fn eventfd() -> OwnedFileDescriptor {
+    let file_descriptor =
+      unsafe { syscall!(Sysno::eventfd2, 0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) }.unwrap();
+    OwnedFileDescriptor::new(file_descriptor as _)
+  }
+
+  #[test]
+  fn wake_from_another_thread() {
+    static IO: LinuxIo = LinuxIo::new();
+    IO.epoll().unwrap();
+
+    let waker = std::thread::spawn(|| IO.wake());
+    // the wake is handled whether it arrives before or after the reactor starts waiting
+    assert_eq!(IO.turn(None).unwrap(), 1);
+    waker.join().unwrap();
+  }
+
+  #[test]
+  fn ready_after_would_block() {
+    static IO: LinuxIo = LinuxIo::new();
+
+    let mut pipe = [0i32; 2];
+    unsafe {
+      syscall!(
+        Sysno::pipe2,
+        pipe.as_mut_ptr(),
+        libc::O_CLOEXEC | libc::O_NONBLOCK
+      )
+    }
+    .unwrap();
+    let [read, write] = pipe.map(|file_descriptor| OwnedFileDescriptor::new(file_descriptor as _));
+    let read = Registration::new(&IO, read).unwrap();
+
+    let mut buf = [0u8; 8];
+    let mut operation = pin!(read.io_operation(Interest::Read, |file_descriptor| unsafe {
+      syscall!(Sysno::read, file_descriptor, buf.as_mut_ptr(), buf.len())
+    }));
+    let mut cx = Context::from_waker(Waker::noop());
+
+    // the read fails with EAGAIN, which clears the readiness the registration started with
+    assert!(operation.as_mut().poll(&mut cx).is_pending());
+
+    unsafe { syscall!(Sysno::write, write.get(), b"ready".as_ptr(), 5) }.unwrap();
+    assert_eq!(IO.turn(Some(Duration::ZERO)).unwrap(), 1);
+    assert!(matches!(
+      operation.as_mut().poll(&mut cx),
+      Poll::Ready(Ok(5))
+    ));
+  }
+
+  #[test]
+  fn reuse_slots() {
+    static IO: LinuxIo = LinuxIo::new();
+
+    let first = Registration::new(&IO, eventfd()).unwrap();
+    assert_eq!(first.index, 0);
+    drop(first);
+    assert_eq!(Registration::new(&IO, eventfd()).unwrap().index, 0);
+
+    for slot in &IO.slots {
+      slot.in_use.store(true, Ordering::Relaxed);
+    }
+    assert!(matches!(
+      Registration::new(&IO, eventfd()),
+      Err(LinuxError(Errno::ENOSPC))
+    ));
+
+    IO.slots[MAX_REGISTRATIONS - 1]
+      .in_use
+      .store(false, Ordering::Relaxed);
+    let last = Registration::new(&IO, eventfd()).unwrap();
+    assert_eq!(last.index, MAX_REGISTRATIONS - 1);
+  }
+}
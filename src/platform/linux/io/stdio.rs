@@ -0,0 +1,297 @@
+use core::{
+  cell::UnsafeCell,
+  fmt,
+  sync::atomic::{AtomicU32, Ordering},
+};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::{
+  io::{
+    StreamRead, StreamWrite,
+    stdio::{Stderr, Stdin, Stdout},
+  },
+  platform::linux::{
+    FileDescriptor,
+    io::{LinuxError, LinuxIo},
+  },
+};
+
+/// How many bytes of an unfinished line are held before they're written anyway.
+const LINE_BUFFER_SIZE: usize = 1024;
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+const CONTENDED: u32 = 2;
+
+pub(crate) static STDOUT: LineBuffer = LineBuffer::new(libc::STDOUT_FILENO as _);
+pub(crate) static STDERR: LineBuffer = LineBuffer::new(libc::STDERR_FILENO as _);
+
+// the standard streams are shared with other processes, so they're left blocking rather than
+// registered with epoll
+impl Stdin for LinuxIo {
+  fn stdin_stream(&self) -> LinuxStdin {
+    LinuxStdin
+  }
+}
+
+impl Stdout for LinuxIo {
+  fn stdout_stream(&self) -> LinuxLineWriter<'static> {
+    LinuxLineWriter { buffer: &STDOUT }
+  }
+}
+
+impl Stderr for LinuxIo {
+  fn stderr_stream(&self) -> LinuxLineWriter<'static> {
+    LinuxLineWriter { buffer: &STDERR }
+  }
+}
+
+/// Writes all of `data`, retrying partial writes and interrupted syscalls.
+fn write_all(file_descriptor: FileDescriptor, mut data: &[u8]) -> Result<(), Errno> {
+  while !data.is_empty() {
+    match unsafe { syscall!(Sysno::write, file_descriptor, data.as_ptr(), data.len()) } {
+      Ok(length) => data = &data[length..],
+      Err(error) if error == Errno::EINTR => {}
+      Err(error) => return Err(error),
+    }
+  }
+
+  Ok(())
+}
+
+/// A buffer that holds the last unfinished line written to a file descriptor, behind a futex lock.
+pub(crate) struct LineBuffer {
+  file_descriptor: FileDescriptor,
+  lock: AtomicU32,
+  line: UnsafeCell<Line>,
+}
+
+struct Line {
+  bytes: [u8; LINE_BUFFER_SIZE],
+  length: usize,
+}
+
+// Safety: the buffer is only accessed while the lock is held
+unsafe impl Sync for LineBuffer {}
+
+impl LineBuffer {
+  pub(crate) const fn new(file_descriptor: FileDescriptor) -> Self {
+    Self {
+      file_descriptor,
+      lock: AtomicU32::new(UNLOCKED),
+      line: UnsafeCell::new(Line {
+        bytes: [0; LINE_BUFFER_SIZE],
+        length: 0,
+      }),
+    }
+  }
+
+  fn with_lock<T>(&self, f: impl FnOnce(&mut Line) -> T) -> T {
+    if self
+      .lock
+      .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+      .is_err()
+    {
+      while self.lock.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+        // a spurious or interrupted wake only means checking the lock again
+        let _ = unsafe {
+          syscall!(
+            Sysno::futex,
+            &raw const self.lock,
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            CONTENDED,
+            0,
+            0,
+            0
+          )
+        };
+      }
+    }
+
+    // Safety: the lock is held until the line is no longer borrowed
+    let result = f(unsafe { &mut *self.line.get() });
+
+    if self.lock.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+      let _ = unsafe {
+        syscall!(
+          Sysno::futex,
+          &raw const self.lock,
+          libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+          1,
+          0,
+          0,
+          0
+        )
+      };
+    }
+
+    result
+  }
+
+  /// Writes every complete line in `data` along with what was buffered before it, and buffers the
+  /// unfinished line after it. Lines longer than the buffer are written as they come.
+  ///
+  /// The buffer is emptied when writing it fails, so an error loses the buffered output.
+  pub(crate) fn write(&self, data: &[u8]) -> Result<(), Errno> {
+    self.with_lock(|line| {
+      let rest = match data.iter().rposition(|&byte| byte == b'\n') {
+        Some(newline) => {
+          self.flush_locked(line)?;
+          write_all(self.file_descriptor, &data[..=newline])?;
+          &data[newline + 1..]
+        }
+        None => data,
+      };
+
+      if line.length + rest.len() > LINE_BUFFER_SIZE {
+        self.flush_locked(line)?;
+        if rest.len() > LINE_BUFFER_SIZE {
+          return write_all(self.file_descriptor, rest);
+        }
+      }
+
+      line.bytes[line.length..line.length + rest.len()].copy_from_slice(rest);
+      line.length += rest.len();
+      Ok(())
+    })
+  }
+
+  /// Writes the buffered unfinished line.
+  pub(crate) fn flush(&self) -> Result<(), Errno> {
+    self.with_lock(|line| self.flush_locked(line))
+  }
+
+  fn flush_locked(&self, line: &mut Line) -> Result<(), Errno> {
+    let length = line.length;
+    line.length = 0;
+    write_all(self.file_descriptor, &line.bytes[..length])
+  }
+}
+
+/// Formats into a [LineBuffer], remembering the first error instead of returning [fmt::Error].
+pub(crate) struct LineBufferFormat<'a> {
+  pub(crate) buffer: &'a LineBuffer,
+  pub(crate) error: Option<Errno>,
+}
+
+impl fmt::Write for LineBufferFormat<'_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.buffer.write(s.as_bytes()).map_err(|error| {
+      self.error = Some(error);
+      fmt::Error
+    })
+  }
+}
+
+/// The standard input, read with blocking syscalls.
+pub struct LinuxStdin;
+
+impl StreamRead for LinuxStdin {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    loop {
+      match unsafe { syscall!(Sysno::read, libc::STDIN_FILENO, buf.as_mut_ptr(), buf.len()) } {
+        Ok(length) => return Ok(&buf[..length]),
+        Err(error) if error == Errno::EINTR => {}
+        Err(error) => return Err(LinuxError(error)),
+      }
+    }
+  }
+}
+
+/// The standard output or error, which is written a line at a time. Output that doesn't end a line
+/// is held until a later write does, the buffer fills up, or it's [flushed](StreamWrite::flush).
+pub struct LinuxLineWriter<'a> {
+  buffer: &'a LineBuffer,
+}
+
+impl StreamWrite for LinuxLineWriter<'_> {
+  type Error = LinuxError;
+
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), LinuxError> {
+    self.buffer.write(data).map_err(LinuxError)?;
+    Ok((data.len(), &[]))
+  }
+
+  /// Writes the buffered output that doesn't end a line yet.
+  async fn flush(&mut self) -> Result<(), LinuxError> {
+    self.buffer.flush().map_err(LinuxError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use syscalls::{Errno, Sysno, syscall};
+
+  use crate::{
+    io::{StreamWrite, stdio::Stdout},
+    platform::linux::{
+      OwnedFileDescriptor,
+      io::{
+        LinuxIo,
+        stdio::{LINE_BUFFER_SIZE, LineBuffer, LinuxLineWriter},
+      },
+    },
+  };
+
+  fn read_pipe(file_descriptor: &OwnedFileDescriptor, buf: &mut [u8]) -> Result<usize, Errno> {
+    unsafe {
+      syscall!(
+        Sysno::read,
+        file_descriptor.get(),
+        buf.as_mut_ptr(),
+        buf.len()
+      )
+    }
+  }
+
+  #[test]
+  fn line_buffered() {
+    let mut file_descriptors = [0i32; 2];
+    unsafe {
+      syscall!(
+        Sysno::pipe2,
+        file_descriptors.as_mut_ptr(),
+        libc::O_NONBLOCK | libc::O_CLOEXEC
+      )
+    }
+    .unwrap();
+    let [read, write] =
+      file_descriptors.map(|file_descriptor| OwnedFileDescriptor::new(file_descriptor as _));
+
+    let buffer = LineBuffer::new(write.get());
+    let mut writer = LinuxLineWriter { buffer: &buffer };
+    let mut buf = [0; 2 * LINE_BUFFER_SIZE];
+    pollster::block_on(async {
+      writer.write(b"unfinished").await.unwrap();
+      assert_eq!(read_pipe(&read, &mut buf), Err(Errno::EAGAIN));
+
+      writer.write(b" line\nnext").await.unwrap();
+      let length = read_pipe(&read, &mut buf).unwrap();
+      assert_eq!(&buf[..length], b"unfinished line\n");
+
+      writer.flush().await.unwrap();
+      let length = read_pipe(&read, &mut buf).unwrap();
+      assert_eq!(&buf[..length], b"next");
+
+      writer.write(&[b'x'; LINE_BUFFER_SIZE + 1]).await.unwrap();
+      let length = read_pipe(&read, &mut buf).unwrap();
+      assert_eq!(length, LINE_BUFFER_SIZE + 1);
+    });
+
+    // writing to a closed pipe fails instead of panicking
+    drop(read);
+    writer.buffer.write(b"lost\n").unwrap_err();
+  }
+
+  #[test]
+  fn stdout() {
+    let mut stdout = LinuxIo::instance().stdout_stream();
+    pollster::block_on(async {
+      stdout.write(b"").await.unwrap();
+      stdout.flush().await.unwrap();
+    });
+  }
+}
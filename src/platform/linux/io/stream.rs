@@ -0,0 +1,262 @@
+use core::{marker::PhantomData, mem};
+
+use syscalls::{Sysno, syscall};
+
+use crate::{
+  io::{StreamRead, StreamWrite},
+  platform::linux::io::{Interest, LinuxError, Registration},
+};
+
+/// How many buffers a vectored read or write passes at most. The buffers after these are left
+/// for the next call, like the end of a buffer in a partial write.
+const MAX_IO_VECTORS: usize = 64;
+
+/// Buffers in the form readv and writev take them.
+pub(super) struct IoVectors<'a> {
+  vectors: [libc::iovec; MAX_IO_VECTORS],
+  length: usize,
+  _buffers: PhantomData<&'a [u8]>,
+}
+
+impl<'a> IoVectors<'a> {
+  fn new(buffers: impl Iterator<Item = (*mut u8, usize)>) -> Self {
+    let mut vectors = [libc::iovec {
+      iov_base: core::ptr::null_mut(),
+      iov_len: 0,
+    }; MAX_IO_VECTORS];
+    let mut length = 0;
+    for ((base, buffer_length), vector) in buffers.zip(&mut vectors) {
+      *vector = libc::iovec {
+        iov_base: base.cast(),
+        iov_len: buffer_length,
+      };
+      length += 1;
+    }
+
+    Self {
+      vectors,
+      length,
+      _buffers: PhantomData,
+    }
+  }
+
+  /// Buffers to be read into.
+  pub(super) fn from_mut(bufs: &'a mut [&mut [u8]]) -> Self {
+    Self::new(bufs.iter_mut().map(|buf| (buf.as_mut_ptr(), buf.len())))
+  }
+
+  /// Buffers to be written, which readv must not be given.
+  pub(super) fn from_ref(bufs: &'a [&[u8]]) -> Self {
+    Self::new(bufs.iter().map(|buf| (buf.as_ptr().cast_mut(), buf.len())))
+  }
+
+  pub(super) fn as_ptr(&self) -> *const libc::iovec {
+    self.vectors.as_ptr()
+  }
+
+  pub(super) fn len(&self) -> usize {
+    self.length
+  }
+}
+
+/// A nonblocking file descriptor read and written as a stream, like a pipe or a connected socket.
+pub struct LinuxStream<'a> {
+  registration: Registration<'a>,
+}
+
+impl<'a> LinuxStream<'a> {
+  pub fn new(registration: Registration<'a>) -> Self {
+    Self { registration }
+  }
+
+  pub fn registration(&self) -> &Registration<'a> {
+    &self.registration
+  }
+
+  /// Like [StreamWrite::write] for a socket, which fails with `EPIPE` once the connection is closed
+  /// instead of raising `SIGPIPE`.
+  pub(super) async fn send<'b>(&mut self, data: &'b [u8]) -> Result<(usize, &'b [u8]), LinuxError> {
+    let length = self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::sendto,
+          file_descriptor,
+          data.as_ptr(),
+          data.len(),
+          libc::MSG_NOSIGNAL,
+          0,
+          0
+        )
+      })
+      .await?;
+
+    Ok((length, &data[length..]))
+  }
+
+  /// Like [StreamWrite::write_vectored] for a socket, which fails with `EPIPE` once the connection
+  /// is closed instead of raising `SIGPIPE`.
+  pub(super) async fn send_vectored(&mut self, bufs: &[&[u8]]) -> Result<usize, LinuxError> {
+    let vectors = IoVectors::from_ref(bufs);
+    // Safety: msghdr is plain data
+    let mut message: libc::msghdr = unsafe { mem::zeroed() };
+    message.msg_iov = vectors.as_ptr().cast_mut();
+    message.msg_iovlen = vectors.len();
+
+    self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::sendmsg,
+          file_descriptor,
+          &raw const message,
+          libc::MSG_NOSIGNAL
+        )
+      })
+      .await
+  }
+}
+
+impl StreamRead for LinuxStream<'_> {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    let length = self
+      .registration
+      .io_operation(Interest::Read, |file_descriptor| unsafe {
+        syscall!(Sysno::read, file_descriptor, buf.as_mut_ptr(), buf.len())
+      })
+      .await?;
+
+    Ok(&buf[..length])
+  }
+
+  async fn read_vectored(&mut self, bufs: &mut [&mut [u8]]) -> Result<usize, LinuxError> {
+    let vectors = IoVectors::from_mut(bufs);
+    self
+      .registration
+      .io_operation(Interest::Read, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::readv,
+          file_descriptor,
+          vectors.as_ptr(),
+          vectors.len()
+        )
+      })
+      .await
+  }
+}
+
+impl StreamWrite for LinuxStream<'_> {
+  type Error = LinuxError;
+
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), LinuxError> {
+    let length = self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(Sysno::write, file_descriptor, data.as_ptr(), data.len())
+      })
+      .await?;
+
+    Ok((length, &data[length..]))
+  }
+
+  async fn write_vectored(&mut self, bufs: &[&[u8]]) -> Result<usize, LinuxError> {
+    let vectors = IoVectors::from_ref(bufs);
+    self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::writev,
+          file_descriptor,
+          vectors.as_ptr(),
+          vectors.len()
+        )
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::{future::poll_fn, pin::pin, task::Poll};
+
+  use syscalls::{Sysno, syscall};
+
+  use crate::{
+    io::{StreamRead, StreamWrite},
+    platform::linux::{
+      OwnedFileDescriptor,
+      io::{LinuxIo, LinuxStream, Registration},
+    },
+  };
+
+  fn pipe() -> (LinuxStream<'static>, LinuxStream<'static>) {
+    let mut file_descriptors = [0i32; 2];
+    unsafe {
+      syscall!(
+        Sysno::pipe2,
+        file_descriptors.as_mut_ptr(),
+        libc::O_NONBLOCK | libc::O_CLOEXEC
+      )
+    }
+    .unwrap();
+
+    let [read, write] = file_descriptors.map(|file_descriptor| {
+      let file_descriptor = OwnedFileDescriptor::new(file_descriptor as _);
+      LinuxStream::new(Registration::new(LinuxIo::instance(), file_descriptor).unwrap())
+    });
+    (read, write)
+  }
+
+  #[test]
+  fn read_waits_for_write() {
+    let (mut reader, mut writer) = pipe();
+
+    LinuxIo::instance().block_on(async {
+      let mut buf = [0; 16];
+      let mut read = pin!(reader.read(&mut buf));
+      let mut write = pin!(writer.write(b"hello"));
+      let mut written = false;
+
+      // the read has to wait for the reactor to see the write
+      let read = poll_fn(|cx| {
+        if let Poll::Ready(result) = read.as_mut().poll(cx) {
+          return Poll::Ready(result);
+        }
+        if !written && let Poll::Ready(result) = write.as_mut().poll(cx) {
+          assert_eq!(result.unwrap().0, 5);
+          written = true;
+        }
+        Poll::Pending
+      })
+      .await
+      .unwrap();
+
+      assert_eq!(read, b"hello");
+    });
+  }
+
+  #[test]
+  fn vectored() {
+    let (mut reader, mut writer) = pipe();
+
+    LinuxIo::instance().block_on(async {
+      let written = writer
+        .write_vectored(&[b"hello", b"", b" world"])
+        .await
+        .unwrap();
+      assert_eq!(written, 11);
+
+      let mut first = [0; 4];
+      let mut second = [0; 16];
+      let read = reader
+        .read_vectored(&mut [&mut first, &mut second])
+        .await
+        .unwrap();
+      assert_eq!(read, 11);
+      assert_eq!(&first, b"hell");
+      assert_eq!(&second[..7], b"o world");
+    });
+  }
+}
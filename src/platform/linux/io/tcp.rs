@@ -2,42 +2,105 @@ use core::net::SocketAddr;
 use syscalls::{Sysno, syscall};
 
 use crate::{
-  io::net::tcp::{TcpClient, TcpSocket},
+  io::{
+    StreamPeek, StreamRead, StreamWrite,
+    net::tcp::{
+      Shutdown, TcpClient, TcpListener, TcpServer, TcpSocket, TcpSocketNagle, TcpSocketShutdown,
+    },
+  },
   platform::linux::{
-    FileDescriptor,
-    io::{LinuxError, LinuxIo},
+    FileDescriptor, OwnedFileDescriptor,
+    io::{Interest, LinuxError, LinuxIo, LinuxStream, Registration, net},
   },
 };
 
 impl TcpClient for LinuxIo {
   type Error = LinuxError;
-  async fn open_connection<'a>(&'a self, address: SocketAddr) -> Result<LinuxSocket, LinuxError> {
-    let family = match &address {
-      SocketAddr::V4(_) => libc::AF_INET,
-      SocketAddr::V6(_) => libc::AF_INET6,
-    };
+  async fn open_connection<'a>(
+    &'a self,
+    address: SocketAddr,
+  ) -> Result<LinuxSocket<'a>, LinuxError> {
+    let socket = net::socket(
+      net::address_family(&address),
+      libc::SOCK_STREAM | libc::SOCK_NONBLOCK,
+      libc::IPPROTO_TCP,
+    )
+    .map_err(LinuxError)?;
+    let registration = Registration::new(self, OwnedFileDescriptor::new(socket))?;
+
+    net::connect_registered(&registration, address).await?;
+
+    let local_address = net::local_address(registration.file_descriptor()).map_err(LinuxError)?;
+    Ok(LinuxSocket {
+      stream: LinuxStream::new(registration),
+      local_address,
+      peer_address: address,
+    })
+  }
+}
 
-    let _socket = unsafe {
-      syscall!(
-        Sysno::socket,
-        family,
+/// How many connections can wait to be accepted by a [LinuxListener].
+const BACKLOG: i32 = 128;
+
+impl TcpServer for LinuxIo {
+  type Error = LinuxError;
+
+  async fn listen(&self, address: SocketAddr) -> Result<LinuxListener<'_>, LinuxError> {
+    let socket = OwnedFileDescriptor::new(
+      net::socket(
+        net::address_family(&address),
         libc::SOCK_STREAM | libc::SOCK_NONBLOCK,
-        libc::IPPROTO_TCP
+        libc::IPPROTO_TCP,
       )
-    }
-    .map_err(LinuxError)?;
+      .map_err(LinuxError)?,
+    );
+
+    net::set_option(socket.get(), libc::SOL_SOCKET, libc::SO_REUSEADDR, 1i32)
+      .map_err(LinuxError)?;
+    net::bind(socket.get(), address).map_err(LinuxError)?;
+    net::listen(socket.get(), BACKLOG).map_err(LinuxError)?;
 
-    todo!("register on io worker thread")
+    Ok(LinuxListener {
+      local_address: net::local_address(socket.get()).map_err(LinuxError)?,
+      registration: Registration::new(self, socket)?,
+    })
   }
 }
 
-pub struct LinuxSocket {
-  file_descriptor: FileDescriptor,
+/// A listening TCP socket.
+pub struct LinuxListener<'a> {
+  registration: Registration<'a>,
+  local_address: SocketAddr,
+}
+
+impl LinuxListener<'_> {
+  pub fn local_address(&self) -> SocketAddr {
+    self.local_address
+  }
+}
+
+impl<'a> TcpListener for LinuxListener<'a> {
+  type Error = LinuxError;
+
+  async fn accept(&mut self) -> Result<LinuxSocket<'a>, LinuxError> {
+    let socket = net::accept_registered(&self.registration).await?;
+
+    Ok(LinuxSocket {
+      local_address: net::local_address(socket.get()).map_err(LinuxError)?,
+      peer_address: net::peer_address(socket.get()).map_err(LinuxError)?,
+      stream: LinuxStream::new(Registration::new(self.registration.io(), socket)?),
+    })
+  }
+}
+
+/// A connected TCP socket, which is closed when dropped.
+pub struct LinuxSocket<'a> {
+  stream: LinuxStream<'a>,
   local_address: SocketAddr,
   peer_address: SocketAddr,
 }
 
-impl TcpSocket for LinuxSocket {
+impl TcpSocket for LinuxSocket<'_> {
   fn local_address(&self) -> SocketAddr {
     self.local_address
   }
@@ -46,3 +109,172 @@ impl TcpSocket for LinuxSocket {
     self.peer_address
   }
 }
+
+impl TcpSocketNagle for LinuxSocket<'_> {
+  type Error = LinuxError;
+
+  fn set_nagle_algorithm(&self, value: bool) -> Result<(), LinuxError> {
+    net::set_nagle_algorithm(self.file_descriptor(), value).map_err(LinuxError)
+  }
+}
+
+impl TcpSocketShutdown for LinuxSocket<'_> {
+  type Error = LinuxError;
+
+  fn shutdown(&self, how: Shutdown) -> Result<(), LinuxError> {
+    net::shutdown(self.file_descriptor(), how).map_err(LinuxError)
+  }
+}
+
+impl LinuxSocket<'_> {
+  fn file_descriptor(&self) -> FileDescriptor {
+    self.stream.registration().file_descriptor()
+  }
+}
+
+impl StreamRead for LinuxSocket<'_> {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    self.stream.read(buf).await
+  }
+
+  async fn read_vectored(&mut self, bufs: &mut [&mut [u8]]) -> Result<usize, LinuxError> {
+    self.stream.read_vectored(bufs).await
+  }
+}
+
+impl StreamWrite for LinuxSocket<'_> {
+  type Error = LinuxError;
+
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), LinuxError> {
+    self.stream.send(data).await
+  }
+
+  async fn write_vectored(&mut self, bufs: &[&[u8]]) -> Result<usize, LinuxError> {
+    self.stream.send_vectored(bufs).await
+  }
+
+  /// Shuts down writing, so the peer reads the end of the stream.
+  async fn close(&mut self) -> Result<(), LinuxError> {
+    net::shutdown(self.file_descriptor(), Shutdown::Write).map_err(LinuxError)
+  }
+}
+
+impl StreamPeek for LinuxSocket<'_> {
+  type Error = LinuxError;
+
+  async fn peek<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    let length = self
+      .stream
+      .registration()
+      .io_operation(Interest::Read, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::recvfrom,
+          file_descriptor,
+          buf.as_mut_ptr(),
+          buf.len(),
+          libc::MSG_PEEK,
+          0,
+          0
+        )
+      })
+      .await?;
+
+    Ok(&buf[..length])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::net::{Ipv4Addr, SocketAddr};
+
+  use syscalls::Errno;
+
+  use crate::{
+    io::{
+      StreamPeek, StreamRead, StreamWrite,
+      net::tcp::{
+        Shutdown, TcpClient, TcpListener, TcpServer, TcpSocket, TcpSocketNagle, TcpSocketShutdown,
+      },
+    },
+    platform::linux::io::{LinuxIo, LinuxSocket, join, raises_sigpipe},
+  };
+
+  async fn connect(io: &'static LinuxIo) -> (LinuxSocket<'static>, LinuxSocket<'static>) {
+    let mut listener = io
+      .listen(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+      .await
+      .unwrap();
+
+    let address = listener.local_address();
+
+    let (server, client) = join(listener.accept(), io.open_connection(address)).await;
+
+    (server.unwrap(), client.unwrap())
+  }
+
+  #[test]
+  fn loopback() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut server, mut client) = connect(io).await;
+      assert_eq!(server.peer_address(), client.local_address());
+      assert_eq!(server.local_address(), client.peer_address());
+
+      client.set_nagle_algorithm(false).unwrap();
+      assert_eq!(client.write(b"ping").await.unwrap(), (4, &b""[..]));
+
+      let mut buf = [0; 16];
+      assert_eq!(server.peek(&mut buf).await.unwrap(), b"ping");
+      assert_eq!(server.read(&mut buf).await.unwrap(), b"ping");
+
+      assert_eq!(server.write(b"pong").await.unwrap().0, 4);
+      assert_eq!(client.read(&mut buf).await.unwrap(), b"pong");
+    });
+  }
+
+  #[test]
+  fn shutdown() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut server, client) = connect(io).await;
+
+      client.shutdown(Shutdown::Write).unwrap();
+      let mut buf = [0; 16];
+      assert_eq!(server.read(&mut buf).await.unwrap(), b"");
+    });
+  }
+
+  #[test]
+  fn drop_closes() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut server, client) = connect(io).await;
+
+      drop(client);
+      let mut buf = [0; 16];
+      assert_eq!(server.read(&mut buf).await.unwrap(), b"");
+    });
+  }
+
+  #[test]
+  fn write_after_peer_dropped() {
+    let io = LinuxIo::instance();
+    let raised = raises_sigpipe(|| {
+      io.block_on(async {
+        let (mut server, client) = connect(io).await;
+        drop(client);
+
+        // the first writes can succeed before the peer's reset arrives
+        let error = loop {
+          if let Err(error) = server.write(b"lost").await {
+            break error;
+          }
+        };
+        assert_eq!(error.0, Errno::EPIPE);
+      })
+    });
+    assert!(!raised);
+  }
+}
@@ -0,0 +1,279 @@
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::{
+  io::{
+    DatagramReader, DatagramWriter,
+    net::udp::{UdpIo, UdpSocket, UdpSocketMulticast},
+  },
+  platform::linux::{
+    FileDescriptor, OwnedFileDescriptor,
+    io::{
+      Interest, LinuxError, LinuxIo, Registration,
+      net::{self, RawSocketAddress},
+    },
+  },
+};
+
+impl UdpIo for LinuxIo {
+  type Error = LinuxError;
+
+  async fn bind<'a>(&'a self, address: SocketAddr) -> Result<LinuxUdpSocket<'a>, LinuxError> {
+    let socket = OwnedFileDescriptor::new(
+      net::socket(
+        net::address_family(&address),
+        libc::SOCK_DGRAM | libc::SOCK_NONBLOCK,
+        libc::IPPROTO_UDP,
+      )
+      .map_err(LinuxError)?,
+    );
+    net::bind(socket.get(), address).map_err(LinuxError)?;
+
+    Ok(LinuxUdpSocket {
+      local_address: net::local_address(socket.get()).map_err(LinuxError)?,
+      registration: Registration::new(self, socket)?,
+    })
+  }
+}
+
+/// A UDP socket, which is closed when dropped.
+pub struct LinuxUdpSocket<'a> {
+  registration: Registration<'a>,
+  local_address: SocketAddr,
+}
+
+impl LinuxUdpSocket<'_> {
+  fn file_descriptor(&self) -> FileDescriptor {
+    self.registration.file_descriptor()
+  }
+
+  fn set_multicast_v4(&self, name: i32, group: Ipv4Addr, interface: Ipv4Addr) -> Result<(), Errno> {
+    let request = libc::ip_mreq {
+      imr_multiaddr: libc::in_addr {
+        s_addr: u32::from(group).to_be(),
+      },
+      imr_interface: libc::in_addr {
+        s_addr: u32::from(interface).to_be(),
+      },
+    };
+    net::set_option(self.file_descriptor(), libc::IPPROTO_IP, name, request)
+  }
+
+  fn set_multicast_v6(&self, name: i32, group: Ipv6Addr, interface: u32) -> Result<(), Errno> {
+    let request = libc::ipv6_mreq {
+      ipv6mr_multiaddr: libc::in6_addr {
+        s6_addr: group.octets(),
+      },
+      ipv6mr_interface: interface,
+    };
+    net::set_option(self.file_descriptor(), libc::IPPROTO_IPV6, name, request)
+  }
+}
+
+impl UdpSocket for LinuxUdpSocket<'_> {
+  type Error = LinuxError;
+
+  fn local_address(&self) -> SocketAddr {
+    self.local_address
+  }
+
+  async fn connect(&mut self, address: SocketAddr) -> Result<(), LinuxError> {
+    // connecting a datagram socket only sets its peer, so it never blocks
+    net::connect(self.file_descriptor(), address).map_err(LinuxError)
+  }
+
+  async fn send_to(&mut self, data: &[u8], address: SocketAddr) -> Result<(), LinuxError> {
+    let address = RawSocketAddress::from(address);
+    self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::sendto,
+          file_descriptor,
+          data.as_ptr(),
+          data.len(),
+          0,
+          address.as_ptr(),
+          address.len()
+        )
+      })
+      .await?;
+
+    Ok(())
+  }
+
+  async fn recv_from<'a>(
+    &mut self,
+    buf: &'a mut [u8],
+  ) -> Result<(&'a [u8], SocketAddr), LinuxError> {
+    let mut address = RawSocketAddress::empty();
+    let length = self
+      .registration
+      .io_operation(Interest::Read, |file_descriptor| {
+        *address.len_mut() = size_of::<libc::sockaddr_storage>() as _;
+        unsafe {
+          syscall!(
+            Sysno::recvfrom,
+            file_descriptor,
+            buf.as_mut_ptr(),
+            buf.len(),
+            0,
+            address.as_mut_ptr(),
+            &raw mut *address.len_mut()
+          )
+        }
+      })
+      .await?;
+
+    let address = address
+      .to_socket_address()
+      .ok_or(LinuxError(Errno::EAFNOSUPPORT))?;
+    Ok((&buf[..length], address))
+  }
+
+  fn set_broadcast(&self, value: bool) -> Result<(), LinuxError> {
+    net::set_option(
+      self.file_descriptor(),
+      libc::SOL_SOCKET,
+      libc::SO_BROADCAST,
+      i32::from(value),
+    )
+    .map_err(LinuxError)
+  }
+}
+
+impl UdpSocketMulticast for LinuxUdpSocket<'_> {
+  type Error = LinuxError;
+
+  fn join_multicast_v4(&self, group: Ipv4Addr, interface: Ipv4Addr) -> Result<(), LinuxError> {
+    self
+      .set_multicast_v4(libc::IP_ADD_MEMBERSHIP, group, interface)
+      .map_err(LinuxError)
+  }
+
+  fn leave_multicast_v4(&self, group: Ipv4Addr, interface: Ipv4Addr) -> Result<(), LinuxError> {
+    self
+      .set_multicast_v4(libc::IP_DROP_MEMBERSHIP, group, interface)
+      .map_err(LinuxError)
+  }
+
+  fn join_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> Result<(), LinuxError> {
+    self
+      .set_multicast_v6(libc::IPV6_ADD_MEMBERSHIP, group, interface)
+      .map_err(LinuxError)
+  }
+
+  fn leave_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> Result<(), LinuxError> {
+    self
+      .set_multicast_v6(libc::IPV6_DROP_MEMBERSHIP, group, interface)
+      .map_err(LinuxError)
+  }
+}
+
+impl DatagramReader for LinuxUdpSocket<'_> {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    let length = self
+      .registration
+      .io_operation(Interest::Read, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::recvfrom,
+          file_descriptor,
+          buf.as_mut_ptr(),
+          buf.len(),
+          0,
+          0,
+          0
+        )
+      })
+      .await?;
+
+    Ok(&buf[..length])
+  }
+}
+
+impl DatagramWriter for LinuxUdpSocket<'_> {
+  type Error = LinuxError;
+
+  async fn write(&mut self, buf: &[u8]) -> Result<(), LinuxError> {
+    self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::sendto,
+          file_descriptor,
+          buf.as_ptr(),
+          buf.len(),
+          0,
+          0,
+          0
+        )
+      })
+      .await?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::net::{Ipv4Addr, SocketAddr};
+
+  use crate::{
+    io::{
+      DatagramReader, DatagramWriter,
+      net::udp::{UdpIo, UdpSocket, UdpSocketMulticast},
+    },
+    platform::linux::io::LinuxIo,
+  };
+
+  #[test]
+  fn send_and_receive() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let localhost = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+      let mut first = io.bind(localhost).await.unwrap();
+      let mut second = io.bind(localhost).await.unwrap();
+
+      first
+        .send_to(b"hello", second.local_address())
+        .await
+        .unwrap();
+      let mut buf = [0; 16];
+      let (datagram, address) = second.recv_from(&mut buf).await.unwrap();
+      assert_eq!(datagram, b"hello");
+      assert_eq!(address, first.local_address());
+
+      second.connect(first.local_address()).await.unwrap();
+      second.write(b"world").await.unwrap();
+      assert_eq!(first.read(&mut buf).await.unwrap(), b"world");
+
+      // the rest of a datagram that doesn't fit is discarded
+      second.write(b"truncated").await.unwrap();
+      assert_eq!(first.read(&mut buf[..5]).await.unwrap(), b"trunc");
+    });
+  }
+
+  #[test]
+  fn options() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let socket = io
+        .bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+        .await
+        .unwrap();
+
+      socket.set_broadcast(true).unwrap();
+
+      let group = Ipv4Addr::new(239, 255, 0, 1);
+      socket
+        .join_multicast_v4(group, Ipv4Addr::LOCALHOST)
+        .unwrap();
+      socket
+        .leave_multicast_v4(group, Ipv4Addr::LOCALHOST)
+        .unwrap();
+    });
+  }
+}
@@ -0,0 +1,667 @@
+use core::{mem, ptr};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::{
+  io::{
+    DatagramReader, DatagramWriter, StreamRead, StreamWrite,
+    net::{
+      tcp::Shutdown,
+      unix::{UnixCredentials, UnixDatagram, UnixIo, UnixListener, UnixSocketAddress, UnixStream},
+    },
+  },
+  platform::linux::{
+    FileDescriptor, OwnedFileDescriptor,
+    io::{
+      Interest, LinuxError, LinuxIo, LinuxStream, Registration,
+      net::{self, RawSocketAddress},
+    },
+  },
+};
+
+/// How many file descriptors can be passed with one message.
+pub const MAX_PASSED_FILE_DESCRIPTORS: usize = 16;
+
+/// How many connections can wait to be accepted by a [LinuxUnixListener].
+const BACKLOG: i32 = 128;
+
+const CONTROL_SIZE: usize =
+  unsafe { libc::CMSG_SPACE((MAX_PASSED_FILE_DESCRIPTORS * size_of::<i32>()) as u32) } as usize;
+
+/// The ancillary data of a message, aligned for its headers.
+#[repr(C, align(8))]
+struct ControlBuffer([u8; CONTROL_SIZE]);
+
+fn unix_socket(kind: i32) -> Result<OwnedFileDescriptor, LinuxError> {
+  net::socket(libc::AF_UNIX, kind | libc::SOCK_NONBLOCK, 0)
+    .map(OwnedFileDescriptor::new)
+    .map_err(LinuxError)
+}
+
+fn socket_pair(
+  io: &LinuxIo,
+  kind: i32,
+) -> Result<(Registration<'_>, Registration<'_>), LinuxError> {
+  let mut file_descriptors = [0i32; 2];
+  unsafe {
+    syscall!(
+      Sysno::socketpair,
+      libc::AF_UNIX,
+      kind | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+      0,
+      file_descriptors.as_mut_ptr()
+    )
+  }
+  .map_err(LinuxError)?;
+
+  let [first, second] =
+    file_descriptors.map(|file_descriptor| OwnedFileDescriptor::new(file_descriptor as _));
+  Ok((
+    Registration::new(io, first)?,
+    Registration::new(io, second)?,
+  ))
+}
+
+fn unix_address(address: Result<RawSocketAddress, Errno>) -> Result<UnixSocketAddress, LinuxError> {
+  address
+    .map_err(LinuxError)?
+    .to_unix_address()
+    .ok_or(LinuxError(Errno::EAFNOSUPPORT))
+}
+
+/// Sends `data` along with duplicates of `file_descriptors`, which stay open in this process.
+async fn send_with_file_descriptors(
+  registration: &Registration<'_>,
+  data: &[u8],
+  file_descriptors: &[FileDescriptor],
+) -> Result<usize, LinuxError> {
+  if file_descriptors.len() > MAX_PASSED_FILE_DESCRIPTORS {
+    return Err(LinuxError(Errno::EINVAL));
+  }
+
+  let mut control = ControlBuffer([0; CONTROL_SIZE]);
+  let mut data = libc::iovec {
+    iov_base: data.as_ptr().cast_mut().cast(),
+    iov_len: data.len(),
+  };
+  // Safety: msghdr is plain data
+  let mut message: libc::msghdr = unsafe { mem::zeroed() };
+  message.msg_iov = &raw mut data;
+  message.msg_iovlen = 1;
+
+  if !file_descriptors.is_empty() {
+    let length = size_of_val(file_descriptors) as u32;
+    message.msg_control = control.0.as_mut_ptr().cast();
+    message.msg_controllen = unsafe { libc::CMSG_SPACE(length) } as _;
+
+    // Safety: the control buffer has space for the header and every file descriptor
+    unsafe {
+      let header = libc::CMSG_FIRSTHDR(&raw const message);
+      (*header).cmsg_level = libc::SOL_SOCKET;
+      (*header).cmsg_type = libc::SCM_RIGHTS;
+      (*header).cmsg_len = libc::CMSG_LEN(length) as _;
+      ptr::copy_nonoverlapping(
+        file_descriptors.as_ptr(),
+        libc::CMSG_DATA(header).cast(),
+        file_descriptors.len(),
+      );
+    }
+  }
+
+  registration
+    .io_operation(Interest::Write, |file_descriptor| unsafe {
+      syscall!(
+        Sysno::sendmsg,
+        file_descriptor,
+        &raw const message,
+        libc::MSG_NOSIGNAL
+      )
+    })
+    .await
+}
+
+/// Receives into `buf`, and writes the file descriptors passed along with the data to
+/// `file_descriptors`. Also returns whether more file descriptors were passed than fit in
+/// `file_descriptors` or in the control buffer, in which case the rest were closed.
+async fn recv_with_file_descriptors<'a, 'b>(
+  registration: &Registration<'_>,
+  buf: &'a mut [u8],
+  file_descriptors: &'b mut [Option<OwnedFileDescriptor>],
+) -> Result<(&'a [u8], &'b mut [Option<OwnedFileDescriptor>], bool), LinuxError> {
+  let mut control = ControlBuffer([0; CONTROL_SIZE]);
+  let mut data = libc::iovec {
+    iov_base: buf.as_mut_ptr().cast(),
+    iov_len: buf.len(),
+  };
+  // Safety: msghdr is plain data
+  let mut message: libc::msghdr = unsafe { mem::zeroed() };
+  message.msg_iov = &raw mut data;
+  message.msg_iovlen = 1;
+  message.msg_control = control.0.as_mut_ptr().cast();
+
+  let length = registration
+    .io_operation(Interest::Read, |file_descriptor| {
+      message.msg_controllen = CONTROL_SIZE as _;
+      unsafe {
+        syscall!(
+          Sysno::recvmsg,
+          file_descriptor,
+          &raw mut message,
+          libc::MSG_CMSG_CLOEXEC
+        )
+      }
+    })
+    .await?;
+
+  // the kernel closes the file descriptors that didn't fit in the control buffer
+  let mut truncated = message.msg_flags & libc::MSG_CTRUNC != 0;
+  let mut received = 0;
+  // Safety: the kernel wrote the headers within msg_controllen
+  unsafe {
+    let mut header = libc::CMSG_FIRSTHDR(&raw const message);
+    while !header.is_null() {
+      if (*header).cmsg_level == libc::SOL_SOCKET && (*header).cmsg_type == libc::SCM_RIGHTS {
+        let count = ((*header).cmsg_len as usize - libc::CMSG_LEN(0) as usize) / size_of::<i32>();
+        let data = libc::CMSG_DATA(header).cast::<i32>();
+
+        for index in 0..count {
+          let file_descriptor =
+            OwnedFileDescriptor::new(data.add(index).read_unaligned() as FileDescriptor);
+          match file_descriptors.get_mut(received) {
+            Some(slot) => {
+              *slot = Some(file_descriptor);
+              received += 1;
+            }
+            None => truncated = true,
+          }
+        }
+      }
+
+      header = libc::CMSG_NXTHDR(&raw const message, header);
+    }
+  }
+
+  // the data has been taken off the socket either way, so it's returned along with the truncation
+  Ok((&buf[..length], &mut file_descriptors[..received], truncated))
+}
+
+impl UnixIo for LinuxIo {
+  type Error = LinuxError;
+
+  async fn connect<'a>(
+    &'a self,
+    address: &UnixSocketAddress,
+  ) -> Result<LinuxUnixStream<'a>, LinuxError> {
+    let registration = Registration::new(self, unix_socket(libc::SOCK_STREAM)?)?;
+    net::connect_registered(&registration, address).await?;
+
+    Ok(LinuxUnixStream {
+      stream: LinuxStream::new(registration),
+    })
+  }
+
+  /// Listens at `address`. Listening at an unnamed address binds to a unique abstract address
+  /// instead.
+  async fn listen<'a>(
+    &'a self,
+    address: &UnixSocketAddress,
+  ) -> Result<LinuxUnixListener<'a>, LinuxError> {
+    let socket = unix_socket(libc::SOCK_STREAM)?;
+    net::bind(socket.get(), address).map_err(LinuxError)?;
+    net::listen(socket.get(), BACKLOG).map_err(LinuxError)?;
+
+    Ok(LinuxUnixListener {
+      registration: Registration::new(self, socket)?,
+    })
+  }
+
+  /// Binds a datagram socket to `address`. Binding to an unnamed address binds to a unique abstract
+  /// address instead.
+  async fn bind_datagram<'a>(
+    &'a self,
+    address: &UnixSocketAddress,
+  ) -> Result<LinuxUnixDatagram<'a>, LinuxError> {
+    let socket = unix_socket(libc::SOCK_DGRAM)?;
+    net::bind(socket.get(), address).map_err(LinuxError)?;
+
+    Ok(LinuxUnixDatagram {
+      registration: Registration::new(self, socket)?,
+    })
+  }
+
+  fn stream_pair<'a>(&'a self) -> Result<(LinuxUnixStream<'a>, LinuxUnixStream<'a>), LinuxError> {
+    let (first, second) = socket_pair(self, libc::SOCK_STREAM)?;
+    Ok((
+      LinuxUnixStream {
+        stream: LinuxStream::new(first),
+      },
+      LinuxUnixStream {
+        stream: LinuxStream::new(second),
+      },
+    ))
+  }
+
+  fn datagram_pair<'a>(
+    &'a self,
+  ) -> Result<(LinuxUnixDatagram<'a>, LinuxUnixDatagram<'a>), LinuxError> {
+    let (first, second) = socket_pair(self, libc::SOCK_DGRAM)?;
+    Ok((
+      LinuxUnixDatagram {
+        registration: first,
+      },
+      LinuxUnixDatagram {
+        registration: second,
+      },
+    ))
+  }
+}
+
+/// A listening Unix stream socket.
+pub struct LinuxUnixListener<'a> {
+  registration: Registration<'a>,
+}
+
+impl<'a> UnixListener for LinuxUnixListener<'a> {
+  type Error = LinuxError;
+
+  fn local_address(&self) -> Result<UnixSocketAddress, LinuxError> {
+    unix_address(net::raw_local_address(self.registration.file_descriptor()))
+  }
+
+  async fn accept(&mut self) -> Result<LinuxUnixStream<'a>, LinuxError> {
+    let socket = net::accept_registered(&self.registration).await?;
+
+    Ok(LinuxUnixStream {
+      stream: LinuxStream::new(Registration::new(self.registration.io(), socket)?),
+    })
+  }
+}
+
+/// A connected Unix stream socket, which is closed when dropped.
+pub struct LinuxUnixStream<'a> {
+  stream: LinuxStream<'a>,
+}
+
+impl LinuxUnixStream<'_> {
+  fn file_descriptor(&self) -> FileDescriptor {
+    self.stream.registration().file_descriptor()
+  }
+
+  /// Writes `data` along with duplicates of `file_descriptors`, which stay open in this process.
+  /// Returns how many bytes were written, like [StreamWrite::write].
+  pub async fn send_with_file_descriptors(
+    &mut self,
+    data: &[u8],
+    file_descriptors: &[FileDescriptor],
+  ) -> Result<usize, LinuxError> {
+    send_with_file_descriptors(self.stream.registration(), data, file_descriptors).await
+  }
+
+  /// Reads into `buf`, and writes the file descriptors passed along with the data to
+  /// `file_descriptors`. Also returns whether more file descriptors were passed than fit, in which
+  /// case the rest were closed.
+  pub async fn recv_with_file_descriptors<'a, 'b>(
+    &mut self,
+    buf: &'a mut [u8],
+    file_descriptors: &'b mut [Option<OwnedFileDescriptor>],
+  ) -> Result<(&'a [u8], &'b mut [Option<OwnedFileDescriptor>], bool), LinuxError> {
+    recv_with_file_descriptors(self.stream.registration(), buf, file_descriptors).await
+  }
+}
+
+impl UnixStream for LinuxUnixStream<'_> {
+  type Error = LinuxError;
+
+  fn local_address(&self) -> Result<UnixSocketAddress, LinuxError> {
+    unix_address(net::raw_local_address(self.file_descriptor()))
+  }
+
+  fn peer_address(&self) -> Result<UnixSocketAddress, LinuxError> {
+    unix_address(net::raw_peer_address(self.file_descriptor()))
+  }
+
+  fn peer_credentials(&self) -> Result<UnixCredentials, LinuxError> {
+    let credentials: libc::ucred =
+      net::get_option(self.file_descriptor(), libc::SOL_SOCKET, libc::SO_PEERCRED)
+        .map_err(LinuxError)?;
+
+    Ok(UnixCredentials {
+      process_id: credentials.pid,
+      user_id: credentials.uid,
+      group_id: credentials.gid,
+    })
+  }
+}
+
+impl StreamRead for LinuxUnixStream<'_> {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    self.stream.read(buf).await
+  }
+
+  async fn read_vectored(&mut self, bufs: &mut [&mut [u8]]) -> Result<usize, LinuxError> {
+    self.stream.read_vectored(bufs).await
+  }
+}
+
+impl StreamWrite for LinuxUnixStream<'_> {
+  type Error = LinuxError;
+
+  async fn write<'a>(&mut self, data: &'a [u8]) -> Result<(usize, &'a [u8]), LinuxError> {
+    self.stream.send(data).await
+  }
+
+  async fn write_vectored(&mut self, bufs: &[&[u8]]) -> Result<usize, LinuxError> {
+    self.stream.send_vectored(bufs).await
+  }
+
+  /// Shuts down writing, so the peer reads the end of the stream.
+  async fn close(&mut self) -> Result<(), LinuxError> {
+    net::shutdown(self.file_descriptor(), Shutdown::Write).map_err(LinuxError)
+  }
+}
+
+/// A Unix datagram socket, which is closed when dropped.
+pub struct LinuxUnixDatagram<'a> {
+  registration: Registration<'a>,
+}
+
+impl LinuxUnixDatagram<'_> {
+  /// Writes `data` as one datagram along with duplicates of `file_descriptors`, which stay open in
+  /// this process.
+  pub async fn send_with_file_descriptors(
+    &mut self,
+    data: &[u8],
+    file_descriptors: &[FileDescriptor],
+  ) -> Result<(), LinuxError> {
+    send_with_file_descriptors(&self.registration, data, file_descriptors).await?;
+    Ok(())
+  }
+
+  /// Reads one datagram into `buf`, and writes the file descriptors passed along with it to
+  /// `file_descriptors`. Also returns whether more file descriptors were passed than fit, in which
+  /// case the rest were closed.
+  pub async fn recv_with_file_descriptors<'a, 'b>(
+    &mut self,
+    buf: &'a mut [u8],
+    file_descriptors: &'b mut [Option<OwnedFileDescriptor>],
+  ) -> Result<(&'a [u8], &'b mut [Option<OwnedFileDescriptor>], bool), LinuxError> {
+    recv_with_file_descriptors(&self.registration, buf, file_descriptors).await
+  }
+}
+
+impl UnixDatagram for LinuxUnixDatagram<'_> {
+  type Error = LinuxError;
+
+  fn local_address(&self) -> Result<UnixSocketAddress, LinuxError> {
+    unix_address(net::raw_local_address(self.registration.file_descriptor()))
+  }
+
+  async fn connect(&mut self, address: &UnixSocketAddress) -> Result<(), LinuxError> {
+    net::connect(self.registration.file_descriptor(), address).map_err(LinuxError)
+  }
+
+  async fn send_to(&mut self, data: &[u8], address: &UnixSocketAddress) -> Result<(), LinuxError> {
+    let address = RawSocketAddress::from(address);
+    self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::sendto,
+          file_descriptor,
+          data.as_ptr(),
+          data.len(),
+          libc::MSG_NOSIGNAL,
+          address.as_ptr(),
+          address.len()
+        )
+      })
+      .await?;
+
+    Ok(())
+  }
+
+  async fn recv_from<'a>(
+    &mut self,
+    buf: &'a mut [u8],
+  ) -> Result<(&'a [u8], UnixSocketAddress), LinuxError> {
+    let mut address = RawSocketAddress::empty();
+    let length = self
+      .registration
+      .io_operation(Interest::Read, |file_descriptor| {
+        *address.len_mut() = size_of::<libc::sockaddr_storage>() as _;
+        unsafe {
+          syscall!(
+            Sysno::recvfrom,
+            file_descriptor,
+            buf.as_mut_ptr(),
+            buf.len(),
+            0,
+            address.as_mut_ptr(),
+            &raw mut *address.len_mut()
+          )
+        }
+      })
+      .await?;
+
+    Ok((&buf[..length], unix_address(Ok(address))?))
+  }
+}
+
+impl DatagramReader for LinuxUnixDatagram<'_> {
+  type Error = LinuxError;
+
+  async fn read<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], LinuxError> {
+    let length = self
+      .registration
+      .io_operation(Interest::Read, |file_descriptor| unsafe {
+        syscall!(Sysno::read, file_descriptor, buf.as_mut_ptr(), buf.len())
+      })
+      .await?;
+
+    Ok(&buf[..length])
+  }
+}
+
+impl DatagramWriter for LinuxUnixDatagram<'_> {
+  type Error = LinuxError;
+
+  async fn write(&mut self, buf: &[u8]) -> Result<(), LinuxError> {
+    self
+      .registration
+      .io_operation(Interest::Write, |file_descriptor| unsafe {
+        syscall!(
+          Sysno::sendto,
+          file_descriptor,
+          buf.as_ptr(),
+          buf.len(),
+          libc::MSG_NOSIGNAL,
+          0,
+          0
+        )
+      })
+      .await?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use syscalls::{Errno, Sysno, syscall};
+
+  use crate::{
+    io::{
+      DatagramReader, DatagramWriter, StreamRead, StreamWrite,
+      net::unix::{UnixDatagram, UnixIo, UnixListener, UnixSocketAddress, UnixStream},
+    },
+    platform::linux::{
+      OwnedFileDescriptor,
+      io::{LinuxIo, join, raises_sigpipe},
+    },
+  };
+
+  #[test]
+  fn address() {
+    let path = UnixSocketAddress::from_pathname(b"/tmp/socket").unwrap();
+    assert_eq!(path.as_pathname(), Some(&b"/tmp/socket"[..]));
+    assert_eq!(path.as_abstract(), None);
+
+    let name = UnixSocketAddress::from_abstract(b"name").unwrap();
+    assert_eq!(name.as_abstract(), Some(&b"name"[..]));
+
+    assert!(UnixSocketAddress::from_pathname(b"").is_none());
+    assert!(UnixSocketAddress::from_pathname(b"a\0b").is_none());
+    assert!(UnixSocketAddress::from_abstract(&[b'a'; 108]).is_none());
+  }
+
+  #[test]
+  fn stream_pair() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut first, mut second) = io.stream_pair().unwrap();
+      assert!(first.local_address().unwrap().is_unnamed());
+
+      first.write(b"hello").await.unwrap();
+      let mut buf = [0; 16];
+      assert_eq!(second.read(&mut buf).await.unwrap(), b"hello");
+
+      let credentials = second.peer_credentials().unwrap();
+      assert_eq!(
+        credentials.process_id as usize,
+        unsafe { syscall!(Sysno::getpid) }.unwrap()
+      );
+      assert_eq!(
+        credentials.user_id as usize,
+        unsafe { syscall!(Sysno::getuid) }.unwrap()
+      );
+    });
+  }
+
+  #[test]
+  fn write_after_peer_dropped() {
+    let io = LinuxIo::instance();
+    let raised = raises_sigpipe(|| {
+      io.block_on(async {
+        let (mut first, second) = io.stream_pair().unwrap();
+        drop(second);
+
+        let error = first.write(b"lost").await.unwrap_err();
+        assert_eq!(error.0, Errno::EPIPE);
+      })
+    });
+    assert!(!raised);
+  }
+
+  #[test]
+  fn abstract_listener() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      // an unnamed address binds to a unique abstract address
+      let mut listener = io.listen(&UnixSocketAddress::unnamed()).await.unwrap();
+      let address = listener.local_address().unwrap();
+      assert!(address.as_abstract().is_some());
+
+      let (server, client) = join(listener.accept(), io.connect(&address)).await;
+      let (mut server, mut client) = (server.unwrap(), client.unwrap());
+
+      assert_eq!(client.peer_address().unwrap(), address);
+      client.write(b"ping").await.unwrap();
+      let mut buf = [0; 16];
+      assert_eq!(server.read(&mut buf).await.unwrap(), b"ping");
+    });
+  }
+
+  #[test]
+  fn datagrams() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let mut first = io
+        .bind_datagram(&UnixSocketAddress::unnamed())
+        .await
+        .unwrap();
+      let mut second = io
+        .bind_datagram(&UnixSocketAddress::unnamed())
+        .await
+        .unwrap();
+
+      first
+        .send_to(b"hello", &second.local_address().unwrap())
+        .await
+        .unwrap();
+      let mut buf = [0; 16];
+      let (datagram, address) = second.recv_from(&mut buf).await.unwrap();
+      assert_eq!(datagram, b"hello");
+      assert_eq!(address, first.local_address().unwrap());
+
+      second.connect(&address).await.unwrap();
+      second.write(b"world").await.unwrap();
+      assert_eq!(first.read(&mut buf).await.unwrap(), b"world");
+    });
+  }
+
+  #[test]
+  fn pass_file_descriptors() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut first, mut second) = io.datagram_pair().unwrap();
+
+      let mut pipe = [0i32; 2];
+      unsafe { syscall!(Sysno::pipe2, pipe.as_mut_ptr(), libc::O_CLOEXEC) }.unwrap();
+      let [read, write] =
+        pipe.map(|file_descriptor| OwnedFileDescriptor::new(file_descriptor as _));
+
+      first
+        .send_with_file_descriptors(b"pipe", &[write.get()])
+        .await
+        .unwrap();
+      drop(write);
+
+      let mut buf = [0; 16];
+      let mut file_descriptors = [const { None }; 4];
+      let (data, received, truncated) = second
+        .recv_with_file_descriptors(&mut buf, &mut file_descriptors)
+        .await
+        .unwrap();
+      assert_eq!(data, b"pipe");
+      assert_eq!(received.len(), 1);
+      assert!(!truncated);
+
+      // the received file descriptor is the write end of the pipe
+      let write = received[0].take().unwrap();
+      unsafe { syscall!(Sysno::write, write.get(), b"passed".as_ptr(), 6) }.unwrap();
+      let length =
+        unsafe { syscall!(Sysno::read, read.get(), buf.as_mut_ptr(), buf.len()) }.unwrap();
+      assert_eq!(&buf[..length], b"passed");
+    });
+  }
+
+  #[test]
+  fn too_many_file_descriptors() {
+    let io = LinuxIo::instance();
+    io.block_on(async {
+      let (mut first, mut second) = io.datagram_pair().unwrap();
+
+      let mut pipe = [0i32; 2];
+      unsafe { syscall!(Sysno::pipe2, pipe.as_mut_ptr(), libc::O_CLOEXEC) }.unwrap();
+      let [read, write] =
+        pipe.map(|file_descriptor| OwnedFileDescriptor::new(file_descriptor as _));
+
+      first
+        .send_with_file_descriptors(b"pipe", &[read.get(), write.get()])
+        .await
+        .unwrap();
+
+      let mut buf = [0; 16];
+      let mut file_descriptors = [const { None }; 1];
+      let (data, received, truncated) = second
+        .recv_with_file_descriptors(&mut buf, &mut file_descriptors)
+        .await
+        .unwrap();
+      assert_eq!(data, b"pipe");
+      assert_eq!(received.len(), 1);
+      assert!(truncated);
+    });
+  }
+}
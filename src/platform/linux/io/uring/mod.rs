@@ -0,0 +1,824 @@
+use core::{
+  cell::SyncUnsafeCell,
+  ffi::CStr,
+  future::poll_fn,
+  hint,
+  mem::MaybeUninit,
+  ptr,
+  sync::atomic::{AtomicBool, AtomicI32, AtomicU8, AtomicU32, Ordering},
+  task::{Context, Poll},
+  time::Duration,
+};
+
+use syscalls::{Errno, Sysno, syscall};
+
+use crate::{
+  alloc::{SliceAllocator, strategy::Unique},
+  io::{Io, StreamRead, StreamWrite},
+  platform::linux::{
+    FileDescriptor, MaybeFileDescriptor, OwnedFileDescriptor,
+    io::{LinuxError, reactor::WakerCell, wake::Wake},
+  },
+};
+
+mod sys;
+mod tcp;
+
+pub use tcp::{UringListener, UringSocket};
+
+use sys::{CompletionQueueEntry, Parameters, SubmissionQueueEntry};
+
+/// How many operations can be in flight on a [UringIo] at once.
+pub const MAX_OPERATIONS: usize = 256;
+
+/// The offset that reads and writes from the current file position.
+pub const CURRENT_POSITION: u64 = u64::MAX;
+
+/// The user data of the poll on the eventfd that wakes the ring.
+const WAKE_TOKEN: u64 = u64::MAX;
+/// The user data of requests whose completions aren't waited on, like cancellations.
+const IGNORED_TOKEN: u64 = u64::MAX - 1;
+
+const UNINITIALIZED: MaybeFileDescriptor = -1;
+const INITIALIZING: MaybeFileDescriptor = -2;
+
+const FREE: u8 = 0;
+const PENDING: u8 = 1;
+const COMPLETE: u8 = 2;
+
+/// A shared memory region mapped from the ring, which is unmapped when dropped.
+struct Mapping {
+  pointer: *mut u8,
+  size: usize,
+}
+
+impl Mapping {
+  fn new(ring: FileDescriptor, size: usize, offset: i64) -> Result<Self, LinuxError> {
+    let pointer = unsafe {
+      syscall!(
+        Sysno::mmap,
+        0,
+        size,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED | libc::MAP_POPULATE,
+        ring,
+        offset
+      )
+    }
+    .map_err(LinuxError)?;
+
+    Ok(Self {
+      pointer: pointer as _,
+      size,
+    })
+  }
+
+  /// Safety: `offset` has to be in the mapping, and aligned for `T`.
+  unsafe fn at<T>(&self, offset: u32) -> *mut T {
+    unsafe { self.pointer.add(offset as usize).cast() }
+  }
+}
+
+impl Drop for Mapping {
+  fn drop(&mut self) {
+    let _ = unsafe { syscall!(Sysno::munmap, self.pointer, self.size) };
+  }
+}
+
+/// The submission and completion queues shared with the kernel.
+struct Queues {
+  submission_ring: Mapping,
+  completion_ring: Mapping,
+  entries: Mapping,
+  features: u32,
+  submission_head: *const AtomicU32,
+  submission_tail: *const AtomicU32,
+  submission_mask: u32,
+  submission_entries: u32,
+  submission_array: *mut u32,
+  completion_head: *const AtomicU32,
+  completion_tail: *const AtomicU32,
+  completion_mask: u32,
+  completions: *const CompletionQueueEntry,
+}
+
+impl Queues {
+  fn new(ring: FileDescriptor, parameters: &Parameters) -> Result<Self, LinuxError> {
+    let submission_ring = Mapping::new(
+      ring,
+      parameters.sq_off.array as usize + parameters.sq_entries as usize * size_of::<u32>(),
+      sys::IORING_OFF_SQ_RING,
+    )?;
+    let completion_ring = Mapping::new(
+      ring,
+      parameters.cq_off.cqes as usize
+        + parameters.cq_entries as usize * size_of::<CompletionQueueEntry>(),
+      sys::IORING_OFF_CQ_RING,
+    )?;
+    let entries = Mapping::new(
+      ring,
+      parameters.sq_entries as usize * size_of::<SubmissionQueueEntry>(),
+      sys::IORING_OFF_SQES,
+    )?;
+
+    // Safety: the kernel gave the offsets of these fields in the mappings
+    unsafe {
+      Ok(Self {
+        features: parameters.features,
+        submission_head: submission_ring.at(parameters.sq_off.head),
+        submission_tail: submission_ring.at(parameters.sq_off.tail),
+        submission_mask: *submission_ring.at::<u32>(parameters.sq_off.ring_mask),
+        submission_entries: *submission_ring.at::<u32>(parameters.sq_off.ring_entries),
+        submission_array: submission_ring.at(parameters.sq_off.array),
+        completion_head: completion_ring.at(parameters.cq_off.head),
+        completion_tail: completion_ring.at(parameters.cq_off.tail),
+        completion_mask: *completion_ring.at::<u32>(parameters.cq_off.ring_mask),
+        completions: completion_ring.at(parameters.cq_off.cqes),
+        submission_ring,
+        completion_ring,
+        entries,
+      })
+    }
+  }
+
+  fn submission_head(&self) -> &AtomicU32 {
+    // Safety: the pointer is in the submission ring, which lives as long as self
+    unsafe { &*self.submission_head }
+  }
+
+  fn submission_tail(&self) -> &AtomicU32 {
+    // Safety: the pointer is in the submission ring, which lives as long as self
+    unsafe { &*self.submission_tail }
+  }
+
+  fn completion_head(&self) -> &AtomicU32 {
+    // Safety: the pointer is in the completion ring, which lives as long as self
+    unsafe { &*self.completion_head }
+  }
+
+  fn completion_tail(&self) -> &AtomicU32 {
+    // Safety: the pointer is in the completion ring, which lives as long as self
+    unsafe { &*self.completion_tail }
+  }
+
+  /// How many entries have been queued, but not consumed by the kernel yet.
+  fn unsubmitted(&self) -> u32 {
+    let tail = self.submission_tail().load(Ordering::Acquire);
+    tail.wrapping_sub(self.submission_head().load(Ordering::Acquire))
+  }
+
+  /// Queues `entry`, returning false if the submission queue is full.
+  ///
+  /// Safety: only one thread can push at a time.
+  unsafe fn push(&self, entry: SubmissionQueueEntry) -> bool {
+    let tail = self.submission_tail().load(Ordering::Relaxed);
+    if tail.wrapping_sub(self.submission_head().load(Ordering::Acquire)) >= self.submission_entries
+    {
+      return false;
+    }
+
+    let index = tail & self.submission_mask;
+    // Safety: index is masked to the size of the queue, and the kernel doesn't read entries past
+    // the tail
+    unsafe {
+      self
+        .entries
+        .pointer
+        .cast::<SubmissionQueueEntry>()
+        .add(index as usize)
+        .write(entry);
+      self.submission_array.add(index as usize).write(index);
+    }
+    self
+      .submission_tail()
+      .store(tail.wrapping_add(1), Ordering::Release);
+    true
+  }
+}
+
+/// The state of an operation submitted to the ring.
+struct OperationSlot {
+  state: AtomicU8,
+  result: AtomicI32,
+  waker: WakerCell,
+}
+
+impl OperationSlot {
+  const fn new() -> Self {
+    Self {
+      state: AtomicU8::new(FREE),
+      result: AtomicI32::new(0),
+      waker: WakerCell::new(),
+    }
+  }
+
+  fn complete(&self, result: i32) {
+    self.result.store(result, Ordering::Relaxed);
+    self.state.store(COMPLETE, Ordering::Release);
+    self.waker.wake();
+  }
+
+  fn poll_complete(&self, cx: &mut Context<'_>) -> Poll<i32> {
+    if self.state.load(Ordering::Acquire) == COMPLETE {
+      return Poll::Ready(self.result.load(Ordering::Relaxed));
+    }
+
+    self.waker.register(cx.waker());
+
+    // the operation could have completed before the waker was stored
+    if self.state.load(Ordering::Acquire) == COMPLETE {
+      return Poll::Ready(self.result.load(Ordering::Relaxed));
+    }
+
+    Poll::Pending
+  }
+}
+
+/// Linux's completion-based [Io], which submits operations to an io_uring.
+///
+/// This is the alternative to the epoll backend in [LinuxIo](super::LinuxIo), picked by
+/// constructing one or the other. Since the kernel writes to buffers after an operation was
+/// submitted, reads and writes go through buffers owned by the operation, allocated from `A`.
+///
+/// The ring is set up the first time it's needed. Like [LinuxIo](super::LinuxIo), completions are
+/// only handled while driving it with [UringIo::turn] or [UringIo::block_on].
+pub struct UringIo<'a, A: ?Sized> {
+  allocator: &'a A,
+  buffer_size: usize,
+  ring: AtomicI32,
+  wake: Wake,
+  queues: SyncUnsafeCell<MaybeUninit<Queues>>,
+  submission_lock: AtomicBool,
+  completion_lock: AtomicBool,
+  slots: [OperationSlot; MAX_OPERATIONS],
+}
+
+// Safety: the queues are written once before the ring is published, and then only accessed while
+// holding their lock
+unsafe impl<A: Sync + ?Sized> Sync for UringIo<'_, A> {}
+unsafe impl<A: Sync + ?Sized> Send for UringIo<'_, A> {}
+
+fn with_lock<R>(lock: &AtomicBool, f: impl FnOnce() -> R) -> R {
+  while lock
+    .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+    .is_err()
+  {
+    hint::spin_loop();
+  }
+  let result = f();
+  lock.store(false, Ordering::Release);
+  result
+}
+
+impl<'a, A: ?Sized> UringIo<'a, A> {
+  /// Creates an io_uring backend whose streams buffer `buffer_size` bytes per operation.
+  pub const fn new(allocator: &'a A, buffer_size: usize) -> Self {
+    Self {
+      allocator,
+      buffer_size,
+      ring: AtomicI32::new(UNINITIALIZED),
+      wake: Wake::new(),
+      queues: SyncUnsafeCell::new(MaybeUninit::uninit()),
+      submission_lock: AtomicBool::new(false),
+      completion_lock: AtomicBool::new(false),
+      slots: [const { OperationSlot::new() }; MAX_OPERATIONS],
+    }
+  }
+
+  pub fn allocator(&self) -> &'a A {
+    self.allocator
+  }
+
+  /// Returns the ring and its queues, setting it up if this is the first time it's needed.
+  fn ring(&self) -> Result<(FileDescriptor, &Queues), LinuxError> {
+    loop {
+      match self.ring.compare_exchange(
+        UNINITIALIZED,
+        INITIALIZING,
+        Ordering::Acquire,
+        Ordering::Acquire,
+      ) {
+        Ok(_) => break,
+        Err(INITIALIZING) => hint::spin_loop(),
+        // Safety: the queues are initialized before the ring is published
+        Err(ring) => return Ok((ring as _, unsafe { (*self.queues.get()).assume_init_ref() })),
+      }
+    }
+
+    match Self::setup() {
+      Ok((ring, wake, queues)) => {
+        // Safety: only the initializing thread can write the queues
+        let queues = unsafe { (*self.queues.get()).write(queues) };
+        self.wake.set_eventfd(wake);
+        self.ring.store(ring as _, Ordering::Release);
+        self.arm_wake(ring, queues);
+        Ok((ring, queues))
+      }
+      Err(error) => {
+        self.ring.store(UNINITIALIZED, Ordering::Release);
+        Err(error)
+      }
+    }
+  }
+
+  fn setup() -> Result<(FileDescriptor, FileDescriptor, Queues), LinuxError> {
+    let mut parameters = Parameters::default();
+    let ring = OwnedFileDescriptor::new(
+      unsafe { syscall!(Sysno::io_uring_setup, MAX_OPERATIONS, &raw mut parameters) }
+        .map_err(LinuxError)? as _,
+    );
+    let queues = Queues::new(ring.get(), &parameters)?;
+    let wake = OwnedFileDescriptor::new(
+      unsafe { syscall!(Sysno::eventfd2, 0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) }
+        .map_err(LinuxError)? as _,
+    );
+
+    Ok((ring.into_raw(), wake.into_raw(), queues))
+  }
+
+  /// Polls the wake eventfd, so signalling it completes a wait on the ring.
+  fn arm_wake(&self, ring: FileDescriptor, queues: &Queues) {
+    self.push(
+      ring,
+      queues,
+      SubmissionQueueEntry {
+        opcode: sys::IORING_OP_POLL_ADD,
+        fd: self.wake.eventfd(),
+        op_flags: libc::POLLIN as u32,
+        user_data: WAKE_TOKEN,
+        ..Default::default()
+      },
+    );
+  }
+
+  /// Queues `entry` and submits it to the kernel right away, so anything it points to only has to
+  /// outlive this call, except for buffers that are written to on completion.
+  fn push(&self, ring: FileDescriptor, queues: &Queues, entry: SubmissionQueueEntry) {
+    with_lock(&self.submission_lock, || {
+      // Safety: the submission lock is held
+      while !unsafe { queues.push(entry) } {
+        Self::enter(ring, queues.unsubmitted(), 0, 0, ptr::null(), 0);
+      }
+    });
+    Self::enter(ring, queues.unsubmitted(), 0, 0, ptr::null(), 0);
+  }
+
+  /// Submits queued entries, and waits for `min_complete` completions. Entries that couldn't be
+  /// submitted stay queued until the next call.
+  fn enter(
+    ring: FileDescriptor,
+    to_submit: u32,
+    min_complete: u32,
+    flags: u32,
+    argument: *const sys::GetEventsArgument,
+    argument_size: usize,
+  ) {
+    // failing is fine, errors like EINTR, EBUSY or ETIME leave the queues as they were
+    let _ = unsafe {
+      syscall!(
+        Sysno::io_uring_enter,
+        ring,
+        to_submit,
+        min_complete,
+        flags,
+        argument,
+        argument_size
+      )
+    };
+  }
+
+  /// Wakes a thread blocked in [UringIo::turn].
+  pub fn wake(&self) {
+    self.wake.signal();
+  }
+
+  /// Waits until an operation completes, or `timeout` passes, and wakes the tasks waiting on it.
+  /// Returns how many completions were handled.
+  pub fn turn(&self, timeout: Option<Duration>) -> Result<usize, LinuxError> {
+    let (ring, queues) = self.ring()?;
+
+    match timeout {
+      None => Self::enter(
+        ring,
+        queues.unsubmitted(),
+        1,
+        sys::IORING_ENTER_GETEVENTS,
+        ptr::null(),
+        0,
+      ),
+      Some(timeout) if queues.features & sys::IORING_FEAT_EXT_ARG != 0 => {
+        let timeout = sys::KernelTimespec {
+          tv_sec: timeout.as_secs() as _,
+          tv_nsec: timeout.subsec_nanos() as _,
+        };
+        let argument = sys::GetEventsArgument {
+          sigmask: 0,
+          sigmask_sz: 0,
+          min_wait_usec: 0,
+          ts: &raw const timeout as u64,
+        };
+        Self::enter(
+          ring,
+          queues.unsubmitted(),
+          1,
+          sys::IORING_ENTER_GETEVENTS | sys::IORING_ENTER_EXT_ARG,
+          &raw const argument,
+          size_of::<sys::GetEventsArgument>(),
+        );
+      }
+      // without extended arguments, the kernel can't wait with a timeout
+      Some(_) => Self::enter(ring, queues.unsubmitted(), 0, 0, ptr::null(), 0),
+    }
+
+    Ok(self.reap(ring, queues))
+  }
+
+  fn reap(&self, ring: FileDescriptor, queues: &Queues) -> usize {
+    let mut rearm = false;
+    let count = with_lock(&self.completion_lock, || {
+      let mut head = queues.completion_head().load(Ordering::Relaxed);
+      let tail = queues.completion_tail().load(Ordering::Acquire);
+      let count = tail.wrapping_sub(head) as usize;
+
+      while head != tail {
+        // Safety: the entries between the head and tail were written by the kernel
+        let completion = unsafe {
+          queues
+            .completions
+            .add((head & queues.completion_mask) as usize)
+            .read()
+        };
+        head = head.wrapping_add(1);
+
+        match completion.user_data {
+          WAKE_TOKEN => rearm = true,
+          IGNORED_TOKEN => {}
+          index => self.slots[index as usize].complete(completion.res),
+        }
+      }
+
+      queues.completion_head().store(head, Ordering::Release);
+      count
+    });
+
+    if rearm {
+      self.wake.drain();
+      self.arm_wake(ring, queues);
+    }
+
+    count
+  }
+
+  /// Runs `future` to completion on this thread, driving the ring while it's pending.
+  pub fn block_on<F: Future>(&'static self, future: F) -> F::Output {
+    // the wake eventfd has to exist before a waker can signal it
+    self.ring().expect("failed to set up io_uring");
+    self.wake.block_on(
+      || {
+        self.turn(None).expect("failed to wait for io completions");
+      },
+      future,
+    )
+  }
+
+  /// Submits `entry`, and waits for it to complete. Returns the non-negative result of the
+  /// operation.
+  ///
+  /// If the future is dropped before the operation completes, the operation is cancelled, and the
+  /// drop blocks until the kernel is done with it. Buffers written to on completion must be owned
+  /// by the caller of this function, so they're leaked along with a leaked future.
+  async fn operation(&self, mut entry: SubmissionQueueEntry) -> Result<usize, LinuxError> {
+    let (ring, queues) = self.ring()?;
+    let Some(index) = self.slots.iter().position(|slot| {
+      slot
+        .state
+        .compare_exchange(FREE, PENDING, Ordering::Acquire, Ordering::Relaxed)
+        .is_ok()
+    }) else {
+      return Err(LinuxError(Errno::EBUSY));
+    };
+
+    entry.user_data = index as u64;
+    let operation = Operation { io: self, index };
+    self.push(ring, queues, entry);
+
+    let result = poll_fn(|cx| self.slots[operation.index].poll_complete(cx)).await;
+    match result {
+      0.. => Ok(result as usize),
+      _ => Err(LinuxError(Errno::new(-result))),
+    }
+  }
+
+  async fn read_into(
+    &self,
+    file_descriptor: FileDescriptor,
+    buffer: *mut u8,
+    length: usize,
+    offset: u64,
+  ) -> Result<usize, LinuxError> {
+    self
+      .operation(SubmissionQueueEntry {
+        opcode: sys::IORING_OP_READ,
+        fd: file_descriptor as _,
+        off: offset,
+        addr: buffer as u64,
+        len: length.try_into().unwrap_or(u32::MAX),
+        ..Default::default()
+      })
+      .await
+  }
+
+  async fn write_from(
+    &self,
+    file_descriptor: FileDescriptor,
+    data: *const u8,
+    length: usize,
+    offset: u64,
+  ) -> Result<usize, LinuxError> {
+    self
+      .operation(SubmissionQueueEntry {
+        opcode: sys::IORING_OP_WRITE,
+        fd: file_descriptor as _,
+        off: offset,
+        addr: data as u64,
+        len: length.try_into().unwrap_or(u32::MAX),
+        ..Default::default()
+      })
+      .await
+  }
+
+  /// Opens the file at `path` as a stream, with the `open` flags and mode.
+  pub async fn open(
+    &self,
+    path: &CStr,
+    flags: i32,
+    mode: u32,
+  ) -> Result<UringStream<'_, 'a, A>, LinuxError> {
+    let file_descriptor = self
+      .operation(SubmissionQueueEntry {
+        opcode: sys::IORING_OP_OPENAT,
+        fd: libc::AT_FDCWD,
+        addr: path.as_ptr() as u64,
+        len: mode,
+        op_flags: (flags | libc::O_CLOEXEC) as u32,
+        ..Default::default()
+      })
+      .await?;
+
+    Ok(UringStream::new(
+      self,
+      OwnedFileDescriptor::new(file_descriptor as _),
+    ))
+  }
+}
+
+impl<A: ?Sized> Io for UringIo<'_, A> {}
+
+impl<A: ?Sized> Drop for UringIo<'_, A> {
+  fn drop(&mut self) {
+    let ring = *self.ring.get_mut();
+    if ring < 0 {
+      return;
+    }
+
+    // nothing can be in flight, since every operation borrows the ring
+    // Safety: the queues were initialized along with the ring
+    unsafe { self.queues.get_mut().assume_init_drop() };
+    drop(OwnedFileDescriptor::new(ring as _));
+    drop(OwnedFileDescriptor::new(self.wake.eventfd() as _));
+  }
+}
+
+/// An operation in flight, which is cancelled when dropped.
+struct Operation<'r, 'a, A: ?Sized> {
+  io: &'r UringIo<'a, A>,
+  index: usize,
+}
+
+impl<A: ?Sized> Drop for Operation<'_, '_, A> {
+  fn drop(&mut self) {
+    let slot = &self.io.slots[self.index];
+    if slot.state.load(Ordering::Acquire) != COMPLETE {
+      // the ring exists, since the operation was submitted to it
+      if let Ok((ring, queues)) = self.io.ring() {
+        self.io.push(
+          ring,
+          queues,
+          SubmissionQueueEntry {
+            opcode: sys::IORING_OP_ASYNC_CANCEL,
+            addr: self.index as u64,
+            user_data: IGNORED_TOKEN,
+            ..Default::default()
+          },
+        );
+      }
+
+      // the kernel could still write to the operation's buffers until it completes, which the
+      // cancellation guarantees it does, so this blocks rather than polling
+      while slot.state.load(Ordering::Acquire) != COMPLETE {
+        let _ = self.io.turn(None);
+      }
+    }
+
+    slot.waker.with(Option::take);
+    slot.state.store(FREE, Ordering::Release);
+  }
+}
+
+/// A file descriptor whose reads and writes are submitted to a [UringIo], like a file or a
+/// connected socket.
+///
+/// As a [StreamRead] and [StreamWrite], data goes through a buffer owned by the stream, which is
+/// allocated again if an operation using it was dropped.
+pub struct UringStream<'s, 'a, A: ?Sized> {
+  io: &'s UringIo<'a, A>,
+  file_descriptor: OwnedFileDescriptor,
+  buffer: Option<Unique<'a, [u8]>>,
+}
+
+impl<'s, 'a, A: ?Sized> UringStream<'s, 'a, A> {
+  pub fn new(io: &'s UringIo<'a, A>, file_descriptor: OwnedFileDescriptor) -> Self {
+    Self {
+      io,
+      file_descriptor,
+      buffer: None,
+    }
+  }
+
+  pub fn file_descriptor(&self) -> FileDescriptor {
+    self.file_descriptor.get()
+  }
+
+  /// Reads into `buffer` at `offset`, or the current position if it's [CURRENT_POSITION].
+  /// Returns the buffer back along with how many bytes were read.
+  pub async fn read_at<'b>(
+    &self,
+    mut buffer: Unique<'b, [u8]>,
+    offset: u64,
+  ) -> (Result<usize, LinuxError>, Unique<'b, [u8]>) {
+    let length = buffer.len();
+    let result = self
+      .io
+      .read_into(self.file_descriptor(), buffer.as_mut_ptr(), length, offset)
+      .await;
+    (result, buffer)
+  }
+
+  /// Writes `buffer` at `offset`, or the current position if it's [CURRENT_POSITION]. Returns
+  /// the buffer back along with how many bytes were written.
+  pub async fn write_at<'b>(
+    &self,
+    buffer: Unique<'b, [u8]>,
+    offset: u64,
+  ) -> (Result<usize, LinuxError>, Unique<'b, [u8]>) {
+    let result = self
+      .io
+      .write_from(
+        self.file_descriptor(),
+        buffer.as_ptr(),
+        buffer.len(),
+        offset,
+      )
+      .await;
+    (result, buffer)
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> UringStream<'_, 'a, A> {
+  async fn take_buffer(&mut self) -> Result<Unique<'a, [u8]>, LinuxError> {
+    match self.buffer.take() {
+      Some(buffer) => Ok(buffer),
+      None => self
+        .io
+        .allocator
+        .from_zeros::<crate::alloc::strategy::UniqueStrategy>(self.io.buffer_size)
+        .await
+        .map_err(|_| LinuxError(Errno::ENOMEM)),
+    }
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> StreamRead for UringStream<'_, 'a, A> {
+  type Error = LinuxError;
+
+  async fn read<'b>(&mut self, buf: &'b mut [u8]) -> Result<&'b [u8], LinuxError> {
+    let mut buffer = self.take_buffer().await?;
+    let length = buf.len().min(buffer.len());
+    let result = self
+      .io
+      .read_into(
+        self.file_descriptor(),
+        buffer.as_mut_ptr(),
+        length,
+        CURRENT_POSITION,
+      )
+      .await;
+
+    let read = result.map(|read| {
+      buf[..read].copy_from_slice(&buffer[..read]);
+      &buf[..read]
+    });
+    self.buffer = Some(buffer);
+    read
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> UringStream<'_, 'a, A> {
+  /// Copies as much of `data` as fits into the stream's buffer, and submits `entry` to write it.
+  async fn write_buffered<'b>(
+    &mut self,
+    data: &'b [u8],
+    entry: SubmissionQueueEntry,
+  ) -> Result<(usize, &'b [u8]), LinuxError> {
+    let mut buffer = self.take_buffer().await?;
+    let length = data.len().min(buffer.len());
+    buffer[..length].copy_from_slice(&data[..length]);
+    let result = self
+      .io
+      .operation(SubmissionQueueEntry {
+        fd: self.file_descriptor() as _,
+        addr: buffer.as_ptr() as u64,
+        len: length.try_into().unwrap_or(u32::MAX),
+        ..entry
+      })
+      .await;
+
+    self.buffer = Some(buffer);
+    let written = result?;
+    Ok((written, &data[written..]))
+  }
+
+  /// Writes to a socket, like [write](StreamWrite::write), except that the write fails with EPIPE
+  /// instead of raising SIGPIPE if the peer is gone.
+  pub(super) async fn send<'b>(&mut self, data: &'b [u8]) -> Result<(usize, &'b [u8]), LinuxError> {
+    self
+      .write_buffered(
+        data,
+        SubmissionQueueEntry {
+          opcode: sys::IORING_OP_SEND,
+          op_flags: libc::MSG_NOSIGNAL as u32,
+          ..Default::default()
+        },
+      )
+      .await
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> StreamWrite for UringStream<'_, 'a, A> {
+  type Error = LinuxError;
+
+  async fn write<'b>(&mut self, data: &'b [u8]) -> Result<(usize, &'b [u8]), LinuxError> {
+    self
+      .write_buffered(
+        data,
+        SubmissionQueueEntry {
+          opcode: sys::IORING_OP_WRITE,
+          off: CURRENT_POSITION,
+          ..Default::default()
+        },
+      )
+      .await
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::{
+    alloc::{ForeignAllocator, SliceAllocator, StdAlloc, strategy::UniqueStrategy},
+    io::{StreamRead, StreamWrite},
+    platform::linux::io::uring::UringIo,
+  };
+
+  static ALLOCATOR: ForeignAllocator<StdAlloc> = ForeignAllocator::new(StdAlloc);
+  static IO: UringIo<ForeignAllocator<StdAlloc>> = UringIo::new(&ALLOCATOR, 64);
+
+  #[test]
+  fn file() {
+    IO.block_on(async {
+      let mut file = IO
+        .open(c"/tmp", libc::O_TMPFILE | libc::O_RDWR, 0o600)
+        .await
+        .unwrap();
+
+      let (written, _) = file.write(b"hello world").await.unwrap();
+      assert_eq!(written, 11);
+
+      let buffer = SliceAllocator::<[u8]>::from_bytes::<UniqueStrategy>(&ALLOCATOR, b"HELLO")
+        .await
+        .unwrap();
+      let (written, _) = file.write_at(buffer, 0).await;
+      assert_eq!(written.unwrap(), 5);
+
+      let buffer = SliceAllocator::<[u8]>::from_zeros::<UniqueStrategy>(&ALLOCATOR, 11)
+        .await
+        .unwrap();
+      let (read, buffer) = file.read_at(buffer, 0).await;
+      assert_eq!(&buffer[..read.unwrap()], b"HELLO world");
+
+      // the stream's position is still at the end
+      let mut buf = [0; 4];
+      assert_eq!(file.read(&mut buf).await.unwrap(), b"");
+    });
+  }
+}
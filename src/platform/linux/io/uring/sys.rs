@@ -0,0 +1,108 @@
+//! The io_uring kernel interface, from `linux/io_uring.h`.
+
+pub const IORING_OFF_SQ_RING: i64 = 0;
+pub const IORING_OFF_CQ_RING: i64 = 0x8000000;
+pub const IORING_OFF_SQES: i64 = 0x10000000;
+
+pub const IORING_ENTER_GETEVENTS: u32 = 1 << 0;
+pub const IORING_ENTER_EXT_ARG: u32 = 1 << 3;
+
+pub const IORING_FEAT_EXT_ARG: u32 = 1 << 8;
+
+pub const IORING_OP_POLL_ADD: u8 = 6;
+pub const IORING_OP_ACCEPT: u8 = 13;
+pub const IORING_OP_ASYNC_CANCEL: u8 = 14;
+pub const IORING_OP_CONNECT: u8 = 16;
+pub const IORING_OP_OPENAT: u8 = 18;
+pub const IORING_OP_READ: u8 = 22;
+pub const IORING_OP_WRITE: u8 = 23;
+pub const IORING_OP_SEND: u8 = 26;
+
+#[repr(C)]
+#[derive(Default)]
+pub struct SubmissionQueueOffsets {
+  pub head: u32,
+  pub tail: u32,
+  pub ring_mask: u32,
+  pub ring_entries: u32,
+  pub flags: u32,
+  pub dropped: u32,
+  pub array: u32,
+  pub resv1: u32,
+  pub user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub struct CompletionQueueOffsets {
+  pub head: u32,
+  pub tail: u32,
+  pub ring_mask: u32,
+  pub ring_entries: u32,
+  pub overflow: u32,
+  pub cqes: u32,
+  pub flags: u32,
+  pub resv1: u32,
+  pub user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub struct Parameters {
+  pub sq_entries: u32,
+  pub cq_entries: u32,
+  pub flags: u32,
+  pub sq_thread_cpu: u32,
+  pub sq_thread_idle: u32,
+  pub features: u32,
+  pub wq_fd: u32,
+  pub resv: [u32; 3],
+  pub sq_off: SubmissionQueueOffsets,
+  pub cq_off: CompletionQueueOffsets,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct SubmissionQueueEntry {
+  pub opcode: u8,
+  pub flags: u8,
+  pub ioprio: u16,
+  pub fd: i32,
+  /// The file offset, or the second address of an operation.
+  pub off: u64,
+  pub addr: u64,
+  pub len: u32,
+  /// The flags of the operation, like `rw_flags`, `accept_flags` or `open_flags`.
+  pub op_flags: u32,
+  pub user_data: u64,
+  pub buf_index: u16,
+  pub personality: u16,
+  pub file_index: u32,
+  pub addr3: u64,
+  pub pad: u64,
+}
+
+#[repr(C)]
+pub struct CompletionQueueEntry {
+  pub user_data: u64,
+  pub res: i32,
+  pub flags: u32,
+}
+
+#[repr(C)]
+pub struct GetEventsArgument {
+  pub sigmask: u64,
+  pub sigmask_sz: u32,
+  pub min_wait_usec: u32,
+  pub ts: u64,
+}
+
+#[repr(C)]
+pub struct KernelTimespec {
+  pub tv_sec: i64,
+  pub tv_nsec: i64,
+}
+
+const _: () = assert!(size_of::<Parameters>() == 120);
+const _: () = assert!(size_of::<SubmissionQueueEntry>() == 64);
+const _: () = assert!(size_of::<CompletionQueueEntry>() == 16);
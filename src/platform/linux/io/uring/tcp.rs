@@ -0,0 +1,244 @@
+use core::net::SocketAddr;
+
+use crate::{
+  alloc::SliceAllocator,
+  io::{
+    StreamRead, StreamWrite,
+    net::tcp::{
+      Shutdown, TcpClient, TcpListener, TcpServer, TcpSocket, TcpSocketNagle, TcpSocketShutdown,
+    },
+  },
+  platform::linux::{
+    OwnedFileDescriptor,
+    io::{
+      LinuxError,
+      net::{self, RawSocketAddress},
+      uring::{UringIo, UringStream, sys},
+    },
+  },
+};
+
+/// How many connections can wait to be accepted by a [UringListener].
+const BACKLOG: i32 = 128;
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> TcpClient for UringIo<'a, A> {
+  type Error = LinuxError;
+
+  async fn open_connection<'s>(
+    &'s self,
+    address: SocketAddr,
+  ) -> Result<UringSocket<'s, 'a, A>, LinuxError> {
+    // the ring waits for sockets itself, so they're blocking
+    let socket = OwnedFileDescriptor::new(
+      net::socket(
+        net::address_family(&address),
+        libc::SOCK_STREAM,
+        libc::IPPROTO_TCP,
+      )
+      .map_err(LinuxError)?,
+    );
+
+    let raw_address = RawSocketAddress::from(address);
+    self
+      .operation(sys::SubmissionQueueEntry {
+        opcode: sys::IORING_OP_CONNECT,
+        fd: socket.get() as _,
+        addr: raw_address.as_ptr() as u64,
+        off: raw_address.len() as u64,
+        ..Default::default()
+      })
+      .await?;
+
+    let local_address = net::local_address(socket.get()).map_err(LinuxError)?;
+    Ok(UringSocket {
+      stream: UringStream::new(self, socket),
+      local_address,
+      peer_address: address,
+    })
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> TcpServer for UringIo<'a, A> {
+  type Error = LinuxError;
+
+  async fn listen(&self, address: SocketAddr) -> Result<UringListener<'_, 'a, A>, LinuxError> {
+    let socket = OwnedFileDescriptor::new(
+      net::socket(
+        net::address_family(&address),
+        libc::SOCK_STREAM,
+        libc::IPPROTO_TCP,
+      )
+      .map_err(LinuxError)?,
+    );
+
+    net::set_option(socket.get(), libc::SOL_SOCKET, libc::SO_REUSEADDR, 1i32)
+      .map_err(LinuxError)?;
+    net::bind(socket.get(), address).map_err(LinuxError)?;
+    net::listen(socket.get(), BACKLOG).map_err(LinuxError)?;
+
+    Ok(UringListener {
+      io: self,
+      local_address: net::local_address(socket.get()).map_err(LinuxError)?,
+      file_descriptor: socket,
+    })
+  }
+}
+
+/// A listening TCP socket, which accepts connections through a [UringIo].
+pub struct UringListener<'s, 'a, A: ?Sized> {
+  io: &'s UringIo<'a, A>,
+  file_descriptor: OwnedFileDescriptor,
+  local_address: SocketAddr,
+}
+
+impl<A: ?Sized> UringListener<'_, '_, A> {
+  pub fn local_address(&self) -> SocketAddr {
+    self.local_address
+  }
+}
+
+impl<'s, 'a, A: SliceAllocator<'a, [u8]> + ?Sized> TcpListener for UringListener<'s, 'a, A> {
+  type Error = LinuxError;
+
+  async fn accept(&mut self) -> Result<UringSocket<'s, 'a, A>, LinuxError> {
+    let socket = self
+      .io
+      .operation(sys::SubmissionQueueEntry {
+        opcode: sys::IORING_OP_ACCEPT,
+        fd: self.file_descriptor.get() as _,
+        op_flags: libc::SOCK_CLOEXEC as u32,
+        ..Default::default()
+      })
+      .await?;
+    let socket = OwnedFileDescriptor::new(socket as _);
+
+    Ok(UringSocket {
+      local_address: net::local_address(socket.get()).map_err(LinuxError)?,
+      peer_address: net::peer_address(socket.get()).map_err(LinuxError)?,
+      stream: UringStream::new(self.io, socket),
+    })
+  }
+}
+
+/// A connected TCP socket, read and written through a [UringIo].
+pub struct UringSocket<'s, 'a, A: ?Sized> {
+  stream: UringStream<'s, 'a, A>,
+  local_address: SocketAddr,
+  peer_address: SocketAddr,
+}
+
+impl<A: ?Sized> TcpSocket for UringSocket<'_, '_, A> {
+  fn local_address(&self) -> SocketAddr {
+    self.local_address
+  }
+
+  fn peer_address(&self) -> SocketAddr {
+    self.peer_address
+  }
+}
+
+impl<A: ?Sized> TcpSocketNagle for UringSocket<'_, '_, A> {
+  type Error = LinuxError;
+
+  fn set_nagle_algorithm(&self, value: bool) -> Result<(), LinuxError> {
+    net::set_nagle_algorithm(self.stream.file_descriptor(), value).map_err(LinuxError)
+  }
+}
+
+impl<A: ?Sized> TcpSocketShutdown for UringSocket<'_, '_, A> {
+  type Error = LinuxError;
+
+  fn shutdown(&self, how: Shutdown) -> Result<(), LinuxError> {
+    net::shutdown(self.stream.file_descriptor(), how).map_err(LinuxError)
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> StreamRead for UringSocket<'_, 'a, A> {
+  type Error = LinuxError;
+
+  async fn read<'b>(&mut self, buf: &'b mut [u8]) -> Result<&'b [u8], LinuxError> {
+    self.stream.read(buf).await
+  }
+}
+
+impl<'a, A: SliceAllocator<'a, [u8]> + ?Sized> StreamWrite for UringSocket<'_, 'a, A> {
+  type Error = LinuxError;
+
+  async fn write<'b>(&mut self, data: &'b [u8]) -> Result<(usize, &'b [u8]), LinuxError> {
+    self.stream.send(data).await
+  }
+
+  /// Shuts down writing, so the peer reads the end of the stream.
+  async fn close(&mut self) -> Result<(), LinuxError> {
+    net::shutdown(self.stream.file_descriptor(), Shutdown::Write).map_err(LinuxError)
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use core::net::{Ipv4Addr, SocketAddr};
+
+  use syscalls::Errno;
+
+  use crate::{
+    alloc::{ForeignAllocator, StdAlloc},
+    io::{
+      StreamRead, StreamWrite,
+      net::tcp::{TcpClient, TcpListener, TcpServer, TcpSocket},
+    },
+    platform::linux::io::{
+      join, raises_sigpipe,
+      uring::{UringIo, UringSocket},
+    },
+  };
+
+  static ALLOCATOR: ForeignAllocator<StdAlloc> = ForeignAllocator::new(StdAlloc);
+  static IO: UringIo<ForeignAllocator<StdAlloc>> = UringIo::new(&ALLOCATOR, 64);
+
+  type Socket = UringSocket<'static, 'static, ForeignAllocator<StdAlloc>>;
+
+  async fn connect() -> (Socket, Socket) {
+    let mut listener = IO
+      .listen(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+      .await
+      .unwrap();
+    let address = listener.local_address();
+
+    let (server, client) = join(listener.accept(), IO.open_connection(address)).await;
+    (server.unwrap(), client.unwrap())
+  }
+
+  #[test]
+  fn loopback() {
+    IO.block_on(async {
+      let (mut server, mut client) = connect().await;
+
+      assert_eq!(server.peer_address(), client.local_address());
+      assert_eq!(client.peer_address(), server.local_address());
+
+      assert_eq!(client.write(b"ping").await.unwrap().0, 4);
+      let mut buf = [0; 16];
+      assert_eq!(server.read(&mut buf).await.unwrap(), b"ping");
+    });
+  }
+
+  #[test]
+  fn write_after_peer_dropped() {
+    let raised = raises_sigpipe(|| {
+      IO.block_on(async {
+        let (mut server, client) = connect().await;
+        drop(client);
+
+        // the first writes can succeed before the peer's reset arrives
+        let error = loop {
+          if let Err(error) = server.write(b"lost").await {
+            break error;
+          }
+        };
+        assert_eq!(error.0, Errno::EPIPE);
+      })
+    });
+    assert!(!raised);
+  }
+}
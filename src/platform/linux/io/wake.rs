@@ -0,0 +1,132 @@
+use core::{
+  pin::pin,
+  sync::atomic::{AtomicBool, AtomicI32, AtomicU32, Ordering},
+  task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use syscalls::{Sysno, syscall};
+
+use crate::platform::linux::{FileDescriptor, MaybeFileDescriptor};
+
+/// Wakes the threads running futures with [Wake::block_on].
+///
+/// Only one thread waits for io events at a time, and the others sleep until they're woken, or the
+/// waiting thread stops waiting and one of them has to take over.
+pub(super) struct Wake {
+  /// The eventfd that interrupts the thread waiting for io events.
+  eventfd: AtomicI32,
+  /// Incremented by every wake, and slept on by threads that aren't waiting for io events.
+  generation: AtomicU32,
+  turning: AtomicBool,
+}
+
+impl Wake {
+  pub(super) const fn new() -> Self {
+    Self {
+      eventfd: AtomicI32::new(-1),
+      generation: AtomicU32::new(0),
+      turning: AtomicBool::new(false),
+    }
+  }
+
+  /// Returns the eventfd, or a negative value if it hasn't been created yet.
+  pub(super) fn eventfd(&self) -> MaybeFileDescriptor {
+    self.eventfd.load(Ordering::Acquire)
+  }
+
+  pub(super) fn set_eventfd(&self, eventfd: FileDescriptor) {
+    self.eventfd.store(eventfd as _, Ordering::Release);
+  }
+
+  /// Wakes every sleeping thread, and the thread waiting for io events.
+  pub(super) fn signal(&self) {
+    self.wake_sleeping();
+
+    let eventfd = self.eventfd();
+    if eventfd < 0 {
+      // nothing can be waiting on an eventfd that doesn't exist yet
+      return;
+    }
+
+    let value = 1u64;
+    // failing means the counter is full, which wakes the waiting thread anyway
+    let _ = unsafe { syscall!(Sysno::write, eventfd, &raw const value, size_of::<u64>()) };
+  }
+
+  fn wake_sleeping(&self) {
+    self.generation.fetch_add(1, Ordering::Release);
+    let _ = unsafe {
+      syscall!(
+        Sysno::futex,
+        &raw const self.generation,
+        libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+        i32::MAX,
+        0,
+        0,
+        0
+      )
+    };
+  }
+
+  /// Resets the eventfd once the waiting thread has seen it.
+  pub(super) fn drain(&self) {
+    let mut value = 0u64;
+    let _ = unsafe {
+      syscall!(
+        Sysno::read,
+        self.eventfd(),
+        &raw mut value,
+        size_of::<u64>()
+      )
+    };
+  }
+
+  /// Polls `future` until it's ready. While it's pending, this thread either calls `turn` to wait
+  /// for io events, which has to return once the eventfd is signalled, or sleeps if another thread
+  /// is already waiting for them.
+  pub(super) fn block_on<F: Future>(&'static self, mut turn: impl FnMut(), future: F) -> F::Output {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+      |wake| RawWaker::new(wake, &VTABLE),
+      // Safety: the data is always a `&'static Wake`
+      |wake| unsafe { &*wake.cast::<Wake>() }.signal(),
+      |wake| unsafe { &*wake.cast::<Wake>() }.signal(),
+      |_| {},
+    );
+
+    // Safety: the vtable only uses the data as a `&'static Wake`
+    let waker = unsafe { Waker::new(core::ptr::from_ref(self).cast(), &VTABLE) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+      let generation = self.generation.load(Ordering::Acquire);
+      if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+        return value;
+      }
+
+      if self
+        .turning
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_ok()
+      {
+        turn();
+        self.turning.store(false, Ordering::Release);
+        // a sleeping thread might have to wait for io events now
+        self.wake_sleeping();
+      } else {
+        // returns right away if there was a wake since the future was polled
+        let _ = unsafe {
+          syscall!(
+            Sysno::futex,
+            &raw const self.generation,
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            generation,
+            0,
+            0,
+            0
+          )
+        };
+      }
+    }
+  }
+}
@@ -1,8 +1,11 @@
 use core::{
   marker::PhantomData,
+  mem,
   ptr::{self, Pointee},
 };
 
+use syscalls::{Sysno, syscall};
+
 pub mod io;
 pub mod rt;
 mod sync;
@@ -14,6 +17,33 @@ pub type MaybeFileDescriptor = i32;
 #[cfg(unix)]
 pub type FileDescriptor = u32;
 
+/// A file descriptor that's closed when dropped.
+pub struct OwnedFileDescriptor(FileDescriptor);
+
+impl OwnedFileDescriptor {
+  pub fn new(file_descriptor: FileDescriptor) -> Self {
+    Self(file_descriptor)
+  }
+
+  pub fn get(&self) -> FileDescriptor {
+    self.0
+  }
+
+  /// Returns the file descriptor without closing it.
+  pub fn into_raw(self) -> FileDescriptor {
+    let file_descriptor = self.0;
+    mem::forget(self);
+    file_descriptor
+  }
+}
+
+impl Drop for OwnedFileDescriptor {
+  fn drop(&mut self) {
+    // the file descriptor is released even if close fails, so there's nothing to retry
+    let _ = unsafe { syscall!(Sysno::close, self.0) };
+  }
+}
+
 type ProcessId = libc::pid_t;
 
 #[derive(Clone, Copy)]
@@ -4,7 +4,7 @@ use core::panic::PanicInfo;
 
 use syscalls::{Sysno, syscall};
 
-use crate::println;
+use crate::{eprintln, internal};
 
 #[cfg(not(any(test, doctest)))]
 #[lang = "termination"]
@@ -28,16 +28,19 @@ fn start<T: Termination + 'static>(
   use crate::platform::active;
   active::rt::handle_args(argc, argv);
   // todo: handle sigpipe
-  main().value()
+  let value = main().value();
+  internal::flush_stdout();
+  value
 }
 
 #[panic_handler]
 fn panic_handler(info: &PanicInfo) -> ! {
-  println!("{info}");
+  internal::flush_stdout();
+  eprintln!("{info}");
 
   unsafe {
     if let Err(error) = syscall!(Sysno::exit, 1) {
-      println!("failed to exit?! {error}")
+      eprintln!("failed to exit?! {error}")
     }
     unreachable_unchecked()
   }
@@ -0,0 +1,322 @@
+use core::{
+  fmt::{self, Debug},
+  iter::FusedIterator,
+  ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+  slice,
+};
+
+pub(crate) const WORD_BITS: usize = usize::BITS as usize;
+
+/// The number of words needed to store `bits` bits.
+pub const fn words_for(bits: usize) -> usize {
+  bits.div_ceil(WORD_BITS)
+}
+
+/// Returns the index of the word containing bit `index`, and the mask of the bit in that word.
+const fn locate(index: usize) -> (usize, usize) {
+  (index / WORD_BITS, 1 << (index % WORD_BITS))
+}
+
+pub(crate) fn test(words: &[usize], index: usize) -> bool {
+  let (word, mask) = locate(index);
+  words[word] & mask != 0
+}
+
+pub(crate) fn set(words: &mut [usize], index: usize) {
+  let (word, mask) = locate(index);
+  words[word] |= mask;
+}
+
+pub(crate) fn clear(words: &mut [usize], index: usize) {
+  let (word, mask) = locate(index);
+  words[word] &= !mask;
+}
+
+pub(crate) fn toggle(words: &mut [usize], index: usize) {
+  let (word, mask) = locate(index);
+  words[word] ^= mask;
+}
+
+pub(crate) fn count_ones(words: &[usize]) -> usize {
+  words.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+/// Counts the set bits before `index`.
+pub(crate) fn rank(words: &[usize], index: usize) -> usize {
+  let (word, mask) = locate(index);
+  let partial = words
+    .get(word)
+    .map_or(0, |bits| (bits & (mask - 1)).count_ones());
+  count_ones(&words[..word.min(words.len())]) + partial as usize
+}
+
+/// Finds the index of the `n`th set bit, counting from zero.
+pub(crate) fn select(words: &[usize], mut n: usize) -> Option<usize> {
+  for (index, &word) in words.iter().enumerate() {
+    let ones = word.count_ones() as usize;
+    if n >= ones {
+      n -= ones;
+      continue;
+    }
+
+    let mut word = word;
+    for _ in 0..n {
+      // clears the lowest set bit
+      word &= word - 1;
+    }
+
+    return Some(index * WORD_BITS + word.trailing_zeros() as usize);
+  }
+
+  None
+}
+
+/// Clears the bits of the last word past `bits`, which operations like [Not] would otherwise set.
+pub(crate) fn mask_tail(words: &mut [usize], bits: usize) {
+  let used = bits % WORD_BITS;
+  if used != 0
+    && let Some(last) = words.last_mut()
+  {
+    *last &= (1 << used) - 1;
+  }
+}
+
+/// An iterator over the indices of the set bits, from lowest to highest.
+#[derive(Clone)]
+pub struct Ones<'a> {
+  words: slice::Iter<'a, usize>,
+  base: usize,
+  current: usize,
+}
+
+impl<'a> Ones<'a> {
+  pub(crate) fn new(words: &'a [usize]) -> Self {
+    let mut words = words.iter();
+    let current = words.next().copied().unwrap_or(0);
+
+    Self {
+      words,
+      base: 0,
+      current,
+    }
+  }
+}
+
+impl Iterator for Ones<'_> {
+  type Item = usize;
+
+  fn next(&mut self) -> Option<usize> {
+    while self.current == 0 {
+      self.current = *self.words.next()?;
+      self.base += WORD_BITS;
+    }
+
+    let bit = self.current.trailing_zeros() as usize;
+    self.current &= self.current - 1;
+
+    Some(self.base + bit)
+  }
+}
+
+impl FusedIterator for Ones<'_> {}
+
+/// A set of `BITS` bits stored inline.
+///
+/// The bits are packed into `usize` words from the lowest bit up, so a `FixedBitSet<1024>` has the
+/// same layout as the kernel's `fd_set` on Linux.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const BITS: usize>
+where
+  [(); words_for(BITS)]:,
+{
+  words: [usize; words_for(BITS)],
+}
+
+impl<const BITS: usize> FixedBitSet<BITS>
+where
+  [(); words_for(BITS)]:,
+{
+  pub const fn new() -> Self {
+    Self {
+      words: [0; words_for(BITS)],
+    }
+  }
+
+  /// Creates a set with every bit set.
+  pub fn full() -> Self {
+    !Self::new()
+  }
+
+  pub const fn capacity(&self) -> usize {
+    BITS
+  }
+
+  fn check(&self, index: usize) {
+    assert!(
+      index < BITS,
+      "bit index {index} out of bounds for length {BITS}"
+    );
+  }
+
+  /// Whether bit `index` is set.
+  ///
+  /// Panics if `index >= BITS`.
+  pub fn test(&self, index: usize) -> bool {
+    self.check(index);
+    test(&self.words, index)
+  }
+
+  /// Panics if `index >= BITS`.
+  pub fn set(&mut self, index: usize) {
+    self.check(index);
+    set(&mut self.words, index);
+  }
+
+  /// Panics if `index >= BITS`.
+  pub fn clear(&mut self, index: usize) {
+    self.check(index);
+    clear(&mut self.words, index);
+  }
+
+  /// Panics if `index >= BITS`.
+  pub fn toggle(&mut self, index: usize) {
+    self.check(index);
+    toggle(&mut self.words, index);
+  }
+
+  /// Clears every bit.
+  pub fn reset(&mut self) {
+    self.words = [0; words_for(BITS)];
+  }
+
+  pub fn count_ones(&self) -> usize {
+    count_ones(&self.words)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.words.iter().all(|&word| word == 0)
+  }
+
+  /// Counts the set bits before `index`.
+  ///
+  /// Panics if `index > BITS`.
+  pub fn rank(&self, index: usize) -> usize {
+    assert!(
+      index <= BITS,
+      "bit index {index} out of bounds for length {BITS}"
+    );
+    rank(&self.words, index)
+  }
+
+  /// Finds the index of the `n`th set bit, counting from zero.
+  pub fn select(&self, n: usize) -> Option<usize> {
+    select(&self.words, n)
+  }
+
+  pub fn iter_ones(&self) -> Ones<'_> {
+    Ones::new(&self.words)
+  }
+
+  pub fn as_words(&self) -> &[usize] {
+    &self.words
+  }
+
+  pub fn as_words_mut(&mut self) -> &mut [usize] {
+    &mut self.words
+  }
+}
+
+impl<const BITS: usize> Default for FixedBitSet<BITS>
+where
+  [(); words_for(BITS)]:,
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const BITS: usize> Debug for FixedBitSet<BITS>
+where
+  [(); words_for(BITS)]:,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.iter_ones()).finish()
+  }
+}
+
+macro_rules! bitwise_op {
+  ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+    impl<const BITS: usize> $assign_trait for FixedBitSet<BITS>
+    where
+      [(); words_for(BITS)]:,
+    {
+      fn $assign_method(&mut self, rhs: Self) {
+        for (word, rhs) in self.words.iter_mut().zip(rhs.words) {
+          *word = *word $op rhs;
+        }
+      }
+    }
+
+    impl<const BITS: usize> $trait for FixedBitSet<BITS>
+    where
+      [(); words_for(BITS)]:,
+    {
+      type Output = Self;
+
+      fn $method(mut self, rhs: Self) -> Self {
+        self.$assign_method(rhs);
+        self
+      }
+    }
+  };
+}
+
+bitwise_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bitwise_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bitwise_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl<const BITS: usize> Not for FixedBitSet<BITS>
+where
+  [(); words_for(BITS)]:,
+{
+  type Output = Self;
+
+  fn not(mut self) -> Self {
+    for word in &mut self.words {
+      *word = !*word;
+    }
+    mask_tail(&mut self.words, BITS);
+
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::FixedBitSet;
+
+  #[test]
+  fn set_rank_select() {
+    let mut bits = FixedBitSet::<130>::new();
+    for index in [0, 3, 64, 65, 129] {
+      bits.set(index);
+    }
+    bits.toggle(3);
+    bits.clear(65);
+
+    assert!(bits.test(129) && !bits.test(3));
+    assert_eq!(bits.count_ones(), 3);
+    assert_eq!(bits.rank(64), 1);
+    assert_eq!(bits.rank(130), 3);
+    assert_eq!(bits.select(1), Some(64));
+    assert_eq!(bits.select(3), None);
+    assert!(bits.iter_ones().eq([0, 64, 129]));
+
+    let inverse = !bits;
+    assert_eq!(inverse.count_ones(), 127);
+    assert!((inverse & bits).is_empty());
+    assert_eq!(inverse | bits, FixedBitSet::full());
+    assert_eq!((inverse ^ FixedBitSet::full()), bits);
+  }
+}
@@ -0,0 +1,133 @@
+use core::{
+  clone::CloneToUninit,
+  fmt::{self, Debug, Display},
+  ops::{Deref, DerefMut},
+};
+
+use crate::alloc::{
+  SliceAllocator, SliceDst, UnsizedMaybeUninit, clone::clone_slice_dst_in, strategy::Strategy,
+};
+
+/// Either a reference to borrowed data, or a handle to data owned through the strategy `S`.
+///
+/// Cloning the borrowed data needs an allocation, so the allocator is passed to [Cow::to_mut] and
+/// [Cow::into_owned] instead of being stored.
+pub enum Cow<'a, T: ?Sized + 'a, S: Strategy> {
+  Borrowed(&'a T),
+  Owned(S::Handle<'a, T>),
+}
+
+impl<'a, T: ?Sized + 'a, S: Strategy> Cow<'a, T, S> {
+  pub fn is_borrowed(&self) -> bool {
+    matches!(self, Cow::Borrowed(_))
+  }
+
+  pub fn is_owned(&self) -> bool {
+    matches!(self, Cow::Owned(_))
+  }
+}
+
+impl<'a, T: SliceDst + CloneToUninit + ?Sized + 'a, S: Strategy> Cow<'a, T, S> {
+  /// Returns a mutable reference to the owned data, cloning the borrowed data into an allocation
+  /// from `allocator` first if needed.
+  pub async fn to_mut<A: SliceAllocator<'a, T>>(
+    &mut self,
+    allocator: &'a A,
+  ) -> Result<&mut T, A::Error>
+  where
+    S::Handle<'a, T>: DerefMut<Target = T>,
+    S::Data<'a, UnsizedMaybeUninit<T>>: SliceDst,
+  {
+    if let Cow::Borrowed(value) = self {
+      *self = Cow::Owned(clone_slice_dst_in::<_, S, _>(value, allocator).await?);
+    }
+
+    let Cow::Owned(handle) = self else {
+      unreachable!("value was just cloned");
+    };
+
+    Ok(handle)
+  }
+
+  /// Returns the owned data, cloning the borrowed data into an allocation from `allocator` if
+  /// needed.
+  pub async fn into_owned<A: SliceAllocator<'a, T>>(
+    self,
+    allocator: &'a A,
+  ) -> Result<S::Handle<'a, T>, A::Error>
+  where
+    S::Data<'a, UnsizedMaybeUninit<T>>: SliceDst,
+  {
+    match self {
+      Cow::Borrowed(value) => clone_slice_dst_in::<_, S, _>(value, allocator).await,
+      Cow::Owned(handle) => Ok(handle),
+    }
+  }
+}
+
+impl<'a, T: ?Sized + 'a, S: Strategy> Deref for Cow<'a, T, S>
+where
+  S::Handle<'a, T>: Deref<Target = T>,
+{
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    match self {
+      Cow::Borrowed(value) => value,
+      Cow::Owned(handle) => handle,
+    }
+  }
+}
+
+impl<'a, T: ?Sized + 'a, S: Strategy> From<&'a T> for Cow<'a, T, S> {
+  fn from(value: &'a T) -> Self {
+    Cow::Borrowed(value)
+  }
+}
+
+impl<'a, T: Debug + ?Sized + 'a, S: Strategy> Debug for Cow<'a, T, S>
+where
+  S::Handle<'a, T>: Deref<Target = T>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.deref().fmt(f)
+  }
+}
+
+impl<'a, T: Display + ?Sized + 'a, S: Strategy> Display for Cow<'a, T, S>
+where
+  S::Handle<'a, T>: Deref<Target = T>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.deref().fmt(f)
+  }
+}
+
+#[cfg(test)]
+#[cfg(feature = "libc")]
+mod tests {
+  use crate::{
+    alloc::{ForeignAllocator, StdAlloc, strategy::UniqueStrategy},
+    types::Cow,
+  };
+
+  #[pollster::test]
+  async fn clone_on_write() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut text = Cow::<str, UniqueStrategy>::from("hello");
+    assert!(text.is_borrowed());
+
+    text
+      .to_mut(&allocator)
+      .await
+      .unwrap()
+      .make_ascii_uppercase();
+    assert!(text.is_owned());
+    assert_eq!(&*text, "HELLO");
+
+    let numbers = Cow::<[u32], UniqueStrategy>::from(&[1, 2, 3][..]);
+    let mut owned = numbers.into_owned(&allocator).await.unwrap();
+    owned[0] = 4;
+    assert_eq!(&*owned, &[4, 2, 3]);
+  }
+}
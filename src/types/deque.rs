@@ -0,0 +1,375 @@
+use core::{
+  array,
+  fmt::{self, Debug},
+  iter::FusedIterator,
+  marker::PhantomData,
+  mem::MaybeUninit,
+  ops::{Index, IndexMut},
+  slice,
+};
+
+/// A ring buffer over `V`, with the same storage options as [BaseVec](super::vec::BaseVec).
+#[derive(SliceDst)]
+#[repr(C)]
+pub struct BaseDeque<T, V: AsRef<[MaybeUninit<T>]> + ?Sized> {
+  phantom: PhantomData<T>,
+  head: usize,
+  length: usize,
+  values: V,
+}
+
+impl<T, V: AsRef<[MaybeUninit<T>]> + SliceDst + ?Sized> BaseDequeHeader<T, V>
+where
+  V::Header: Default,
+{
+  pub fn new() -> Self {
+    Self {
+      phantom: PhantomData,
+      head: 0,
+      length: 0,
+      values_header: V::Header::default(),
+    }
+  }
+}
+
+impl<T, V: AsRef<[MaybeUninit<T>]> + SliceDst + ?Sized> Default for BaseDequeHeader<T, V>
+where
+  V::Header: Default,
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+pub type SliceDeque<T> = BaseDeque<T, [MaybeUninit<T>]>;
+
+pub type FixedDeque<T, const CAPACITY: usize> = BaseDeque<T, [MaybeUninit<T>; CAPACITY]>;
+
+impl<T, const CAPACITY: usize> FixedDeque<T, CAPACITY> {
+  pub fn new() -> Self {
+    Self {
+      phantom: PhantomData,
+      head: 0,
+      length: 0,
+      values: array::from_fn(|_| MaybeUninit::uninit()),
+    }
+  }
+}
+
+impl<T, const CAPACITY: usize> Default for FixedDeque<T, CAPACITY> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Safety: every value in `values` must be initialized
+unsafe fn assume_init_slice<T>(values: &[MaybeUninit<T>]) -> &[T] {
+  unsafe { &*(values as *const [MaybeUninit<T>] as *const [T]) }
+}
+
+/// Safety: every value in `values` must be initialized
+unsafe fn assume_init_slice_mut<T>(values: &mut [MaybeUninit<T>]) -> &mut [T] {
+  unsafe { &mut *(values as *mut [MaybeUninit<T>] as *mut [T]) }
+}
+
+impl<T, V: AsRef<[MaybeUninit<T>]> + ?Sized> BaseDeque<T, V> {
+  pub fn capacity(&self) -> usize {
+    self.values.as_ref().len()
+  }
+
+  pub fn len(&self) -> usize {
+    self.length
+  }
+
+  pub fn is_full(&self) -> bool {
+    self.capacity() <= self.length
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.length == 0
+  }
+
+  /// Maps a logical index (counting from the front) to an index into `values`.
+  fn physical_index(&self, index: usize) -> usize {
+    let index = self.head + index;
+    if index >= self.capacity() {
+      index - self.capacity()
+    } else {
+      index
+    }
+  }
+
+  pub fn get(&self, index: usize) -> Option<&T> {
+    if index >= self.length {
+      return None;
+    }
+
+    // Safety: every logical index below length is initialized
+    Some(unsafe { self.values.as_ref()[self.physical_index(index)].assume_init_ref() })
+  }
+
+  pub fn front(&self) -> Option<&T> {
+    self.get(0)
+  }
+
+  pub fn back(&self) -> Option<&T> {
+    self.get(self.length.checked_sub(1)?)
+  }
+
+  /// Returns the contents of the deque in order, split where the buffer wraps around.
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let values = self.values.as_ref();
+    let wrapped = (self.head + self.length).saturating_sub(self.capacity());
+    let front = &values[self.head..self.head + self.length - wrapped];
+    // Safety: both ranges only cover initialized values
+    unsafe {
+      (
+        assume_init_slice(front),
+        assume_init_slice(&values[..wrapped]),
+      )
+    }
+  }
+
+  pub fn iter(&self) -> Iter<'_, T> {
+    let (front, back) = self.as_slices();
+    Iter {
+      front: front.iter(),
+      back: back.iter(),
+    }
+  }
+}
+
+impl<T, V: AsRef<[MaybeUninit<T>]> + AsMut<[MaybeUninit<T>]> + ?Sized> BaseDeque<T, V> {
+  pub fn push_back(&mut self, value: T) -> Result<(), T> {
+    if self.is_full() {
+      return Err(value);
+    }
+
+    let index = self.physical_index(self.length);
+    self.values.as_mut()[index].write(value);
+    self.length += 1;
+
+    Ok(())
+  }
+
+  pub fn push_front(&mut self, value: T) -> Result<(), T> {
+    if self.is_full() {
+      return Err(value);
+    }
+
+    self.head = self.physical_index(self.capacity() - 1);
+    self.values.as_mut()[self.head].write(value);
+    self.length += 1;
+
+    Ok(())
+  }
+
+  pub fn pop_back(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    self.length -= 1;
+    let index = self.physical_index(self.length);
+    // Safety: known to be initialized at index, since it was the last value
+    Some(unsafe { self.values.as_mut()[index].assume_init_read() })
+  }
+
+  pub fn pop_front(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    let index = self.head;
+    self.head = self.physical_index(1);
+    self.length -= 1;
+    // Safety: known to be initialized at index, since it was the first value
+    Some(unsafe { self.values.as_mut()[index].assume_init_read() })
+  }
+
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    if index >= self.length {
+      return None;
+    }
+
+    let index = self.physical_index(index);
+    // Safety: every logical index below length is initialized
+    Some(unsafe { self.values.as_mut()[index].assume_init_mut() })
+  }
+
+  pub fn front_mut(&mut self) -> Option<&mut T> {
+    self.get_mut(0)
+  }
+
+  pub fn back_mut(&mut self) -> Option<&mut T> {
+    self.get_mut(self.length.checked_sub(1)?)
+  }
+
+  pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+    let (head, length) = (self.head, self.length);
+    let wrapped = (head + length).saturating_sub(self.capacity());
+    let (back, front) = self.values.as_mut().split_at_mut(head);
+    // Safety: both ranges only cover initialized values
+    unsafe {
+      (
+        assume_init_slice_mut(&mut front[..length - wrapped]),
+        assume_init_slice_mut(&mut back[..wrapped]),
+      )
+    }
+  }
+
+  /// Rotates the buffer so that the contents start at the beginning of it, and returns them as a
+  /// single slice.
+  pub fn make_contiguous(&mut self) -> &mut [T] {
+    let head = self.head;
+    self.values.as_mut().rotate_left(head);
+    self.head = 0;
+
+    let length = self.length;
+    // Safety: the first `length` values are initialized after rotating
+    unsafe { assume_init_slice_mut(&mut self.values.as_mut()[..length]) }
+  }
+
+  pub fn clear(&mut self) {
+    while self.pop_back().is_some() {}
+    self.head = 0;
+  }
+
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    let (front, back) = self.as_mut_slices();
+    IterMut {
+      front: front.iter_mut(),
+      back: back.iter_mut(),
+    }
+  }
+}
+
+impl<T, V: AsRef<[MaybeUninit<T>]> + ?Sized> Index<usize> for BaseDeque<T, V> {
+  type Output = T;
+
+  fn index(&self, index: usize) -> &T {
+    self.get(index).expect("index out of bounds")
+  }
+}
+
+impl<T, V: AsRef<[MaybeUninit<T>]> + AsMut<[MaybeUninit<T>]> + ?Sized> IndexMut<usize>
+  for BaseDeque<T, V>
+{
+  fn index_mut(&mut self, index: usize) -> &mut T {
+    self.get_mut(index).expect("index out of bounds")
+  }
+}
+
+impl<T: Debug, V: AsRef<[MaybeUninit<T>]> + ?Sized> Debug for BaseDeque<T, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<'b, T, V: AsRef<[MaybeUninit<T>]> + ?Sized> IntoIterator for &'b BaseDeque<T, V> {
+  type Item = &'b T;
+  type IntoIter = Iter<'b, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'b, T, V: AsRef<[MaybeUninit<T>]> + AsMut<[MaybeUninit<T>]> + ?Sized> IntoIterator
+  for &'b mut BaseDeque<T, V>
+{
+  type Item = &'b mut T;
+  type IntoIter = IterMut<'b, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+pub struct Iter<'b, T> {
+  front: slice::Iter<'b, T>,
+  back: slice::Iter<'b, T>,
+}
+
+impl<'b, T> Iterator for Iter<'b, T> {
+  type Item = &'b T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.front.next().or_else(|| self.back.next())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let length = self.front.len() + self.back.len();
+    (length, Some(length))
+  }
+}
+
+impl<'b, T> DoubleEndedIterator for Iter<'b, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.back.next_back().or_else(|| self.front.next_back())
+  }
+}
+
+impl<'b, T> ExactSizeIterator for Iter<'b, T> {}
+impl<'b, T> FusedIterator for Iter<'b, T> {}
+
+pub struct IterMut<'b, T> {
+  front: slice::IterMut<'b, T>,
+  back: slice::IterMut<'b, T>,
+}
+
+impl<'b, T> Iterator for IterMut<'b, T> {
+  type Item = &'b mut T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.front.next().or_else(|| self.back.next())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let length = self.front.len() + self.back.len();
+    (length, Some(length))
+  }
+}
+
+impl<'b, T> DoubleEndedIterator for IterMut<'b, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.back.next_back().or_else(|| self.front.next_back())
+  }
+}
+
+impl<'b, T> ExactSizeIterator for IterMut<'b, T> {}
+impl<'b, T> FusedIterator for IterMut<'b, T> {}
+
+#[cfg(test)]
+mod tests {
+  use crate::types::deque::FixedDeque;
+
+  #[test]
+  fn wrap_around() {
+    let mut deque = FixedDeque::<u32, 4>::new();
+    deque.push_back(1).unwrap();
+    deque.push_back(2).unwrap();
+    deque.push_front(0).unwrap();
+    deque.push_front(9).unwrap();
+    assert_eq!(deque.push_back(3), Err(3));
+
+    assert_eq!(deque.as_slices(), (&[9, 0][..], &[1, 2][..]));
+    assert!(deque.iter().copied().eq([9, 0, 1, 2]));
+    assert!(deque.iter().rev().copied().eq([2, 1, 0, 9]));
+    assert_eq!(deque[1], 0);
+
+    assert_eq!(deque.pop_front(), Some(9));
+    assert_eq!(deque.pop_back(), Some(2));
+    deque.push_back(5).unwrap();
+    assert_eq!(deque.make_contiguous(), &[0, 1, 5]);
+    assert_eq!(deque.as_slices(), (&[0, 1, 5][..], &[][..]));
+  }
+
+  #[test]
+  fn empty() {
+    let mut deque = FixedDeque::<u32, 0>::new();
+    assert_eq!(deque.push_front(1), Err(1));
+    assert_eq!(deque.pop_back(), None);
+    assert_eq!(deque.as_slices(), (&[][..], &[][..]));
+  }
+}
@@ -0,0 +1,279 @@
+use core::{
+  cmp::Ordering,
+  fmt::{self, Debug},
+  mem,
+  ops::{Deref, DerefMut},
+  slice,
+};
+
+use crate::types::vec::FixedVec;
+
+/// Decides the order of values in a heap. The greatest value according to the comparator is
+/// popped first.
+pub trait Compare<T: ?Sized> {
+  fn compare(&self, left: &T, right: &T) -> Ordering;
+}
+
+/// Orders values by their [Ord] implementation, making a max-heap.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct NaturalOrder;
+
+impl<T: Ord + ?Sized> Compare<T> for NaturalOrder {
+  fn compare(&self, left: &T, right: &T) -> Ordering {
+    left.cmp(right)
+  }
+}
+
+/// Orders values by the reverse of their [Ord] implementation, making a min-heap.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ReverseOrder;
+
+impl<T: Ord + ?Sized> Compare<T> for ReverseOrder {
+  fn compare(&self, left: &T, right: &T) -> Ordering {
+    right.cmp(left)
+  }
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+  fn compare(&self, left: &T, right: &T) -> Ordering {
+    self(left, right)
+  }
+}
+
+/// Moves the value at `index` up until its parent is at least as great.
+pub(crate) fn sift_up<T, C: Compare<T>>(data: &mut [T], mut index: usize, compare: &C) {
+  while index > 0 {
+    let parent = (index - 1) / 2;
+    if compare.compare(&data[index], &data[parent]) != Ordering::Greater {
+      break;
+    }
+
+    data.swap(index, parent);
+    index = parent;
+  }
+}
+
+/// Moves the value at `index` down until both of its children are at most as great.
+pub(crate) fn sift_down<T, C: Compare<T>>(data: &mut [T], mut index: usize, compare: &C) {
+  loop {
+    let left = index * 2 + 1;
+    let right = left + 1;
+    let mut greatest = index;
+
+    if left < data.len() && compare.compare(&data[left], &data[greatest]) == Ordering::Greater {
+      greatest = left;
+    }
+    if right < data.len() && compare.compare(&data[right], &data[greatest]) == Ordering::Greater {
+      greatest = right;
+    }
+    if greatest == index {
+      return;
+    }
+
+    data.swap(index, greatest);
+    index = greatest;
+  }
+}
+
+/// Rearranges `data` into a heap.
+pub(crate) fn heapify<T, C: Compare<T>>(data: &mut [T], compare: &C) {
+  for index in (0..data.len() / 2).rev() {
+    sift_down(data, index, compare);
+  }
+}
+
+/// Sorts a heap in place, from least to greatest.
+pub(crate) fn sort_heap<T, C: Compare<T>>(data: &mut [T], compare: &C) {
+  for end in (1..data.len()).rev() {
+    data.swap(0, end);
+    sift_down(&mut data[..end], 0, compare);
+  }
+}
+
+/// A mutable reference to the greatest value of a heap, which restores the heap order once
+/// dropped.
+pub struct PeekMut<'b, T, C: Compare<T>> {
+  data: &'b mut [T],
+  compare: &'b C,
+}
+
+impl<'b, T, C: Compare<T>> PeekMut<'b, T, C> {
+  /// Safety: `data` must not be empty
+  pub(crate) unsafe fn new(data: &'b mut [T], compare: &'b C) -> Self {
+    debug_assert!(!data.is_empty());
+    Self { data, compare }
+  }
+}
+
+impl<'b, T, C: Compare<T>> Deref for PeekMut<'b, T, C> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    // Safety: never empty
+    unsafe { self.data.get_unchecked(0) }
+  }
+}
+
+impl<'b, T, C: Compare<T>> DerefMut for PeekMut<'b, T, C> {
+  fn deref_mut(&mut self) -> &mut T {
+    // Safety: never empty
+    unsafe { self.data.get_unchecked_mut(0) }
+  }
+}
+
+impl<'b, T, C: Compare<T>> Drop for PeekMut<'b, T, C> {
+  fn drop(&mut self) {
+    sift_down(self.data, 0, self.compare);
+  }
+}
+
+/// A priority queue with room for `CAPACITY` values, stored inline.
+pub struct FixedHeap<T, const CAPACITY: usize, C: Compare<T> = NaturalOrder> {
+  data: FixedVec<T, CAPACITY>,
+  compare: C,
+}
+
+impl<T, const CAPACITY: usize, C: Compare<T> + Default> FixedHeap<T, CAPACITY, C> {
+  pub fn new() -> Self {
+    Self::with_comparator(C::default())
+  }
+}
+
+impl<T, const CAPACITY: usize, C: Compare<T> + Default> Default for FixedHeap<T, CAPACITY, C> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T, const CAPACITY: usize, C: Compare<T>> FixedHeap<T, CAPACITY, C> {
+  pub fn with_comparator(compare: C) -> Self {
+    Self {
+      data: FixedVec::new(),
+      compare,
+    }
+  }
+
+  /// Turns the values of `data` into a heap.
+  pub fn from_vec(mut data: FixedVec<T, CAPACITY>, compare: C) -> Self {
+    heapify(&mut data, &compare);
+    Self { data, compare }
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.data.capacity()
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  pub fn is_full(&self) -> bool {
+    self.data.is_full()
+  }
+
+  /// Returns the greatest value in the heap.
+  pub fn peek(&self) -> Option<&T> {
+    self.data.first()
+  }
+
+  /// Returns a mutable reference to the greatest value in the heap, which is moved to its new
+  /// position once the reference is dropped.
+  pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+    if self.data.is_empty() {
+      return None;
+    }
+
+    // Safety: checked that the heap isn't empty
+    Some(unsafe { PeekMut::new(&mut self.data, &self.compare) })
+  }
+
+  /// Pushes a value into the heap, or returns it back if the heap is full.
+  pub fn push(&mut self, value: T) -> Result<(), T> {
+    self.data.push(value)?;
+    let index = self.data.len() - 1;
+    sift_up(&mut self.data, index, &self.compare);
+
+    Ok(())
+  }
+
+  /// Removes the greatest value from the heap.
+  pub fn pop(&mut self) -> Option<T> {
+    let mut value = self.data.pop()?;
+    if !self.data.is_empty() {
+      mem::swap(&mut value, &mut self.data[0]);
+      sift_down(&mut self.data, 0, &self.compare);
+    }
+
+    Some(value)
+  }
+
+  pub fn clear(&mut self) {
+    self.data.clear();
+  }
+
+  /// Iterates over the values of the heap in an arbitrary order.
+  pub fn iter(&self) -> slice::Iter<'_, T> {
+    self.data.iter()
+  }
+
+  pub fn as_slice(&self) -> &[T] {
+    &self.data
+  }
+
+  pub fn into_vec(self) -> FixedVec<T, CAPACITY> {
+    self.data
+  }
+
+  /// Returns the values of the heap sorted from least to greatest.
+  pub fn into_sorted_vec(mut self) -> FixedVec<T, CAPACITY> {
+    sort_heap(&mut self.data, &self.compare);
+    self.into_vec()
+  }
+}
+
+impl<T: Debug, const CAPACITY: usize, C: Compare<T>> Debug for FixedHeap<T, CAPACITY, C> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::types::heap::{FixedHeap, ReverseOrder};
+
+  #[test]
+  fn fixed_heap() {
+    let mut heap = FixedHeap::<u32, 5>::new();
+    for value in [3, 1, 4, 1, 5] {
+      heap.push(value).unwrap();
+    }
+    assert_eq!(heap.push(9), Err(9));
+    assert_eq!(heap.peek(), Some(&5));
+
+    *heap.peek_mut().unwrap() = 0;
+    assert_eq!(heap.pop(), Some(4));
+    assert_eq!(&*heap.into_sorted_vec(), &[0, 1, 1, 3]);
+  }
+
+  #[test]
+  fn comparators() {
+    let mut heap = FixedHeap::<u32, 4, _>::with_comparator(ReverseOrder);
+    for value in [3, 1, 4, 2] {
+      heap.push(value).unwrap();
+    }
+    assert_eq!(heap.pop(), Some(1));
+
+    let mut heap =
+      FixedHeap::<(u32, &str), 4, _>::with_comparator(|left: &(u32, &str), right: &(u32, &str)| {
+        left.1.len().cmp(&right.1.len())
+      });
+    heap.push((0, "a")).unwrap();
+    heap.push((1, "abc")).unwrap();
+    heap.push((2, "ab")).unwrap();
+    assert_eq!(heap.pop(), Some((1, "abc")));
+  }
+}
@@ -0,0 +1,711 @@
+use core::{
+  cell::Cell,
+  fmt::{self, Debug},
+  iter::FusedIterator,
+  marker::{PhantomData, PhantomPinned},
+  pin::Pin,
+  ptr::NonNull,
+  sync::atomic::{AtomicBool, Ordering},
+};
+
+mod private {
+  use core::ptr::NonNull;
+
+  pub trait Sealed: Sized {
+    fn next(&self) -> Option<NonNull<Self>>;
+    fn set_next(&self, next: Option<NonNull<Self>>);
+    /// Always returns [None] for links that don't point back.
+    fn prev(&self) -> Option<NonNull<Self>>;
+    fn set_prev(&self, prev: Option<NonNull<Self>>);
+    fn set_linked(&self, linked: bool);
+  }
+}
+
+use private::Sealed;
+
+/// A link embedded in a node, which lets the node be put into a [List] without allocating.
+pub trait Link: Sealed {
+  /// Whether the link points back to the previous node, which makes removal take constant time.
+  const DOUBLY: bool;
+
+  fn is_linked(&self) -> bool;
+}
+
+/// A link to the next node only. Removing a node from the middle of the list has to search for
+/// the previous one.
+pub struct SinglyLink {
+  next: Cell<Option<NonNull<SinglyLink>>>,
+  linked: AtomicBool,
+  _pinned: PhantomPinned,
+}
+
+impl SinglyLink {
+  pub const fn new() -> Self {
+    Self {
+      next: Cell::new(None),
+      linked: AtomicBool::new(false),
+      _pinned: PhantomPinned,
+    }
+  }
+}
+
+impl Sealed for SinglyLink {
+  fn next(&self) -> Option<NonNull<Self>> {
+    self.next.get()
+  }
+
+  fn set_next(&self, next: Option<NonNull<Self>>) {
+    self.next.set(next);
+  }
+
+  fn prev(&self) -> Option<NonNull<Self>> {
+    None
+  }
+
+  fn set_prev(&self, _prev: Option<NonNull<Self>>) {}
+
+  fn set_linked(&self, linked: bool) {
+    self.linked.store(linked, Ordering::Relaxed);
+  }
+}
+
+impl Link for SinglyLink {
+  const DOUBLY: bool = false;
+
+  fn is_linked(&self) -> bool {
+    self.linked.load(Ordering::Relaxed)
+  }
+}
+
+/// A link to both the next and the previous node.
+pub struct DoublyLink {
+  next: Cell<Option<NonNull<DoublyLink>>>,
+  prev: Cell<Option<NonNull<DoublyLink>>>,
+  linked: AtomicBool,
+  _pinned: PhantomPinned,
+}
+
+impl DoublyLink {
+  pub const fn new() -> Self {
+    Self {
+      next: Cell::new(None),
+      prev: Cell::new(None),
+      linked: AtomicBool::new(false),
+      _pinned: PhantomPinned,
+    }
+  }
+}
+
+impl Sealed for DoublyLink {
+  fn next(&self) -> Option<NonNull<Self>> {
+    self.next.get()
+  }
+
+  fn set_next(&self, next: Option<NonNull<Self>>) {
+    self.next.set(next);
+  }
+
+  fn prev(&self) -> Option<NonNull<Self>> {
+    self.prev.get()
+  }
+
+  fn set_prev(&self, prev: Option<NonNull<Self>>) {
+    self.prev.set(prev);
+  }
+
+  fn set_linked(&self, linked: bool) {
+    self.linked.store(linked, Ordering::Relaxed);
+  }
+}
+
+impl Link for DoublyLink {
+  const DOUBLY: bool = true;
+
+  fn is_linked(&self) -> bool {
+    self.linked.load(Ordering::Relaxed)
+  }
+}
+
+macro_rules! impl_link_common {
+  ($($link:ident),*) => {
+    $(
+      impl Default for $link {
+        fn default() -> Self {
+          Self::new()
+        }
+      }
+
+      impl Drop for $link {
+        fn drop(&mut self) {
+          // the node is pinned, so this runs before its memory is reused, and the list would
+          // otherwise be left pointing at it
+          assert!(!self.is_linked(), "node dropped while still linked into a list");
+        }
+      }
+
+      impl Debug for $link {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+          f.debug_struct(stringify!($link))
+            .field("linked", &self.is_linked())
+            .finish()
+        }
+      }
+
+      // Safety: the pointers are only accessed through the list, which requires `&mut` to modify
+      unsafe impl Send for $link {}
+      unsafe impl Sync for $link {}
+    )*
+  };
+}
+
+impl_link_common!(SinglyLink, DoublyLink);
+
+/// Converts between a node and the [Link] embedded in it. Usually implemented with
+/// [intrusive_adapter](crate::intrusive_adapter).
+///
+/// Safety: `node` must return the node that `link` was given, for every node
+pub unsafe trait Adapter<T> {
+  type Link: Link;
+
+  fn link(node: NonNull<T>) -> NonNull<Self::Link>;
+
+  /// Safety: `link` must be embedded in a `T`
+  unsafe fn node(link: NonNull<Self::Link>) -> NonNull<T>;
+}
+
+/// Declares an [Adapter](crate::types::intrusive::Adapter) for a link field of a node.
+///
+/// ```ignore
+/// struct Waiter {
+///   link: DoublyLink,
+///   thread: u32,
+/// }
+///
+/// intrusive_adapter!(pub WaiterAdapter = Waiter { link: DoublyLink });
+/// ```
+#[macro_export]
+macro_rules! intrusive_adapter {
+  ($vis:vis $name:ident = $node:ty { $field:ident: $link:ty }) => {
+    $vis struct $name;
+
+    unsafe impl $crate::types::intrusive::Adapter<$node> for $name {
+      type Link = $link;
+
+      fn link(node: ::core::ptr::NonNull<$node>) -> ::core::ptr::NonNull<$link> {
+        // Safety: a field of a non-null node is non-null
+        unsafe { ::core::ptr::NonNull::new_unchecked(&raw mut (*node.as_ptr()).$field) }
+      }
+
+      unsafe fn node(link: ::core::ptr::NonNull<$link>) -> ::core::ptr::NonNull<$node> {
+        // Safety: the link is embedded in a node, at the field's offset
+        unsafe {
+          link
+            .byte_sub(::core::mem::offset_of!($node, $field))
+            .cast()
+        }
+      }
+    }
+  };
+}
+
+/// A linked list of pinned nodes, which are linked through the `A::Link` embedded in them rather
+/// than owned by the list.
+///
+/// The list doesn't keep track of its length, so nodes can be removed from it without knowing
+/// anything about the list other than its ends.
+pub struct List<T, A: Adapter<T>> {
+  head: Option<NonNull<A::Link>>,
+  tail: Option<NonNull<A::Link>>,
+  phantom: PhantomData<(NonNull<T>, A)>,
+}
+
+// Safety: the list only hands out access to nodes like a collection of `T` would
+unsafe impl<T: Send, A: Adapter<T>> Send for List<T, A> {}
+unsafe impl<T: Sync, A: Adapter<T>> Sync for List<T, A> {}
+
+impl<T, A: Adapter<T>> List<T, A> {
+  pub const fn new() -> Self {
+    Self {
+      head: None,
+      tail: None,
+      phantom: PhantomData,
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.head.is_none()
+  }
+
+  /// Counts the nodes in the list, which takes linear time.
+  pub fn len(&self) -> usize {
+    self.iter().count()
+  }
+
+  fn link_of(node: Pin<&mut T>) -> NonNull<A::Link> {
+    // Safety: the node is never moved out of
+    A::link(NonNull::from(unsafe { Pin::get_unchecked_mut(node) }))
+  }
+
+  /// Safety: `link` must be linked into this list
+  unsafe fn node_ref<'b>(link: NonNull<A::Link>) -> Pin<&'b T> {
+    // Safety: linked nodes are pinned and live until they're removed
+    unsafe { Pin::new_unchecked(A::node(link).as_ref()) }
+  }
+
+  /// Safety: `link` must be linked into this list
+  unsafe fn node_mut<'b>(link: NonNull<A::Link>) -> Pin<&'b mut T> {
+    // Safety: linked nodes are pinned and only accessed through the list
+    unsafe { Pin::new_unchecked(A::node(link).as_mut()) }
+  }
+
+  /// Safety: `link` must be linked into this list
+  unsafe fn prev_of(&self, link: NonNull<A::Link>) -> Option<NonNull<A::Link>> {
+    if A::Link::DOUBLY {
+      // Safety: linked into this list, so it's alive
+      return unsafe { link.as_ref() }.prev();
+    }
+
+    let mut current = self.head?;
+    loop {
+      // Safety: every link reachable from the head is alive
+      let next = unsafe { current.as_ref() }.next();
+      if next == Some(link) {
+        return Some(current);
+      }
+
+      current = next?;
+    }
+  }
+
+  /// Links `link` after `prev`, or at the front if `prev` is [None].
+  ///
+  /// Safety: `prev` must be linked into this list, and `link` must be alive and pinned
+  unsafe fn link_after(&mut self, prev: Option<NonNull<A::Link>>, link: NonNull<A::Link>) {
+    // Safety: alive as required by the caller
+    let link_ref = unsafe { link.as_ref() };
+    assert!(!link_ref.is_linked(), "node is already linked into a list");
+
+    // Safety: prev is linked into this list
+    let next = match prev {
+      Some(prev) => unsafe { prev.as_ref() }.next(),
+      None => self.head,
+    };
+
+    link_ref.set_prev(prev);
+    link_ref.set_next(next);
+    link_ref.set_linked(true);
+
+    // Safety: both neighbours are linked into this list
+    match prev {
+      Some(prev) => unsafe { prev.as_ref() }.set_next(Some(link)),
+      None => self.head = Some(link),
+    }
+    match next {
+      Some(next) => unsafe { next.as_ref() }.set_prev(Some(link)),
+      None => self.tail = Some(link),
+    }
+  }
+
+  /// Safety: `link` must be linked into this list
+  unsafe fn unlink(&mut self, link: NonNull<A::Link>) {
+    // Safety: linked into this list
+    let prev = unsafe { self.prev_of(link) };
+    let link_ref = unsafe { link.as_ref() };
+    let next = link_ref.next();
+
+    // Safety: both neighbours are linked into this list
+    match prev {
+      Some(prev) => unsafe { prev.as_ref() }.set_next(next),
+      None => self.head = next,
+    }
+    match next {
+      Some(next) => unsafe { next.as_ref() }.set_prev(prev),
+      None => self.tail = prev,
+    }
+
+    link_ref.set_prev(None);
+    link_ref.set_next(None);
+    link_ref.set_linked(false);
+  }
+
+  pub fn front(&self) -> Option<Pin<&T>> {
+    // Safety: the head is linked into this list
+    self.head.map(|link| unsafe { Self::node_ref(link) })
+  }
+
+  pub fn back(&self) -> Option<Pin<&T>> {
+    // Safety: the tail is linked into this list
+    self.tail.map(|link| unsafe { Self::node_ref(link) })
+  }
+
+  pub fn front_mut(&mut self) -> Option<Pin<&mut T>> {
+    // Safety: the head is linked into this list
+    self.head.map(|link| unsafe { Self::node_mut(link) })
+  }
+
+  pub fn back_mut(&mut self) -> Option<Pin<&mut T>> {
+    // Safety: the tail is linked into this list
+    self.tail.map(|link| unsafe { Self::node_mut(link) })
+  }
+
+  /// Links `node` at the front of the list.
+  ///
+  /// Panics if the node is already linked into a list.
+  ///
+  /// Safety: until the node is removed, it must only be accessed through this list, or by handing
+  /// it to [List::remove] on this list
+  pub unsafe fn push_front(&mut self, node: Pin<&mut T>) {
+    // Safety: the node is pinned and alive
+    unsafe { self.link_after(None, Self::link_of(node)) };
+  }
+
+  /// Links `node` at the back of the list.
+  ///
+  /// Panics if the node is already linked into a list.
+  ///
+  /// Safety: same as [List::push_front]
+  pub unsafe fn push_back(&mut self, node: Pin<&mut T>) {
+    // Safety: the tail is linked into this list, and the node is pinned and alive
+    unsafe { self.link_after(self.tail, Self::link_of(node)) };
+  }
+
+  pub fn pop_front(&mut self) -> Option<Pin<&mut T>> {
+    let link = self.head?;
+    // Safety: the head is linked into this list, and stays alive after being unlinked
+    unsafe {
+      self.unlink(link);
+      Some(Self::node_mut(link))
+    }
+  }
+
+  /// Takes linear time for singly linked lists.
+  pub fn pop_back(&mut self) -> Option<Pin<&mut T>> {
+    let link = self.tail?;
+    // Safety: the tail is linked into this list, and stays alive after being unlinked
+    unsafe {
+      self.unlink(link);
+      Some(Self::node_mut(link))
+    }
+  }
+
+  /// Unlinks `node` from the list, returning whether it was linked. Takes linear time for singly
+  /// linked lists.
+  pub fn remove(&mut self, node: Pin<&mut T>) -> bool {
+    let link = Self::link_of(node);
+    // Safety: the node is borrowed, so it's alive
+    let link_ref = unsafe { link.as_ref() };
+    if !link_ref.is_linked() {
+      return false;
+    }
+
+    // the contract of `push_front` means a linked node given to us has to be in this list
+    debug_assert!(link_ref.next().is_some() || self.tail == Some(link));
+    debug_assert!(!A::Link::DOUBLY || link_ref.prev().is_some() || self.head == Some(link));
+
+    // Safety: linked, so linked into this list
+    unsafe { self.unlink(link) };
+
+    true
+  }
+
+  /// Unlinks every node from the list.
+  pub fn clear(&mut self) {
+    while self.pop_front().is_some() {}
+  }
+
+  pub fn iter(&self) -> Iter<'_, T, A> {
+    Iter {
+      next: self.head,
+      phantom: PhantomData,
+    }
+  }
+
+  /// Returns a cursor at the front of the list, or at the "ghost" position past its end if the
+  /// list is empty.
+  pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+    Cursor {
+      list: self,
+      current: self.head,
+    }
+  }
+
+  pub fn cursor_back(&self) -> Cursor<'_, T, A> {
+    Cursor {
+      list: self,
+      current: self.tail,
+    }
+  }
+
+  pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+    CursorMut {
+      current: self.head,
+      list: self,
+    }
+  }
+
+  pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A> {
+    CursorMut {
+      current: self.tail,
+      list: self,
+    }
+  }
+}
+
+impl<T, A: Adapter<T>> Default for List<T, A> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T, A: Adapter<T>> Drop for List<T, A> {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl<T: Debug, A: Adapter<T>> Debug for List<T, A> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<'b, T, A: Adapter<T>> IntoIterator for &'b List<T, A> {
+  type Item = Pin<&'b T>;
+  type IntoIter = Iter<'b, T, A>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+pub struct Iter<'b, T, A: Adapter<T>> {
+  next: Option<NonNull<A::Link>>,
+  phantom: PhantomData<&'b List<T, A>>,
+}
+
+impl<'b, T, A: Adapter<T>> Iterator for Iter<'b, T, A> {
+  type Item = Pin<&'b T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let link = self.next?;
+    // Safety: reachable from the head of the borrowed list, so linked into it
+    unsafe {
+      self.next = link.as_ref().next();
+      Some(List::<T, A>::node_ref(link))
+    }
+  }
+}
+
+impl<'b, T, A: Adapter<T>> FusedIterator for Iter<'b, T, A> {}
+
+/// A position in a [List], which can also be the "ghost" position between its back and front.
+pub struct Cursor<'b, T, A: Adapter<T>> {
+  list: &'b List<T, A>,
+  current: Option<NonNull<A::Link>>,
+}
+
+impl<'b, T, A: Adapter<T>> Cursor<'b, T, A> {
+  /// Returns the node at the cursor, or [None] at the ghost position.
+  pub fn current(&self) -> Option<Pin<&'b T>> {
+    // Safety: the cursor only points at nodes linked into the list
+    self
+      .current
+      .map(|link| unsafe { List::<T, A>::node_ref(link) })
+  }
+
+  /// Moves to the next node, wrapping through the ghost position.
+  pub fn move_next(&mut self) {
+    self.current = match self.current {
+      // Safety: linked into the list
+      Some(link) => unsafe { link.as_ref() }.next(),
+      None => self.list.head,
+    };
+  }
+
+  /// Moves to the previous node, wrapping through the ghost position. Takes linear time for
+  /// singly linked lists.
+  pub fn move_prev(&mut self) {
+    self.current = match self.current {
+      // Safety: linked into the list
+      Some(link) => unsafe { self.list.prev_of(link) },
+      None => self.list.tail,
+    };
+  }
+}
+
+impl<'b, T, A: Adapter<T>> Clone for Cursor<'b, T, A> {
+  fn clone(&self) -> Self {
+    Self {
+      list: self.list,
+      current: self.current,
+    }
+  }
+}
+
+/// A [Cursor] that can also link and unlink nodes.
+pub struct CursorMut<'b, T, A: Adapter<T>> {
+  list: &'b mut List<T, A>,
+  current: Option<NonNull<A::Link>>,
+}
+
+impl<'b, T, A: Adapter<T>> CursorMut<'b, T, A> {
+  pub fn current(&mut self) -> Option<Pin<&mut T>> {
+    // Safety: the cursor only points at nodes linked into the list
+    self
+      .current
+      .map(|link| unsafe { List::<T, A>::node_mut(link) })
+  }
+
+  pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+    Cursor {
+      list: self.list,
+      current: self.current,
+    }
+  }
+
+  pub fn move_next(&mut self) {
+    self.current = match self.current {
+      // Safety: linked into the list
+      Some(link) => unsafe { link.as_ref() }.next(),
+      None => self.list.head,
+    };
+  }
+
+  /// Takes linear time for singly linked lists.
+  pub fn move_prev(&mut self) {
+    self.current = match self.current {
+      // Safety: linked into the list
+      Some(link) => unsafe { self.list.prev_of(link) },
+      None => self.list.tail,
+    };
+  }
+
+  /// Unlinks the node at the cursor and moves to the next one. Takes linear time for singly
+  /// linked lists.
+  pub fn remove_current(&mut self) -> Option<Pin<&mut T>> {
+    let link = self.current?;
+    // Safety: linked into the list, and stays alive after being unlinked
+    unsafe {
+      self.current = link.as_ref().next();
+      self.list.unlink(link);
+      Some(List::<T, A>::node_mut(link))
+    }
+  }
+
+  /// Links `node` after the cursor, or at the front of the list at the ghost position.
+  ///
+  /// Safety: same as [List::push_front]
+  pub unsafe fn insert_after(&mut self, node: Pin<&mut T>) {
+    // Safety: the current link is linked into the list, and the node is pinned and alive
+    unsafe {
+      self
+        .list
+        .link_after(self.current, List::<T, A>::link_of(node))
+    };
+  }
+
+  /// Links `node` before the cursor, or at the back of the list at the ghost position. Takes
+  /// linear time for singly linked lists.
+  ///
+  /// Safety: same as [List::push_front]
+  pub unsafe fn insert_before(&mut self, node: Pin<&mut T>) {
+    // Safety: the current link and its previous one are linked into the list
+    unsafe {
+      let prev = match self.current {
+        Some(link) => self.list.prev_of(link),
+        None => self.list.tail,
+      };
+      self.list.link_after(prev, List::<T, A>::link_of(node));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::pin::pin;
+
+  use crate::types::intrusive::{DoublyLink, Link, List, SinglyLink};
+
+  struct Waiter {
+    id: u32,
+    doubly: DoublyLink,
+    singly: SinglyLink,
+  }
+
+  impl Waiter {
+    fn new(id: u32) -> Self {
+      Self {
+        id,
+        doubly: DoublyLink::new(),
+        singly: SinglyLink::new(),
+      }
+    }
+  }
+
+  crate::intrusive_adapter!(DoublyAdapter = Waiter { doubly: DoublyLink });
+  crate::intrusive_adapter!(SinglyAdapter = Waiter { singly: SinglyLink });
+
+  fn ids<A: crate::types::intrusive::Adapter<Waiter>>(list: &List<Waiter, A>) -> [u32; 4] {
+    let mut ids = [0; 4];
+    for (id, waiter) in ids.iter_mut().zip(list) {
+      *id = waiter.id;
+    }
+    ids
+  }
+
+  #[test]
+  fn doubly() {
+    let mut a = pin!(Waiter::new(1));
+    let mut b = pin!(Waiter::new(2));
+    let mut c = pin!(Waiter::new(3));
+    let mut list = List::<Waiter, DoublyAdapter>::new();
+
+    unsafe {
+      list.push_back(a.as_mut());
+      list.push_back(b.as_mut());
+      list.push_front(c.as_mut());
+    }
+    assert_eq!(ids(&list), [3, 1, 2, 0]);
+    assert!(b.doubly.is_linked());
+
+    assert!(list.remove(a.as_mut()));
+    assert!(!list.remove(a.as_mut()));
+    assert_eq!(ids(&list), [3, 2, 0, 0]);
+
+    let mut cursor = list.cursor_back_mut();
+    assert_eq!(cursor.current().unwrap().id, 2);
+    cursor.move_next();
+    assert!(cursor.current().is_none());
+    unsafe { cursor.insert_before(a.as_mut()) };
+    assert_eq!(ids(&list), [3, 2, 1, 0]);
+
+    assert_eq!(list.pop_back().unwrap().id, 1);
+    assert_eq!(list.pop_front().unwrap().id, 3);
+    assert_eq!(list.len(), 1);
+  }
+
+  #[test]
+  fn singly() {
+    let mut a = pin!(Waiter::new(1));
+    let mut b = pin!(Waiter::new(2));
+    let mut c = pin!(Waiter::new(3));
+    let mut list = List::<Waiter, SinglyAdapter>::new();
+
+    unsafe {
+      list.push_back(a.as_mut());
+      list.push_back(b.as_mut());
+      list.push_back(c.as_mut());
+    }
+
+    assert!(list.remove(b.as_mut()));
+    assert_eq!(ids(&list), [1, 3, 0, 0]);
+    assert_eq!(list.back().unwrap().id, 3);
+
+    let mut cursor = list.cursor_front_mut();
+    assert_eq!(cursor.remove_current().unwrap().id, 1);
+    assert_eq!(cursor.current().unwrap().id, 3);
+    unsafe { cursor.insert_after(b.as_mut()) };
+    assert_eq!(ids(&list), [3, 2, 0, 0]);
+    assert_eq!(list.pop_back().unwrap().id, 2);
+  }
+}
@@ -1 +1,10 @@
+pub mod bitset;
+mod cow;
+pub mod deque;
+pub mod heap;
+pub mod intrusive;
+pub mod path;
+pub mod slice;
 pub mod vec;
+
+pub use cow::*;
@@ -0,0 +1,763 @@
+use core::{
+  ffi::CStr,
+  fmt::{self, Debug, Display},
+  hash::{Hash, Hasher},
+  iter::FusedIterator,
+  ops::{Deref, DerefMut},
+  slice,
+};
+
+use thiserror::Error;
+
+use crate::{
+  alloc::{
+    GrowthStrategy, SliceAllocator, SliceDst, UnsizedMaybeUninit, strategy::Strategy,
+    types::vec::Vec,
+  },
+  types::vec::SliceVec,
+};
+
+const SEPARATOR: u8 = b'/';
+
+/// A string of bytes as the platform uses them, like file names, which doesn't have to be utf-8.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct OsStr([u8]);
+
+impl OsStr {
+  pub fn new<T: AsRef<OsStr> + ?Sized>(value: &T) -> &Self {
+    value.as_ref()
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> &Self {
+    // Safety: OsStr is a transparent wrapper around [u8]
+    unsafe { &*(bytes as *const [u8] as *const Self) }
+  }
+
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.0
+  }
+
+  /// Returns the string as a `str`, if it's valid utf-8.
+  pub fn to_str(&self) -> Option<&str> {
+    str::from_utf8(&self.0).ok()
+  }
+
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Copies the string into `buf` with a nul terminator, to be passed to syscalls.
+  pub fn to_c_str<'a>(&self, buf: &'a mut [u8]) -> Result<&'a CStr, CStrError> {
+    if self.0.contains(&0) {
+      return Err(CStrError::InteriorNul);
+    }
+    let Some(buf) = buf.get_mut(..self.0.len() + 1) else {
+      return Err(CStrError::TooLong);
+    };
+
+    let (last, bytes) = buf
+      .split_last_mut()
+      .expect("buf holds at least the nul terminator");
+    bytes.copy_from_slice(&self.0);
+    *last = 0;
+    Ok(CStr::from_bytes_with_nul(buf).expect("nul terminator was just written"))
+  }
+}
+
+impl Debug for OsStr {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "\"{}\"", self.0.escape_ascii())
+  }
+}
+
+/// Writes the string as utf-8, replacing invalid sequences with `U+FFFD`.
+impl Display for OsStr {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for chunk in self.0.utf8_chunks() {
+      f.write_str(chunk.valid())?;
+      if !chunk.invalid().is_empty() {
+        f.write_str(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]))?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl AsRef<OsStr> for OsStr {
+  fn as_ref(&self) -> &OsStr {
+    self
+  }
+}
+
+impl AsRef<OsStr> for str {
+  fn as_ref(&self) -> &OsStr {
+    OsStr::from_bytes(self.as_bytes())
+  }
+}
+
+impl AsRef<OsStr> for [u8] {
+  fn as_ref(&self) -> &OsStr {
+    OsStr::from_bytes(self)
+  }
+}
+
+impl AsRef<OsStr> for Path {
+  fn as_ref(&self) -> &OsStr {
+    &self.0
+  }
+}
+
+#[derive(Error, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CStrError {
+  #[error("string contains a nul byte")]
+  InteriorNul,
+  #[error("buffer is too small for the string and its nul terminator")]
+  TooLong,
+}
+
+/// A borrowed Unix path. Paths are compared and hashed by their [components](Path::components),
+/// so `a//b/` and `a/./b` are equal.
+#[repr(transparent)]
+pub struct Path(OsStr);
+
+impl Path {
+  pub fn new<T: AsRef<OsStr> + ?Sized>(value: &T) -> &Self {
+    // Safety: Path is a transparent wrapper around OsStr
+    unsafe { &*(value.as_ref() as *const OsStr as *const Self) }
+  }
+
+  pub fn as_os_str(&self) -> &OsStr {
+    &self.0
+  }
+
+  pub fn as_bytes(&self) -> &[u8] {
+    self.0.as_bytes()
+  }
+
+  pub fn to_str(&self) -> Option<&str> {
+    self.0.to_str()
+  }
+
+  pub fn is_absolute(&self) -> bool {
+    self.as_bytes().first() == Some(&SEPARATOR)
+  }
+
+  pub fn is_relative(&self) -> bool {
+    !self.is_absolute()
+  }
+
+  pub fn components(&self) -> Components<'_> {
+    Components::new(self.as_bytes())
+  }
+
+  /// Returns the path without its last component, or `None` if it ends at the root or is empty.
+  pub fn parent(&self) -> Option<&Path> {
+    let mut components = self.components();
+    match components.next_back()? {
+      Component::Normal(_) | Component::CurDir | Component::ParentDir => Some(components.as_path()),
+      Component::RootDir => None,
+    }
+  }
+
+  /// Returns the last component, unless it's `..`.
+  pub fn file_name(&self) -> Option<&OsStr> {
+    match self.components().next_back()? {
+      Component::Normal(name) => Some(name),
+      _ => None,
+    }
+  }
+
+  /// Returns the file name without its extension.
+  pub fn file_stem(&self) -> Option<&OsStr> {
+    let name = self.file_name()?;
+    Some(split_extension(name).0)
+  }
+
+  /// Returns what follows the last `.` of the file name, unless the name starts with it.
+  pub fn extension(&self) -> Option<&OsStr> {
+    split_extension(self.file_name()?).1
+  }
+
+  pub fn starts_with<T: AsRef<Path> + ?Sized>(&self, base: &T) -> bool {
+    let mut components = self.components();
+    base
+      .as_ref()
+      .components()
+      .all(|component| components.next() == Some(component))
+  }
+
+  /// Copies the path into `buf` with a nul terminator, to be passed to syscalls.
+  pub fn to_c_str<'a>(&self, buf: &'a mut [u8]) -> Result<&'a CStr, CStrError> {
+    self.0.to_c_str(buf)
+  }
+
+  /// Copies the path into a new [PathBuf] and pushes `path` onto it.
+  pub async fn join<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>, T: AsRef<Path> + ?Sized>(
+    &self,
+    path: &T,
+    allocator: &'a A,
+  ) -> Result<PathBuf<'a, S, A>, A::Error>
+  where
+    S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+  {
+    let path = path.as_ref();
+    let mut joined = PathBuf::with_capacity(
+      allocator,
+      GrowthStrategy::Exponential,
+      self.as_bytes().len() + 1 + path.as_bytes().len(),
+    )
+    .await?;
+    joined.push(self).await?;
+    joined.push(path).await?;
+
+    Ok(joined)
+  }
+}
+
+fn split_extension(name: &OsStr) -> (&OsStr, Option<&OsStr>) {
+  let bytes = name.as_bytes();
+  match bytes.iter().rposition(|&byte| byte == b'.') {
+    Some(0) | None => (name, None),
+    Some(dot) => (
+      OsStr::from_bytes(&bytes[..dot]),
+      Some(OsStr::from_bytes(&bytes[dot + 1..])),
+    ),
+  }
+}
+
+impl PartialEq for Path {
+  fn eq(&self, other: &Self) -> bool {
+    self.components().eq(other.components())
+  }
+}
+
+impl Eq for Path {}
+
+impl Hash for Path {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    for component in self.components() {
+      component.hash(state);
+    }
+  }
+}
+
+impl Debug for Path {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    Debug::fmt(&self.0, f)
+  }
+}
+
+impl Display for Path {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    Display::fmt(&self.0, f)
+  }
+}
+
+impl AsRef<Path> for Path {
+  fn as_ref(&self) -> &Path {
+    self
+  }
+}
+
+impl AsRef<Path> for OsStr {
+  fn as_ref(&self) -> &Path {
+    Path::new(self)
+  }
+}
+
+impl AsRef<Path> for str {
+  fn as_ref(&self) -> &Path {
+    Path::new(self)
+  }
+}
+
+impl AsRef<Path> for [u8] {
+  fn as_ref(&self) -> &Path {
+    Path::new(self)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Component<'a> {
+  /// The `/` an absolute path starts with.
+  RootDir,
+  /// A `.` at the start of a relative path. Any other `.` is skipped.
+  CurDir,
+  ParentDir,
+  Normal(&'a OsStr),
+}
+
+impl<'a> Component<'a> {
+  pub fn as_os_str(self) -> &'a OsStr {
+    match self {
+      Component::RootDir => OsStr::new("/"),
+      Component::CurDir => OsStr::new("."),
+      Component::ParentDir => OsStr::new(".."),
+      Component::Normal(name) => name,
+    }
+  }
+}
+
+/// The components of a [Path], skipping repeated separators and `.` past the start of the path.
+#[derive(Clone)]
+pub struct Components<'a> {
+  path: &'a [u8],
+  front: usize,
+  back: usize,
+  root: bool,
+  current: bool,
+}
+
+impl<'a> Components<'a> {
+  fn new(path: &'a [u8]) -> Self {
+    let root = path.first() == Some(&SEPARATOR);
+    let current = !root && (path == b"." || path.starts_with(b"./"));
+    Self {
+      path,
+      front: usize::from(root || current),
+      back: path.len(),
+      root,
+      current,
+    }
+  }
+
+  /// Returns the path made of the components that haven't been returned yet.
+  pub fn as_path(&self) -> &'a Path {
+    let start = match self.root || self.current {
+      true => 0,
+      false => self.front,
+    };
+    let mut end = self.back.max(start);
+    while end > start + usize::from(self.root) && self.path[end - 1] == SEPARATOR {
+      end -= 1;
+    }
+
+    Path::new(&self.path[start..end])
+  }
+
+  fn parse(name: &'a [u8]) -> Option<Component<'a>> {
+    match name {
+      b"" | b"." => None,
+      b".." => Some(Component::ParentDir),
+      name => Some(Component::Normal(OsStr::from_bytes(name))),
+    }
+  }
+}
+
+impl<'a> Iterator for Components<'a> {
+  type Item = Component<'a>;
+
+  fn next(&mut self) -> Option<Component<'a>> {
+    if self.root {
+      self.root = false;
+      return Some(Component::RootDir);
+    }
+    if self.current {
+      self.current = false;
+      return Some(Component::CurDir);
+    }
+
+    while self.front < self.back {
+      let rest = &self.path[self.front..self.back];
+      let length = rest
+        .iter()
+        .position(|&byte| byte == SEPARATOR)
+        .unwrap_or(rest.len());
+      self.front += (length + 1).min(rest.len());
+      if let Some(component) = Self::parse(&rest[..length]) {
+        return Some(component);
+      }
+    }
+
+    None
+  }
+}
+
+impl<'a> DoubleEndedIterator for Components<'a> {
+  fn next_back(&mut self) -> Option<Component<'a>> {
+    while self.front < self.back {
+      let rest = &self.path[self.front..self.back];
+      let start = rest
+        .iter()
+        .rposition(|&byte| byte == SEPARATOR)
+        .map_or(0, |separator| separator + 1);
+      self.back = self.front + start.saturating_sub(1);
+      if let Some(component) = Self::parse(&rest[start..]) {
+        return Some(component);
+      }
+    }
+
+    if self.current {
+      self.current = false;
+      return Some(Component::CurDir);
+    }
+    if self.root {
+      self.root = false;
+      return Some(Component::RootDir);
+    }
+
+    None
+  }
+}
+
+impl FusedIterator for Components<'_> {}
+
+/// An owned, growable [Path], stored in a [Vec].
+pub struct PathBuf<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>>
+where
+  S::Handle<'a, SliceVec<u8>>: Deref<Target = SliceVec<u8>>,
+{
+  inner: Vec<'a, u8, S, A>,
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>> PathBuf<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: Deref<Target = SliceVec<u8>>,
+{
+  pub async fn new(allocator: &'a A, strategy: GrowthStrategy) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+  {
+    Ok(Self {
+      inner: Vec::new(allocator, strategy).await?,
+    })
+  }
+
+  pub async fn with_capacity(
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+    capacity: usize,
+  ) -> Result<Self, A::Error>
+  where
+    S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+      DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+    S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+  {
+    Ok(Self {
+      inner: Vec::with_capacity(allocator, strategy, capacity).await?,
+    })
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>> PathBuf<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+  S::UninitHandle<'a, UnsizedMaybeUninit<SliceVec<u8>>>:
+    DerefMut<Target = UnsizedMaybeUninit<SliceVec<u8>>>,
+  S::Data<'a, UnsizedMaybeUninit<SliceVec<u8>>>: SliceDst,
+{
+  pub async fn from_path<T: AsRef<Path> + ?Sized>(
+    path: &T,
+    allocator: &'a A,
+    strategy: GrowthStrategy,
+  ) -> Result<Self, A::Error> {
+    let bytes = path.as_ref().as_bytes();
+    let mut path = Self::with_capacity(allocator, strategy, bytes.len()).await?;
+    path.inner.extend(bytes.iter().copied()).await?;
+
+    Ok(path)
+  }
+
+  pub fn as_path(&self) -> &Path {
+    Path::new(&**self.inner)
+  }
+
+  /// Appends `path`, with a separator if needed. An absolute `path` replaces the whole path.
+  pub async fn push<T: AsRef<Path> + ?Sized>(&mut self, path: &T) -> Result<(), A::Error> {
+    let path = path.as_ref();
+    if path.is_absolute() {
+      self.inner.clear();
+    } else if self.inner.last().is_some_and(|&byte| byte != SEPARATOR) {
+      self.inner.push_resize(SEPARATOR).await?;
+    }
+
+    self.inner.extend(path.as_bytes().iter().copied()).await
+  }
+
+  /// Truncates the path to its [parent](Path::parent). Returns `false` if there's no parent.
+  pub fn pop(&mut self) -> bool {
+    let Some(length) = self
+      .as_path()
+      .parent()
+      .map(|parent| parent.as_bytes().len())
+    else {
+      return false;
+    };
+
+    self.inner.truncate(length);
+    true
+  }
+
+  /// Replaces the file name, or pushes `name` if there's none.
+  pub async fn set_file_name<T: AsRef<OsStr> + ?Sized>(
+    &mut self,
+    name: &T,
+  ) -> Result<(), A::Error> {
+    if self.as_path().file_name().is_some() {
+      self.pop();
+    }
+
+    self.push(Path::new(name)).await
+  }
+
+  /// Replaces the extension, or removes it if `extension` is empty. Returns `false` without
+  /// changing anything if there's no file name.
+  pub async fn set_extension<T: AsRef<OsStr> + ?Sized>(
+    &mut self,
+    extension: &T,
+  ) -> Result<bool, A::Error> {
+    let Some(stem) = self.as_path().file_stem() else {
+      return Ok(false);
+    };
+
+    let stem_end = stem.as_bytes().as_ptr_range().end.addr() - self.inner.as_ptr().addr();
+    self.inner.truncate(stem_end);
+    let extension = extension.as_ref().as_bytes();
+    if !extension.is_empty() {
+      self.inner.push_resize(b'.').await?;
+      self.inner.extend(extension.iter().copied()).await?;
+    }
+
+    Ok(true)
+  }
+
+  /// Normalizes the path lexically, without looking at the filesystem: separators are collapsed,
+  /// `.` is removed, and `..` removes the component before it. `..` is kept at the start of a
+  /// relative path, and dropped at the root. An empty result becomes `.`.
+  ///
+  /// This can change what the path points to when a component before a `..` is a symlink.
+  pub async fn normalize(&mut self) -> Result<(), A::Error> {
+    let bytes: &mut [u8] = &mut self.inner;
+    let root = usize::from(bytes.first() == Some(&SEPARATOR));
+    let (mut read, mut write) = (root, root);
+    let mut normal_components = 0;
+
+    while read < bytes.len() {
+      let length = bytes[read..]
+        .iter()
+        .position(|&byte| byte == SEPARATOR)
+        .unwrap_or(bytes.len() - read);
+      let component = read..read + length;
+      read += length + 1;
+
+      // `Some(true)` for a normal component, and `Some(false)` for `..`
+      let normal = match &bytes[component.clone()] {
+        b"" | b"." => None,
+        b".." => Some(false),
+        _ => Some(true),
+      };
+      match normal {
+        None => {}
+        Some(false) if normal_components > 0 => {
+          write = bytes[root..write]
+            .iter()
+            .rposition(|&byte| byte == SEPARATOR)
+            .map_or(root, |separator| root + separator);
+          normal_components -= 1;
+        }
+        Some(false) if root == 1 => {}
+        Some(normal) => {
+          if write > root {
+            bytes[write] = SEPARATOR;
+            write += 1;
+          }
+          // the written path is never longer than what's been read, so this never overwrites
+          // unread components
+          bytes.copy_within(component, write);
+          write += length;
+          normal_components += usize::from(normal);
+        }
+      }
+    }
+
+    self.inner.truncate(write);
+    if write == 0 {
+      self.inner.push_resize(b'.').await?;
+    }
+
+    Ok(())
+  }
+
+  /// Returns the path as a `CStr` for syscalls, by writing a nul terminator past its end.
+  pub async fn to_c_str(&mut self) -> Result<&CStr, ToCStrError<'a, A>> {
+    if self.inner.contains(&0) {
+      return Err(ToCStrError::InteriorNul);
+    }
+    if self.inner.is_full() {
+      self.inner.grow(1).await.map_err(ToCStrError::Allocator)?;
+    }
+
+    let length = self.inner.len();
+    self.inner.spare_capacity_mut()[0].write(0);
+    // Safety: the first `length` bytes are initialized, and the nul was just written after them
+    let bytes = unsafe { slice::from_raw_parts(self.inner.as_ptr(), length + 1) };
+    Ok(CStr::from_bytes_with_nul(bytes).expect("path has no interior nul"))
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>> Deref for PathBuf<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+{
+  type Target = Path;
+
+  fn deref(&self) -> &Path {
+    Path::new(&**self.inner)
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>> AsRef<Path> for PathBuf<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+{
+  fn as_ref(&self) -> &Path {
+    self
+  }
+}
+
+impl<'a, S: Strategy, A: SliceAllocator<'a, SliceVec<u8>>> Debug for PathBuf<'a, S, A>
+where
+  S::Handle<'a, SliceVec<u8>>: DerefMut<Target = SliceVec<u8>>,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    Debug::fmt(&**self, f)
+  }
+}
+
+#[derive(Error)]
+pub enum ToCStrError<'a, A: SliceAllocator<'a, SliceVec<u8>>> {
+  #[error("{0}")]
+  Allocator(A::Error),
+  #[error("path contains a nul byte")]
+  InteriorNul,
+}
+
+impl<'a, A: SliceAllocator<'a, SliceVec<u8>>> Debug for ToCStrError<'a, A> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Allocator(arg0) => f.debug_tuple("Allocator").field(arg0).finish(),
+      Self::InteriorNul => f.write_str("InteriorNul"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    alloc::{ForeignAllocator, GrowthStrategy, StdAlloc, strategy::UniqueStrategy},
+    types::path::{CStrError, Component, OsStr, Path, PathBuf},
+  };
+
+  #[test]
+  fn components() {
+    let path = Path::new("/usr//lib/./rustlib/");
+    assert!(path.components().eq([
+      Component::RootDir,
+      Component::Normal(OsStr::new("usr")),
+      Component::Normal(OsStr::new("lib")),
+      Component::Normal(OsStr::new("rustlib")),
+    ]));
+    assert!(path.components().rev().eq([
+      Component::Normal(OsStr::new("rustlib")),
+      Component::Normal(OsStr::new("lib")),
+      Component::Normal(OsStr::new("usr")),
+      Component::RootDir,
+    ]));
+
+    let path = Path::new("./../a");
+    assert!(path.components().eq([
+      Component::CurDir,
+      Component::ParentDir,
+      Component::Normal(OsStr::new("a")),
+    ]));
+    assert_eq!(
+      path.components().next_back(),
+      Some(Component::Normal(OsStr::new("a")))
+    );
+
+    assert_eq!(Path::new("a/b/"), Path::new("a//./b"));
+    assert!(Path::new("/a/b/c").starts_with("/a/b"));
+    assert!(!Path::new("/a/bc").starts_with("/a/b"));
+  }
+
+  #[test]
+  fn parts() {
+    let path = Path::new("/home/user/archive.tar.gz");
+    assert_eq!(path.parent(), Some(Path::new("/home/user")));
+    assert_eq!(path.file_name(), Some(OsStr::new("archive.tar.gz")));
+    assert_eq!(path.file_stem(), Some(OsStr::new("archive.tar")));
+    assert_eq!(path.extension(), Some(OsStr::new("gz")));
+
+    assert_eq!(Path::new("/a").parent().unwrap().as_bytes(), b"/");
+    assert_eq!(Path::new("a").parent().unwrap().as_bytes(), b"");
+    assert_eq!(Path::new("/").parent(), None);
+    assert_eq!(Path::new(".bashrc").extension(), None);
+    assert_eq!(Path::new("a/..").file_name(), None);
+
+    let mut buf = [0; 8];
+    assert_eq!(Path::new("/tmp").to_c_str(&mut buf), Ok(c"/tmp"));
+    assert_eq!(
+      Path::new("/tmp/long").to_c_str(&mut buf),
+      Err(CStrError::TooLong)
+    );
+    assert_eq!(
+      Path::new(&b"a\0b"[..]).to_c_str(&mut buf),
+      Err(CStrError::InteriorNul)
+    );
+  }
+
+  #[pollster::test]
+  async fn path_buf() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut path = Path::new("/usr")
+      .join::<UniqueStrategy, _, _>("lib", &allocator)
+      .await
+      .unwrap();
+    assert_eq!(path.as_bytes(), b"/usr/lib");
+
+    path.push("/etc/").await.unwrap();
+    path.push("hosts").await.unwrap();
+    assert_eq!(path.as_bytes(), b"/etc/hosts");
+
+    path.set_extension("bak").await.unwrap();
+    assert_eq!(path.as_bytes(), b"/etc/hosts.bak");
+    path.set_file_name("passwd").await.unwrap();
+    assert_eq!(path.as_bytes(), b"/etc/passwd");
+
+    assert!(path.pop());
+    assert!(path.pop());
+    assert!(!path.pop());
+    assert_eq!(path.to_c_str().await.unwrap(), c"/");
+  }
+
+  #[pollster::test]
+  async fn normalize() {
+    let allocator = ForeignAllocator::new(StdAlloc);
+    for (path, normalized) in [
+      ("/a//b/./c/../d/", "/a/b/d"),
+      ("/../a", "/a"),
+      ("a/../..", ".."),
+      ("../a/b/../../c", "../c"),
+      ("a/..", "."),
+      ("./a", "a"),
+    ] {
+      let mut path =
+        PathBuf::<UniqueStrategy, _>::from_path(path, &allocator, GrowthStrategy::Exponential)
+          .await
+          .unwrap();
+      path.normalize().await.unwrap();
+      assert_eq!(path.as_bytes(), normalized.as_bytes());
+    }
+  }
+}
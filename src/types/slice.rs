@@ -0,0 +1,265 @@
+use core::{cmp::Ordering, mem::MaybeUninit, ops::Range, ptr};
+
+use crate::alloc::{SliceAllocator, strategy::UniqueStrategy};
+
+/// Runs at most this long are sorted by insertion instead of being split further.
+const INSERTION_THRESHOLD: usize = 20;
+
+/// The length of the scratch buffer [stable_sort_by] needs to sort `length` values.
+pub const fn scratch_len(length: usize) -> usize {
+  length / 2
+}
+
+/// Sorts `data` with a stable merge sort, using `scratch` as temporary storage.
+///
+/// A [FixedVec](crate::types::vec::FixedVec) can provide the scratch through
+/// `spare_capacity_mut`.
+///
+/// Panics if `scratch` is shorter than [scratch_len] of `data.len()`.
+pub fn stable_sort_by<T, F: FnMut(&T, &T) -> Ordering>(
+  data: &mut [T],
+  scratch: &mut [MaybeUninit<T>],
+  mut compare: F,
+) {
+  let needed = scratch_len(data.len());
+  let length = scratch.len();
+  assert!(
+    length >= needed,
+    "scratch of length {length} is too short, needs {needed}"
+  );
+
+  merge_sort(data, scratch, &mut compare);
+}
+
+pub fn stable_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(
+  data: &mut [T],
+  scratch: &mut [MaybeUninit<T>],
+  mut key: F,
+) {
+  stable_sort_by(data, scratch, |left, right| key(left).cmp(&key(right)));
+}
+
+pub fn stable_sort<T: Ord>(data: &mut [T], scratch: &mut [MaybeUninit<T>]) {
+  stable_sort_by(data, scratch, T::cmp);
+}
+
+/// Sorts `data` with a stable merge sort, reserving the scratch buffer from `allocator`.
+pub async fn stable_sort_by_in<'a, T: 'a, A: SliceAllocator<'a, [T]>, F>(
+  data: &mut [T],
+  allocator: &'a A,
+  compare: F,
+) -> Result<(), A::Error>
+where
+  F: FnMut(&T, &T) -> Ordering,
+{
+  let length = scratch_len(data.len());
+  let scratch = allocator.reserve_slice::<UniqueStrategy>(length).await?;
+
+  // Safety: the allocation holds `length` uninitialized values, and isn't used elsewhere
+  let scratch = unsafe {
+    let ptr = StrategyHandle::as_value_ptr(&scratch);
+    core::slice::from_raw_parts_mut(ptr.cast::<MaybeUninit<T>>(), length)
+  };
+  stable_sort_by(data, scratch, compare);
+
+  Ok(())
+}
+
+pub async fn stable_sort_in<'a, T: Ord + 'a, A: SliceAllocator<'a, [T]>>(
+  data: &mut [T],
+  allocator: &'a A,
+) -> Result<(), A::Error> {
+  stable_sort_by_in(data, allocator, T::cmp).await
+}
+
+fn merge_sort<T, F: FnMut(&T, &T) -> Ordering>(
+  data: &mut [T],
+  scratch: &mut [MaybeUninit<T>],
+  compare: &mut F,
+) {
+  let length = data.len();
+  if length <= INSERTION_THRESHOLD {
+    insertion_sort(data, compare);
+    return;
+  }
+
+  let middle = length / 2;
+  merge_sort(&mut data[..middle], scratch, compare);
+  merge_sort(&mut data[middle..], scratch, compare);
+
+  // the runs are already in order
+  if compare(&data[middle], &data[middle - 1]) != Ordering::Less {
+    return;
+  }
+
+  merge(data, middle, scratch, compare);
+}
+
+fn insertion_sort<T, F: FnMut(&T, &T) -> Ordering>(data: &mut [T], compare: &mut F) {
+  for index in 1..data.len() {
+    let mut current = index;
+    while current > 0 && compare(&data[current], &data[current - 1]) == Ordering::Less {
+      data.swap(current, current - 1);
+      current -= 1;
+    }
+  }
+}
+
+/// The part of the left run that hasn't been merged yet, which is moved into the gap before the
+/// right run when dropped, even if `compare` panics.
+struct MergeHole<T> {
+  start: *mut T,
+  end: *mut T,
+  dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+  fn drop(&mut self) {
+    unsafe {
+      // Safety: the gap is exactly as long as the rest of the left run
+      let length = self.end.offset_from_unsigned(self.start);
+      ptr::copy_nonoverlapping(self.start, self.dest, length);
+    }
+  }
+}
+
+/// Merges the sorted runs `data[..middle]` and `data[middle..]`, moving the left run into
+/// `scratch` first.
+fn merge<T, F: FnMut(&T, &T) -> Ordering>(
+  data: &mut [T],
+  middle: usize,
+  scratch: &mut [MaybeUninit<T>],
+  compare: &mut F,
+) {
+  let length = data.len();
+  let data = data.as_mut_ptr();
+  let scratch = scratch.as_mut_ptr().cast::<T>();
+
+  unsafe {
+    // Safety: scratch has room for the left run, since middle <= scratch_len(length)
+    ptr::copy_nonoverlapping(data, scratch, middle);
+
+    let mut hole = MergeHole {
+      start: scratch,
+      end: scratch.add(middle),
+      dest: data,
+    };
+    let mut right = data.add(middle);
+    let end = data.add(length);
+
+    while hole.start < hole.end && right < end {
+      // taking from the left run on ties keeps the sort stable
+      let next = if compare(&*right, &*hole.start) == Ordering::Less {
+        right = right.add(1);
+        right.sub(1)
+      } else {
+        hole.start = hole.start.add(1);
+        hole.start.sub(1)
+      };
+
+      // Safety: dest is behind right while the left run isn't empty, so they can't overlap
+      ptr::copy_nonoverlapping(next, hole.dest, 1);
+      hole.dest = hole.dest.add(1);
+    }
+  }
+}
+
+/// Reorders `data` so the value at `index` is where it would be if sorted, and returns it.
+///
+/// Panics if `index >= data.len()`.
+pub fn select_nth_by<T, F: FnMut(&T, &T) -> Ordering>(
+  data: &mut [T],
+  index: usize,
+  compare: F,
+) -> &mut T {
+  data.select_nth_unstable_by(index, compare).1
+}
+
+/// Panics if `index >= data.len()`.
+pub fn select_nth<T: Ord>(data: &mut [T], index: usize) -> &mut T {
+  select_nth_by(data, index, T::cmp)
+}
+
+/// Finds the index of the first value in the sorted `data` whose key isn't less than `key`.
+pub fn lower_bound_by_key<T, K: Ord, F: FnMut(&T) -> K>(data: &[T], key: &K, mut f: F) -> usize {
+  data.partition_point(|value| f(value) < *key)
+}
+
+/// Finds the index of the first value in the sorted `data` whose key is greater than `key`.
+pub fn upper_bound_by_key<T, K: Ord, F: FnMut(&T) -> K>(data: &[T], key: &K, mut f: F) -> usize {
+  data.partition_point(|value| f(value) <= *key)
+}
+
+/// Finds the range of values in the sorted `data` whose key is equal to `key`.
+pub fn equal_range_by_key<T, K: Ord, F: FnMut(&T) -> K>(
+  data: &[T],
+  key: &K,
+  mut f: F,
+) -> Range<usize> {
+  let start = lower_bound_by_key(data, key, &mut f);
+  let end = start + upper_bound_by_key(&data[start..], key, f);
+  start..end
+}
+
+/// Moves the first value of every run that `same` considers equal to the front, and returns how
+/// many there are. The duplicates are left after them in an unspecified order.
+///
+/// `same` is passed the current value and the last value that was kept.
+pub fn partition_dedup_by<T, F: FnMut(&mut T, &mut T) -> bool>(
+  data: &mut [T],
+  mut same: F,
+) -> usize {
+  if data.is_empty() {
+    return 0;
+  }
+
+  let mut kept = 1;
+  for index in 1..data.len() {
+    let (front, back) = data.split_at_mut(index);
+    if !same(&mut back[0], &mut front[kept - 1]) {
+      data.swap(index, kept);
+      kept += 1;
+    }
+  }
+
+  kept
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::types::{slice, vec::FixedVec};
+
+  #[test]
+  fn stable_sort_with_fixed_vec() {
+    let mut data: [(u32, usize); 64] =
+      core::array::from_fn(|index| ((index * 7 % 5) as u32, index));
+    let mut scratch = FixedVec::<(u32, usize), 32>::new();
+    slice::stable_sort_by_key(&mut data, scratch.spare_capacity_mut(), |&(key, _)| key);
+
+    assert!(data.is_sorted());
+    assert_eq!(
+      slice::equal_range_by_key(&data, &2, |&(key, _)| key),
+      26..39
+    );
+    assert_eq!(*slice::select_nth(&mut data, 40), (3, 9));
+
+    let mut values = FixedVec::<u32, 8>::new();
+    for value in [1, 1, 2, 3, 3, 3, 1] {
+      values.push(value).unwrap();
+    }
+    values.dedup();
+    assert_eq!(&*values, &[1, 2, 3, 1]);
+  }
+
+  #[cfg(feature = "libc")]
+  #[pollster::test]
+  async fn stable_sort_in_allocator() {
+    use crate::alloc::{ForeignAllocator, StdAlloc};
+
+    let allocator = ForeignAllocator::new(StdAlloc);
+    let mut data: [u32; 100] = core::array::from_fn(|index| (index as u32 * 37) % 100);
+    slice::stable_sort_in(&mut data, &allocator).await.unwrap();
+
+    assert!(data.iter().copied().eq(0..100));
+  }
+}
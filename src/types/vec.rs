@@ -3,12 +3,14 @@ use core::{
   marker::PhantomData,
   mem::MaybeUninit,
   ops::{Deref, DerefMut},
+  ptr,
 };
 
-use aubystd_macros::slice_dst;
+use zerocopy::{Immutable, IntoBytes};
 
-#[slice_dst(header = BaseVecHeader)]
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use crate::types::slice;
+
+#[derive(SliceDst, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
 pub struct BaseVec<T, V: AsRef<[MaybeUninit<T>]> + ?Sized> {
   phantom: PhantomData<T>,
@@ -53,13 +55,15 @@ impl<T, V: AsRef<[MaybeUninit<T>]> + ?Sized> Deref for BaseVec<T, V> {
   type Target = [T];
 
   fn deref(&self) -> &[T] {
-    unsafe { core::mem::transmute(self.values.as_ref()) }
+    // Safety: the first `length` values are always initialized
+    unsafe { core::mem::transmute(&self.values.as_ref()[..self.length]) }
   }
 }
 
 impl<T, V: AsRef<[MaybeUninit<T>]> + AsMut<[MaybeUninit<T>]> + ?Sized> DerefMut for BaseVec<T, V> {
   fn deref_mut(&mut self) -> &mut [T] {
-    unsafe { core::mem::transmute(self.values.as_mut()) }
+    // Safety: the first `length` values are always initialized
+    unsafe { core::mem::transmute(&mut self.values.as_mut()[..self.length]) }
   }
 }
 
@@ -104,7 +108,15 @@ impl<T, V: AsRef<[MaybeUninit<T>]> + ?Sized> BaseVec<T, V> {
   }
 
   pub fn is_empty(&self) -> bool {
-    !self.is_full()
+    self.length == 0
+  }
+
+  /// Returns the bytes of the initialized values.
+  pub fn as_bytes(&self) -> &[u8]
+  where
+    T: IntoBytes + Immutable,
+  {
+    (**self).as_bytes()
   }
 }
 
@@ -133,4 +145,85 @@ impl<T, V: AsRef<[MaybeUninit<T>]> + AsMut<[MaybeUninit<T>]> + ?Sized> BaseVec<T
 
     Some(value)
   }
+
+  /// Inserts `value` at `index`, shifting every element after it to the right.
+  /// Returns the value back if the vec is full.
+  ///
+  /// Panics if `index > len`.
+  pub fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+    let length = self.len();
+    assert!(index <= length, "insertion index {index} out of bounds for length {length}");
+    if self.is_full() {
+      return Err(value);
+    }
+
+    let values = self.values.as_mut().as_mut_ptr();
+    unsafe {
+      // Safety: index + 1 + (length - index) <= capacity, since the vec isn't full
+      ptr::copy(values.add(index), values.add(index + 1), length - index);
+      values.add(index).write(MaybeUninit::new(value));
+    }
+    self.length += 1;
+
+    Ok(())
+  }
+
+  /// Removes the element at `index`, shifting every element after it to the left.
+  ///
+  /// Panics if `index >= len`.
+  pub fn remove(&mut self, index: usize) -> T {
+    let length = self.len();
+    assert!(index < length, "removal index {index} out of bounds for length {length}");
+
+    let values = self.values.as_mut().as_mut_ptr();
+    unsafe {
+      // Safety: known to be initialized, since index < length
+      let value = values.add(index).read().assume_init();
+      ptr::copy(values.add(index + 1), values.add(index), length - index - 1);
+      self.length -= 1;
+      value
+    }
+  }
+
+  /// Drops every element after the first `length` elements.
+  pub fn truncate(&mut self, length: usize) {
+    while self.len() > length {
+      self.pop();
+    }
+  }
+
+  pub fn clear(&mut self) {
+    self.truncate(0);
+  }
+
+  /// Safety: the first `length` values must be initialized, and `length` can't exceed the capacity
+  pub unsafe fn set_len(&mut self, length: usize) {
+    self.length = length;
+  }
+
+  /// Returns the uninitialized values after the first `len` values.
+  pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+    let length = self.length;
+    &mut self.values.as_mut()[length..]
+  }
+
+  /// Removes every value after the first of a run that `same` considers equal, keeping the
+  /// remaining values in order.
+  ///
+  /// `same` is passed the current value and the last value that was kept.
+  pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same: F) {
+    let length = slice::partition_dedup_by(self, same);
+    self.truncate(length);
+  }
+
+  pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+    self.dedup_by(|current, kept| key(current) == key(kept));
+  }
+
+  pub fn dedup(&mut self)
+  where
+    T: PartialEq,
+  {
+    self.dedup_by(|current, kept| current == kept);
+  }
 }
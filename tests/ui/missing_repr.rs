@@ -0,0 +1,10 @@
+#![no_std]
+#![no_main]
+
+use aubystd::alloc::SliceDst;
+
+#[derive(SliceDst)]
+struct Packet {
+  length: u32,
+  payload: [u8],
+}
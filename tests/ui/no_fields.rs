@@ -0,0 +1,12 @@
+#![no_std]
+#![no_main]
+
+use aubystd::alloc::SliceDst;
+
+#[derive(SliceDst)]
+#[repr(C)]
+struct Empty {}
+
+#[derive(SliceDst)]
+#[repr(C)]
+struct Unit;
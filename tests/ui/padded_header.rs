@@ -0,0 +1,13 @@
+#![no_std]
+#![no_main]
+
+use aubystd::alloc::SliceDst;
+
+// the header is padded to 16 bytes, but the payload starts at 9
+#[derive(SliceDst)]
+#[repr(C)]
+struct Packet {
+  length: u64,
+  kind: u8,
+  payload: [u8],
+}
@@ -0,0 +1,11 @@
+#![no_std]
+#![no_main]
+
+use aubystd::alloc::SliceDst;
+
+#[derive(SliceDst)]
+#[repr(C)]
+struct Packet {
+  length: u32,
+  payload: u64,
+}
@@ -0,0 +1,11 @@
+#![no_std]
+#![no_main]
+
+use aubystd::alloc::SliceDst;
+
+#[derive(SliceDst)]
+#[repr(C)]
+union Value {
+  int: u32,
+  float: f32,
+}
@@ -0,0 +1,12 @@
+#![no_std]
+#![no_main]
+
+use aubystd::alloc::SliceDst;
+
+#[derive(SliceDst)]
+#[slice_dst(name = PacketPrefix)]
+#[repr(C)]
+struct Packet {
+  length: u32,
+  payload: [u8],
+}